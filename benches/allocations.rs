@@ -0,0 +1,70 @@
+//! Compares the number of heap allocations made while reading a RIB dump with and without
+//! a capacity hint for the internal record buffer. The fixtures are TABLE_DUMP_V2 RIB dumps,
+//! the format of the bview files published by route collectors. The hint is pre-reserved to the
+//! size of the largest record in the dump. Run with `cargo bench --bench allocations`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use mrt_rs::Reader;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Reads all records from the given bytes and returns the number of allocations made.
+fn count_allocations(bytes: &[u8], capacity_hint: Option<usize>) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+
+    let mut reader = Reader::new(bytes);
+    if let Some(hint) = capacity_hint {
+        reader = reader.with_capacity_hint(hint);
+    }
+    while let Some(record) = reader.read().unwrap() {
+        drop(record);
+    }
+
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/// Returns the length of the largest record in the given bytes.
+fn largest_record(bytes: &[u8]) -> usize {
+    let mut reader = Reader::new(std::io::Cursor::new(bytes));
+    let headers = reader.headers().unwrap();
+    headers
+        .iter()
+        .map(|(_, header)| header.length as usize)
+        .max()
+        .unwrap_or(0)
+}
+
+fn main() {
+    for fixture in &["res/quagga_rib", "res/openbgpd_rib_table-v2"] {
+        let bytes = std::fs::read(fixture).unwrap();
+        let without_hint = count_allocations(&bytes, None);
+        let with_hint = count_allocations(&bytes, Some(largest_record(&bytes)));
+        println!(
+            "{}: {} allocations without a capacity hint, {} with a capacity hint",
+            fixture, without_hint, with_hint
+        );
+    }
+}
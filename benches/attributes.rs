@@ -0,0 +1,57 @@
+//! Compares decoding all path attributes of every route with lazily decoding only the AS_PATH.
+//! Run with `cargo bench --bench attributes`.
+
+use std::time::{Duration, Instant};
+
+use mrt_rs::attributes::{self, LazyAttributes, PathAttribute};
+use mrt_rs::{AsnSize, ParseOptions, Reader};
+
+/// The number of times the attributes of all routes are decoded.
+const REPETITIONS: usize = 2_000;
+
+/// Decodes the attributes of all routes using the given function and returns the elapsed time.
+fn measure<T, F>(routes: &[T], mut decode: F) -> Duration
+where
+    F: FnMut(&T) -> usize,
+{
+    let start = Instant::now();
+    let mut found = 0;
+    for _ in 0..REPETITIONS {
+        for route in routes {
+            found += decode(route);
+        }
+    }
+    assert_eq!(found, routes.len() * REPETITIONS);
+    start.elapsed()
+}
+
+fn main() {
+    for fixture in &["res/quagga_rib", "res/openbgpd_rib_table-v2"] {
+        let mut reader = Reader::new(std::fs::File::open(fixture).unwrap());
+        let routes: Vec<Vec<u8>> = reader.routes().map(|x| x.unwrap().attributes).collect();
+
+        // Both paths decode from attributes that are already owned, so neither measures a copy.
+        let wrapped: Vec<LazyAttributes> = routes
+            .iter()
+            .map(|x| LazyAttributes::new(x.clone(), AsnSize::Four))
+            .collect();
+
+        let eager = measure(&routes, |bytes| {
+            let attributes =
+                attributes::parse(bytes, AsnSize::Four, &ParseOptions::default()).unwrap();
+            attributes
+                .iter()
+                .filter(|x| matches!(x, PathAttribute::AS_PATH(_)))
+                .count()
+        });
+        let lazy = measure(&wrapped, |attributes| {
+            attributes.get(2).unwrap().iter().count()
+        });
+        println!(
+            "{}: eager {:.1} ms, lazy {:.1} ms",
+            fixture,
+            eager.as_secs_f64() * 1000.0,
+            lazy.as_secs_f64() * 1000.0
+        );
+    }
+}
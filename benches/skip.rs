@@ -0,0 +1,50 @@
+//! Compares skipping rejected records by seeking over their bodies using `Reader::drive_seek`
+//! with reading and discarding them using `Reader::drive`. Run with `cargo bench --bench skip`.
+
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+
+use mrt_rs::{Header, Reader, RecordVisitor};
+
+/// The number of times the fixture is repeated to obtain a measurable amount of data.
+const REPETITIONS: usize = 2_000;
+
+/// Rejects all records except the PEER_INDEX_TABLE.
+struct PeerIndexTableOnly;
+
+impl RecordVisitor for PeerIndexTableOnly {
+    fn visit_header(&mut self, header: &Header) -> bool {
+        header.record_type == 13 && header.sub_type == 1
+    }
+}
+
+/// Drives a visitor over all records using the given drive function and returns the elapsed time.
+fn measure<F>(bytes: &[u8], mut drive: F) -> Duration
+where
+    F: FnMut(&mut Reader<Cursor<&[u8]>>),
+{
+    let mut reader = Reader::new(Cursor::new(bytes));
+    let start = Instant::now();
+    drive(&mut reader);
+    start.elapsed()
+}
+
+fn main() {
+    for fixture in &["res/quagga_rib", "res/openbgpd_rib_table-v2"] {
+        let bytes = std::fs::read(fixture).unwrap().repeat(REPETITIONS);
+        let megabytes = bytes.len() as f64 / (1024.0 * 1024.0);
+
+        let discard = measure(&bytes, |reader| {
+            reader.drive(&mut PeerIndexTableOnly).unwrap()
+        });
+        let seek = measure(&bytes, |reader| {
+            reader.drive_seek(&mut PeerIndexTableOnly).unwrap()
+        });
+        println!(
+            "{}: drive {:.1} MiB/s, drive_seek {:.1} MiB/s",
+            fixture,
+            megabytes / discard.as_secs_f64(),
+            megabytes / seek.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! Compares the throughput of `Reader::read` with `Reader::read_buffered` on a RIB dump that is
+//! read through a `BufReader`. Run with `cargo bench --bench throughput`.
+
+use std::io::BufReader;
+use std::time::{Duration, Instant};
+
+use mrt_rs::Reader;
+
+/// The number of times the fixture is repeated to obtain a measurable amount of data.
+const REPETITIONS: usize = 2_000;
+
+/// Reads all records using the given read function and returns the elapsed time.
+fn measure<F>(bytes: &[u8], mut read: F) -> Duration
+where
+    F: FnMut(&mut Reader<BufReader<&[u8]>>) -> bool,
+{
+    let mut reader = Reader::new(BufReader::with_capacity(64 * 1024, bytes));
+    let start = Instant::now();
+    while read(&mut reader) {}
+    start.elapsed()
+}
+
+fn main() {
+    for fixture in &[
+        "res/quagga_rib",
+        "res/openbgpd_rib_table-v2",
+        "res/bird-mrtdump_bgp",
+    ] {
+        let bytes = std::fs::read(fixture).unwrap().repeat(REPETITIONS);
+        let megabytes = bytes.len() as f64 / (1024.0 * 1024.0);
+
+        let read = measure(&bytes, |reader| reader.read().unwrap().is_some());
+        let buffered = measure(&bytes, |reader| reader.read_buffered().unwrap().is_some());
+        println!(
+            "{}: read {:.1} MiB/s, read_buffered {:.1} MiB/s",
+            fixture,
+            megabytes / read.as_secs_f64(),
+            megabytes / buffered.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::io::Read;
+
+use crate::tabledump::{PeerEntry, TABLE_DUMP_V2};
+use crate::{MrtError, Reader, Record};
+
+/// The number of prefixes each peer of a TABLE_DUMP_V2 RIB dump contributed.
+#[derive(Debug, Default)]
+pub struct PeerDistribution {
+    /// The peers of the PEER_INDEX_TABLE in index order, together with their number of prefixes.
+    pub peers: Vec<(PeerEntry, usize)>,
+
+    /// The number of RIB entries that referenced a peer index not present in the PEER_INDEX_TABLE.
+    pub unknown: usize,
+}
+
+impl PeerDistribution {
+    /// Returns the total number of RIB entries that have been counted.
+    pub fn total(&self) -> usize {
+        self.peers.iter().map(|(_, count)| count).sum::<usize>() + self.unknown
+    }
+}
+
+///
+/// # Summary
+/// Reads all remaining records of a TABLE_DUMP_V2 stream and counts the number of RIB entries
+/// (prefixes) contributed by each peer. Records of other types are ignored. If the stream
+/// contains multiple PEER_INDEX_TABLE records, entries are resolved against the last one.
+/// A stream without RIB entries, such as a dump of a collector without peers, results in a
+/// count of zero for every peer of the table.
+///
+/// # Panics
+/// This function does not panic.
+///
+/// # Errors
+/// Any error encountered while reading records from the stream is returned.
+///
+/// # Safety
+/// This function does not make use of unsafe code.
+///
+pub fn peer_distribution<T: Read>(reader: &mut Reader<T>) -> Result<PeerDistribution, MrtError> {
+    let mut peers = Vec::new();
+    let mut counts: Vec<usize> = Vec::new();
+
+    let mut count = |peer_index: u16| {
+        let index = peer_index as usize;
+        if index >= counts.len() {
+            counts.resize(index + 1, 0);
+        }
+        counts[index] += 1;
+    };
+
+    while let Some((_, record)) = reader.read()? {
+        let record = match record {
+            Record::TABLE_DUMP_V2(x) => x,
+            _ => continue,
+        };
+
+        match record {
+            TABLE_DUMP_V2::PEER_INDEX_TABLE(x) => peers = x.peer_entries,
+            TABLE_DUMP_V2::RIB_IPV4_UNICAST(x)
+            | TABLE_DUMP_V2::RIB_IPV4_MULTICAST(x)
+            | TABLE_DUMP_V2::RIB_IPV6_UNICAST(x)
+            | TABLE_DUMP_V2::RIB_IPV6_MULTICAST(x) => {
+                x.entries.iter().for_each(|e| count(e.peer_index))
+            }
+            TABLE_DUMP_V2::RIB_GENERIC(x) => x.entries.iter().for_each(|e| count(e.peer_index)),
+            TABLE_DUMP_V2::RIB_IPV4_UNICAST_ADDPATH(x)
+            | TABLE_DUMP_V2::RIB_IPV4_MULTICAST_ADDPATH(x)
+            | TABLE_DUMP_V2::RIB_IPV6_UNICAST_ADDPATH(x)
+            | TABLE_DUMP_V2::RIB_IPV6_MULTICAST_ADDPATH(x) => {
+                x.entries.iter().for_each(|e| count(e.peer_index))
+            }
+            TABLE_DUMP_V2::RIB_GENERIC_ADDPATH(x) => {
+                x.entries.iter().for_each(|e| count(e.peer_index))
+            }
+        }
+    }
+
+    // Entries referencing an index beyond the PEER_INDEX_TABLE end up in the unknown bucket.
+    let unknown = counts.iter().skip(peers.len()).sum();
+    counts.resize(peers.len(), 0);
+    Ok(PeerDistribution {
+        peers: peers.into_iter().zip(counts).collect(),
+        unknown,
+    })
+}
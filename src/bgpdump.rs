@@ -0,0 +1,164 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::attributes::{reconstruct_as_path, Origin, PathAttribute, AS_TRANS};
+
+///
+/// A route in a RIB dump that is formatted as a single line of the machine-readable output of
+/// `bgpdump -m`, such that it can replace `bgpdump` in existing pipelines:
+///
+/// `TABLE_DUMP2|time|B|peer_ip|peer_as|prefix|as_path|origin|next_hop|local_pref|med|communities|atomic_aggregate|aggregator|`
+///
+/// Attributes that are absent are left empty, except for LOCAL_PREF and MULTI_EXIT_DISC which
+/// are written as zero. The line does not end with a newline.
+///
+/// # Examples
+/// ```
+/// use mrt_rs::BgpdumpRoute;
+/// use mrt_rs::attributes::{Origin, PathAttribute};
+///
+/// let attributes = [
+///     PathAttribute::ORIGIN(Origin::IGP),
+///     PathAttribute::NEXT_HOP("192.0.2.1".parse().unwrap()),
+/// ];
+/// let route = BgpdumpRoute {
+///     timestamp: 1486802400,
+///     peer_address: "192.0.2.1".parse().unwrap(),
+///     peer_as: 65000,
+///     prefix: "10.0.0.0".parse().unwrap(),
+///     prefix_length: 8,
+///     attributes: &attributes,
+/// };
+///
+/// assert_eq!(
+///     route.to_string(),
+///     "TABLE_DUMP2|1486802400|B|192.0.2.1|65000|10.0.0.0/8||IGP|192.0.2.1|0|0||NAG||"
+/// );
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct BgpdumpRoute<'a> {
+    /// The time at which the route was dumped.
+    pub timestamp: u32,
+
+    /// The IP address of the peer from which the route was received.
+    pub peer_address: IpAddr,
+
+    /// The ASN of the peer from which the route was received.
+    pub peer_as: u32,
+
+    /// The address of the prefix.
+    pub prefix: IpAddr,
+
+    /// The length of the prefix in bits.
+    pub prefix_length: u8,
+
+    /// The path attributes of the route.
+    pub attributes: &'a [PathAttribute],
+}
+
+impl fmt::Display for BgpdumpRoute<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut as_path = None;
+        let mut as4_path = None;
+        let mut origin = "";
+        let mut next_hop = None;
+        let mut local_pref = 0;
+        let mut med = 0;
+        let mut communities = &[][..];
+        let mut atomic_aggregate = "NAG";
+        let mut aggregator = None;
+        let mut as4_aggregator = None;
+
+        for attribute in self.attributes {
+            match attribute {
+                PathAttribute::ORIGIN(x) => {
+                    origin = match x {
+                        Origin::IGP => "IGP",
+                        Origin::EGP => "EGP",
+                        Origin::INCOMPLETE => "INCOMPLETE",
+                    }
+                }
+                PathAttribute::AS_PATH(x) => as_path = Some(x),
+                PathAttribute::AS4_PATH(x) => as4_path = Some(x),
+                PathAttribute::NEXT_HOP(x) => next_hop = Some(*x),
+                PathAttribute::MP_REACH_NLRI(x) if next_hop.is_none() => {
+                    next_hop = mp_next_hop(x);
+                }
+                PathAttribute::LOCAL_PREF(x) => local_pref = *x,
+                PathAttribute::MULTI_EXIT_DISC(x) => med = *x,
+                PathAttribute::COMMUNITY(x) => communities = x,
+                PathAttribute::ATOMIC_AGGREGATE => atomic_aggregate = "AG",
+                PathAttribute::AGGREGATOR(x) => aggregator = Some(x),
+                PathAttribute::AS4_AGGREGATOR(x) => as4_aggregator = Some(x),
+                _ => (),
+            }
+        }
+
+        write!(
+            f,
+            "TABLE_DUMP2|{}|B|{}|{}|{}/{}|",
+            self.timestamp, self.peer_address, self.peer_as, self.prefix, self.prefix_length
+        )?;
+
+        match (as_path, as4_path) {
+            (Some(x), Some(y)) => write!(f, "{}", reconstruct_as_path(x, y))?,
+            (Some(x), None) => write!(f, "{}", x)?,
+            _ => (),
+        }
+
+        write!(f, "|{}|", origin)?;
+        if let Some(x) = next_hop {
+            write!(f, "{}", x)?;
+        }
+        write!(f, "|{}|{}|", local_pref, med)?;
+
+        for (i, community) in communities.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}:{}", community >> 16, community & 0xFFFF)?;
+        }
+        write!(f, "|{}|", atomic_aggregate)?;
+
+        // The AS4_AGGREGATOR is only relevant if the AGGREGATOR contains AS_TRANS.
+        let aggregator = match (aggregator, as4_aggregator) {
+            (Some(x), Some(y)) if x.asn == AS_TRANS => Some(y),
+            (x, _) => x,
+        };
+        if let Some(x) = aggregator {
+            write!(f, "{} {}", x.asn, x.ip)?;
+        }
+        write!(f, "|")
+    }
+}
+
+///
+/// Extracts the (global) next hop from the value of an MP_REACH_NLRI attribute. RIB dumps
+/// should contain the abbreviated form consisting of only the next hop length and address,
+/// but some implementations store the full attribute.
+/// More information can found in [RFC6396](https://tools.ietf.org/html/rfc6396#section-4.3.4).
+///
+fn mp_next_hop(value: &[u8]) -> Option<IpAddr> {
+    let next_hop = match value.first() {
+        Some(&length) if value.len() == 1 + length as usize => &value[1..],
+        _ => {
+            let length = *value.get(3)? as usize;
+            value.get(4..4 + length)?
+        }
+    };
+
+    match next_hop.len() {
+        4 => {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(next_hop);
+            Some(IpAddr::V4(Ipv4Addr::from(bytes)))
+        }
+        16 | 32 => {
+            let mut bytes = [0; 16];
+            bytes.copy_from_slice(&next_hop[..16]);
+            Some(IpAddr::V6(Ipv6Addr::from(bytes)))
+        }
+        _ => None,
+    }
+}
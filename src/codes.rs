@@ -0,0 +1,122 @@
+/// The record type of the deprecated NULL record.
+pub const NULL: u16 = 0;
+/// The record type of the deprecated START record.
+pub const START: u16 = 1;
+/// The record type of the deprecated DIE record.
+pub const DIE: u16 = 2;
+/// The record type of the deprecated I_AM_DEAD record.
+pub const I_AM_DEAD: u16 = 3;
+/// The record type of the deprecated PEER_DOWN record.
+pub const PEER_DOWN: u16 = 4;
+/// The record type of the deprecated BGP record.
+pub const BGP: u16 = 5;
+/// The record type of the deprecated RIP record.
+pub const RIP: u16 = 6;
+/// The record type of the deprecated IDRP record.
+pub const IDRP: u16 = 7;
+/// The record type of the deprecated RIPNG record.
+pub const RIPNG: u16 = 8;
+/// The record type of the deprecated BGP4PLUS record.
+pub const BGP4PLUS: u16 = 9;
+/// The record type of the deprecated BGP4PLUS_01 record.
+pub const BGP4PLUS_01: u16 = 10;
+/// The record type of the OSPFv2 record.
+pub const OSPFV2: u16 = 11;
+/// The record type of the TABLE_DUMP record.
+pub const TABLE_DUMP: u16 = 12;
+/// The record type of the TABLE_DUMP_V2 record.
+pub const TABLE_DUMP_V2: u16 = 13;
+/// The record type of the BGP4MP record.
+pub const BGP4MP: u16 = 16;
+/// The record type of the BGP4MP_ET record, a BGP4MP record with an extended timestamp.
+pub const BGP4MP_ET: u16 = 17;
+/// The record type of the ISIS record.
+pub const ISIS: u16 = 32;
+/// The record type of the ISIS_ET record, an ISIS record with an extended timestamp.
+pub const ISIS_ET: u16 = 33;
+/// The record type of the OSPFv3 record.
+pub const OSPFV3: u16 = 48;
+/// The record type of the OSPFv3_ET record, an OSPFv3 record with an extended timestamp.
+pub const OSPFV3_ET: u16 = 49;
+
+/// The subtypes of the deprecated BGP, BGP4PLUS and BGP4PLUS_01 record types.
+pub mod bgp {
+    /// The subtype of the NULL message.
+    pub const NULL: u16 = 0;
+    /// The subtype of the UPDATE message.
+    pub const UPDATE: u16 = 1;
+    /// The subtype of the PREF_UPDATE message.
+    pub const PREF_UPDATE: u16 = 2;
+    /// The subtype of the STATE_CHANGE message.
+    pub const STATE_CHANGE: u16 = 3;
+    /// The subtype of the SYNC message.
+    pub const SYNC: u16 = 4;
+    /// The subtype of the OPEN message.
+    pub const OPEN: u16 = 5;
+    /// The subtype of the NOTIFY message.
+    pub const NOTIFY: u16 = 6;
+    /// The subtype of the KEEPALIVE message.
+    pub const KEEPALIVE: u16 = 7;
+}
+
+/// The subtypes of the TABLE_DUMP record type.
+pub mod table_dump {
+    /// The subtype of a RIB entry of an IPv4 prefix.
+    pub const AFI_IPV4: u16 = 1;
+    /// The subtype of a RIB entry of an IPv6 prefix.
+    pub const AFI_IPV6: u16 = 2;
+}
+
+/// The subtypes of the TABLE_DUMP_V2 record type.
+pub mod table_dump_v2 {
+    /// The subtype of the PEER_INDEX_TABLE.
+    pub const PEER_INDEX_TABLE: u16 = 1;
+    /// The subtype of the RIB_IPV4_UNICAST entries.
+    pub const RIB_IPV4_UNICAST: u16 = 2;
+    /// The subtype of the RIB_IPV4_MULTICAST entries.
+    pub const RIB_IPV4_MULTICAST: u16 = 3;
+    /// The subtype of the RIB_IPV6_UNICAST entries.
+    pub const RIB_IPV6_UNICAST: u16 = 4;
+    /// The subtype of the RIB_IPV6_MULTICAST entries.
+    pub const RIB_IPV6_MULTICAST: u16 = 5;
+    /// The subtype of the RIB_GENERIC entries.
+    pub const RIB_GENERIC: u16 = 6;
+    /// The subtype of the RIB_IPV4_UNICAST_ADDPATH entries.
+    pub const RIB_IPV4_UNICAST_ADDPATH: u16 = 8;
+    /// The subtype of the RIB_IPV4_MULTICAST_ADDPATH entries.
+    pub const RIB_IPV4_MULTICAST_ADDPATH: u16 = 9;
+    /// The subtype of the RIB_IPV6_UNICAST_ADDPATH entries.
+    pub const RIB_IPV6_UNICAST_ADDPATH: u16 = 10;
+    /// The subtype of the RIB_IPV6_MULTICAST_ADDPATH entries.
+    pub const RIB_IPV6_MULTICAST_ADDPATH: u16 = 11;
+    /// The subtype of the RIB_GENERIC_ADDPATH entries.
+    pub const RIB_GENERIC_ADDPATH: u16 = 12;
+}
+
+/// The subtypes of the BGP4MP and BGP4MP_ET record types.
+pub mod bgp4mp {
+    /// The subtype of the STATE_CHANGE message.
+    pub const STATE_CHANGE: u16 = 0;
+    /// The subtype of the MESSAGE message.
+    pub const MESSAGE: u16 = 1;
+    /// The subtype of the deprecated ENTRY message.
+    pub const ENTRY: u16 = 2;
+    /// The subtype of the deprecated SNAPSHOT message.
+    pub const SNAPSHOT: u16 = 3;
+    /// The subtype of the MESSAGE_AS4 message.
+    pub const MESSAGE_AS4: u16 = 4;
+    /// The subtype of the STATE_CHANGE_AS4 message.
+    pub const STATE_CHANGE_AS4: u16 = 5;
+    /// The subtype of the MESSAGE_LOCAL message.
+    pub const MESSAGE_LOCAL: u16 = 6;
+    /// The subtype of the MESSAGE_AS4_LOCAL message.
+    pub const MESSAGE_AS4_LOCAL: u16 = 7;
+    /// The subtype of the MESSAGE_ADDPATH message.
+    pub const MESSAGE_ADDPATH: u16 = 8;
+    /// The subtype of the MESSAGE_AS4_ADDPATH message.
+    pub const MESSAGE_AS4_ADDPATH: u16 = 9;
+    /// The subtype of the MESSAGE_LOCAL_ADDPATH message.
+    pub const MESSAGE_LOCAL_ADDPATH: u16 = 10;
+    /// The subtype of the MESSAGE_AS4_LOCAL_ADDPATH message.
+    pub const MESSAGE_AS4_LOCAL_ADDPATH: u16 = 11;
+}
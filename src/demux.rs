@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io::{Read, Write};
+
+use crate::{Header, MrtError, Reader, Record, Writer};
+
+///
+/// # Summary
+/// Splits the remaining records of a merged MRT stream into separate streams, such as one stream
+/// per collector or per peer. Every record is assigned a key by `classify` and written to the
+/// Writer of that key. The stream of a key is created by `open` when the first record with that
+/// key is encountered. Returns the Writers of all keys, which should be flushed by the caller if
+/// their streams are buffered.
+///
+/// The records are written in the order in which they are read. Note that the RIB entries of a
+/// TABLE_DUMP_V2 stream can only be read back if the PEER_INDEX_TABLE they refer to is assigned
+/// the same key.
+///
+/// # Examples
+/// ```
+/// use std::fs::File;
+/// use mrt_rs::Reader;
+///
+/// let mut reader = Reader::new(File::open("res/quagga_bgp").unwrap());
+/// let streams = mrt_rs::demux(
+///     &mut reader,
+///     |header, _| header.record_type,
+///     |_| Ok(Vec::new()),
+/// )
+/// .unwrap();
+/// assert!(streams.contains_key(&16));
+/// ```
+///
+/// # Panics
+/// This function does not panic.
+///
+/// # Errors
+/// Any error of the Reader is returned, as well as any error while creating or writing to the
+/// output streams. Records that have been read before the error have been written.
+///
+/// # Safety
+/// This function does not make use of unsafe code.
+///
+pub fn demux<T, K, F, W, O>(
+    reader: &mut Reader<T>,
+    mut classify: F,
+    mut open: O,
+) -> Result<HashMap<K, Writer<W>>, MrtError>
+where
+    T: Read,
+    K: Eq + Hash,
+    F: FnMut(&Header, &Record) -> K,
+    W: Write,
+    O: FnMut(&K) -> std::io::Result<W>,
+{
+    let mut writers: HashMap<K, Writer<W>> = HashMap::new();
+    while let Some((header, record)) = reader.read()? {
+        let key = classify(&header, &record);
+        let writer = match writers.get_mut(&key) {
+            Some(writer) => writer,
+            None => {
+                let writer = Writer::new(open(&key)?);
+                writers.entry(key).or_insert(writer)
+            }
+        };
+        writer.write(&header, &record)?;
+    }
+    Ok(writers)
+}
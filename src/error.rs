@@ -0,0 +1,124 @@
+use std::error;
+use std::fmt;
+use std::io::Error;
+
+use crate::Header;
+
+/// Represents all errors that can occur while reading MRT records using a [`Reader`](crate::Reader).
+#[derive(Debug)]
+pub enum MrtError {
+    /// An IO error occurred while reading from the stream or the stream was ill-formatted.
+    Io(Error),
+
+    /// More records were encountered than `ParseOptions::record_limit` allows.
+    RecordLimitExceeded(usize),
+
+    /// The timestamp of a record lies outside of `ParseOptions::timestamp_bounds`.
+    /// Contains the header of the record, of which the body has not been read.
+    TimestampOutOfRange(Header),
+
+    /// The length of a record exceeds `ParseOptions::max_record_size`.
+    /// Contains the header of the record, of which the body has not been read.
+    RecordTooLarge(Header),
+
+    /// Reading the next record would exceed `ParseOptions::byte_budget`, which is contained.
+    BudgetExceeded(u64),
+
+    /// The stream ended before the end of the body of a record, which typically means that the
+    /// file has been truncated and its last record is incomplete.
+    TruncatedRecord {
+        /// The header of the incomplete record.
+        header: Header,
+        /// The number of bytes of the body that were present in the stream.
+        bytes_available: u32,
+    },
+
+    /// A record contains an address family identifier that is not supported. Some encoders
+    /// zero the AFI of the locally generated BGP4MP subtypes MESSAGE_LOCAL, MESSAGE_AS4_LOCAL,
+    /// MESSAGE_LOCAL_ADDPATH and MESSAGE_AS4_LOCAL_ADDPATH, which can be accepted by enabling
+    /// `ParseOptions::allow_missing_afi`.
+    InvalidAfi {
+        /// The offset in the stream of the record containing the address family.
+        offset: u64,
+        /// The value of the address family identifier.
+        afi: u16,
+    },
+}
+
+impl fmt::Display for MrtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MrtError::Io(e) => write!(f, "{}", e),
+            MrtError::RecordLimitExceeded(limit) => {
+                write!(f, "Stream contains more than {} records.", limit)
+            }
+            MrtError::TimestampOutOfRange(header) => write!(
+                f,
+                "Record timestamp {} is outside of the allowed range.",
+                header.timestamp
+            ),
+            MrtError::RecordTooLarge(header) => write!(
+                f,
+                "Record length {} exceeds the maximum record size.",
+                header.length
+            ),
+            MrtError::BudgetExceeded(budget) => {
+                write!(f, "Stream exceeds the budget of {} bytes.", budget)
+            }
+            MrtError::TruncatedRecord {
+                header,
+                bytes_available,
+            } => write!(
+                f,
+                "Stream ended after {} of the {} bytes of the last record.",
+                bytes_available, header.length
+            ),
+            MrtError::InvalidAfi { offset, afi } => write!(
+                f,
+                "Record at offset {} contains an invalid address family: {}",
+                offset, afi
+            ),
+        }
+    }
+}
+
+impl error::Error for MrtError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            MrtError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl MrtError {
+    /// Converts an error raised while parsing the record at the given offset.
+    pub(crate) fn at_offset(error: Error, offset: u64) -> MrtError {
+        match error.get_ref().and_then(|e| e.downcast_ref::<UnknownAfi>()) {
+            Some(UnknownAfi(afi)) => MrtError::InvalidAfi { offset, afi: *afi },
+            None => MrtError::Io(error),
+        }
+    }
+}
+
+/// The error contained in the IO error raised when an unknown address family is encountered.
+#[derive(Debug)]
+pub(crate) struct UnknownAfi(pub(crate) u16);
+
+impl fmt::Display for UnknownAfi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Number {} does not represent a valid address family.",
+            self.0
+        )
+    }
+}
+
+impl error::Error for UnknownAfi {}
+
+impl From<Error> for MrtError {
+    fn from(error: Error) -> MrtError {
+        MrtError::Io(error)
+    }
+}
@@ -0,0 +1,257 @@
+use byteorder::{BigEndian, ReadBytesExt};
+use std::io::{Error, Read};
+use std::net::IpAddr;
+
+use crate::attributes::{MpReachNlri, PathAttribute};
+use crate::bgp::{self, MessageHeader, UPDATE_TYPE};
+use crate::bgp4mp::{NormalizedContent, SessionKey, BGP4MP};
+use crate::error::UnknownAfi;
+use crate::nlri::{self, Prefix};
+use crate::routes::prefix_address;
+use crate::{MrtError, ParseOptions, Reader, Record, AFI};
+
+///
+/// A single prefix announced or withdrawn by a BGP UPDATE message of a BGP4MP record, as
+/// returned by [`Reader::route_events`].
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteEvent {
+    /// The prefix was announced by the peer with the given path attributes.
+    Announce {
+        /// The timestamp of the record containing the UPDATE message.
+        timestamp: u32,
+
+        /// The session over which the UPDATE message was received.
+        peer: SessionKey,
+
+        /// The address of the prefix, with all bits beyond the prefix length set to zero.
+        prefix: IpAddr,
+
+        /// The length of the prefix in bits.
+        prefix_length: u8,
+
+        /// The path identifier of the prefix if the session uses ADD-PATH.
+        path_id: Option<u32>,
+
+        /// The path attributes of the UPDATE message, which apply to all announced prefixes.
+        attributes: Vec<PathAttribute>,
+    },
+
+    /// The prefix was withdrawn by the peer.
+    Withdraw {
+        /// The timestamp of the record containing the UPDATE message.
+        timestamp: u32,
+
+        /// The session over which the UPDATE message was received.
+        peer: SessionKey,
+
+        /// The address of the prefix, with all bits beyond the prefix length set to zero.
+        prefix: IpAddr,
+
+        /// The length of the prefix in bits.
+        prefix_length: u8,
+
+        /// The path identifier of the prefix if the session uses ADD-PATH.
+        path_id: Option<u32>,
+    },
+}
+
+impl RouteEvent {
+    /// Returns the session over which the prefix was announced or withdrawn.
+    pub fn peer(&self) -> &SessionKey {
+        match self {
+            RouteEvent::Announce { peer, .. } | RouteEvent::Withdraw { peer, .. } => peer,
+        }
+    }
+
+    /// Returns the address and the length of the announced or withdrawn prefix.
+    pub fn prefix(&self) -> (IpAddr, u8) {
+        match self {
+            RouteEvent::Announce {
+                prefix,
+                prefix_length,
+                ..
+            }
+            | RouteEvent::Withdraw {
+                prefix,
+                prefix_length,
+                ..
+            } => (*prefix, *prefix_length),
+        }
+    }
+}
+
+///
+/// An iterator over the prefixes announced and withdrawn by the BGP4MP records read by a
+/// [`Reader`], as returned by [`Reader::route_events`].
+///
+/// Only UPDATE messages received from a peer are considered, being those of MESSAGE and
+/// MESSAGE_AS4 records and their ADD-PATH variants. Records of other types, such as the
+/// TABLE_DUMP_V2 records of merged archives, are skipped. Prefixes of the MP_REACH_NLRI and
+/// MP_UNREACH_NLRI attributes are included, except for address families other than IPv4 and IPv6
+/// unicast or multicast, such as VPN routes. Withdrawals are returned before announcements, as
+/// they are processed in that order by the receiver of the message.
+///
+/// An UPDATE message that cannot be parsed results in an error, after which the iterator
+/// continues with the next record. Errors of the Reader end the iteration.
+///
+/// # Examples
+/// ```
+/// use std::fs::File;
+/// use mrt_rs::{Reader, RouteEvent};
+///
+/// let mut reader = Reader::new(File::open("res/bird-mrtdump_bgp").unwrap());
+/// for event in reader.route_events() {
+///     match event.unwrap() {
+///         RouteEvent::Announce { prefix, prefix_length, .. } => {
+///             println!("+ {}/{}", prefix, prefix_length)
+///         }
+///         RouteEvent::Withdraw { prefix, prefix_length, .. } => {
+///             println!("- {}/{}", prefix, prefix_length)
+///         }
+///     }
+/// }
+/// ```
+///
+#[derive(Debug)]
+pub struct RouteEvents<'a, T: Read> {
+    reader: &'a mut Reader<T>,
+    pending: std::vec::IntoIter<RouteEvent>,
+    done: bool,
+}
+
+impl<'a, T: Read> RouteEvents<'a, T> {
+    pub(crate) fn new(reader: &'a mut Reader<T>) -> RouteEvents<'a, T> {
+        RouteEvents {
+            reader,
+            pending: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+}
+
+impl<T: Read> Iterator for RouteEvents<'_, T> {
+    type Item = Result<RouteEvent, MrtError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.next() {
+                return Some(Ok(event));
+            }
+            if self.done {
+                return None;
+            }
+
+            match self.reader.read() {
+                Ok(Some((header, Record::BGP4MP(record))))
+                | Ok(Some((header, Record::BGP4MP_ET(record)))) => {
+                    match expand(header.timestamp, &record, self.reader.options()) {
+                        Ok(x) => self.pending = x.into_iter(),
+                        Err(e) => return Some(Err(MrtError::from(e))),
+                    }
+                }
+                Ok(Some(_)) => (),
+                Ok(None) => self.done = true,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+/// Converts the UPDATE message of a single BGP4MP record into events.
+fn expand(
+    timestamp: u32,
+    record: &BGP4MP,
+    options: &ParseOptions,
+) -> Result<Vec<RouteEvent>, Error> {
+    let (peer, message, asn_size, add_path) = match record.normalized() {
+        Some(x) if !x.local => match x.content {
+            NormalizedContent::Message(message) => {
+                (x.session_key(), message, x.asn_size, x.add_path)
+            }
+            NormalizedContent::StateChange { .. } => return Ok(Vec::new()),
+        },
+        _ => return Ok(Vec::new()),
+    };
+    match MessageHeader::parse(message) {
+        Ok(x) if x.message_type == UPDATE_TYPE => (),
+        _ => return Ok(Vec::new()),
+    }
+
+    let (withdrawn, attributes, announced) = if add_path {
+        let update = bgp::parse_update_add_path(message, asn_size, options)?;
+        (update.withdrawn_routes, update.attributes, update.nlri)
+    } else {
+        let update = bgp::parse_update(message, asn_size, options)?;
+        let with_path_id = |x: Vec<(u8, Vec<u8>)>| x.into_iter().map(|(l, p)| (None, l, p));
+        (
+            with_path_id(update.withdrawn_routes).collect(),
+            update.attributes,
+            with_path_id(update.nlri).collect(),
+        )
+    };
+
+    let mut withdrawn: Vec<(AFI, Prefix)> = withdrawn.into_iter().map(|x| (AFI::IPV4, x)).collect();
+    let mut announced: Vec<(AFI, Prefix)> = announced.into_iter().map(|x| (AFI::IPV4, x)).collect();
+    for attribute in &attributes {
+        match attribute {
+            PathAttribute::MP_REACH_NLRI(value) if is_supported(value) => {
+                let reach = MpReachNlri::parse(value, add_path, options)?;
+                if let Some(afi) = reach.afi {
+                    let afi = AFI::from(afi)?;
+                    announced.extend(reach.prefixes.into_iter().map(|x| (afi, x)));
+                }
+            }
+            PathAttribute::MP_UNREACH_NLRI(value) if is_supported(value) => {
+                let (afi, prefixes) = parse_mp_unreach_nlri(value, add_path, options)?;
+                withdrawn.extend(prefixes.into_iter().map(|x| (afi, x)));
+            }
+            _ => (),
+        }
+    }
+
+    let address = |afi: AFI, prefix: &[u8], prefix_length: u8| {
+        prefix_address(afi, prefix, prefix_length)
+            .ok_or_else(|| Error::other(UnknownAfi(afi.code())))
+    };
+    let mut events = Vec::with_capacity(withdrawn.len() + announced.len());
+    for (afi, (path_id, prefix_length, prefix)) in withdrawn {
+        events.push(RouteEvent::Withdraw {
+            timestamp,
+            peer,
+            prefix: address(afi, &prefix, prefix_length)?,
+            prefix_length,
+            path_id,
+        });
+    }
+    for (afi, (path_id, prefix_length, prefix)) in announced {
+        events.push(RouteEvent::Announce {
+            timestamp,
+            peer,
+            prefix: address(afi, &prefix, prefix_length)?,
+            prefix_length,
+            path_id,
+            attributes: attributes.clone(),
+        });
+    }
+    Ok(events)
+}
+
+/// Returns true if an MP_REACH_NLRI or MP_UNREACH_NLRI attribute holds unicast or multicast NLRI.
+fn is_supported(value: &[u8]) -> bool {
+    value.len() >= 3 && (value[2] == 1 || value[2] == 2)
+}
+
+/// Parses the address family and the withdrawn prefixes of an MP_UNREACH_NLRI attribute.
+fn parse_mp_unreach_nlri(
+    mut value: &[u8],
+    add_path: bool,
+    options: &ParseOptions,
+) -> Result<(AFI, Vec<Prefix>), Error> {
+    let afi = AFI::from(value.read_u16::<BigEndian>()?)?;
+    let _safi = value.read_u8()?;
+    Ok((afi, nlri::read_prefixes(value, afi, add_path, options)?))
+}
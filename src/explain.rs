@@ -0,0 +1,217 @@
+use crate::{codes, Header};
+
+impl Header {
+    ///
+    /// Returns a human-readable description of the layout of the record body described by this
+    /// header, naming every field in the order in which it appears on the wire. This is useful
+    /// when learning the MRT format or when debugging a stream that fails to parse.
+    ///
+    /// # Examples
+    /// ```
+    /// let header = mrt_rs::HeaderBuilder::new(16, 4).build();
+    /// assert!(header.explain().starts_with("BGP4MP MESSAGE_AS4"));
+    /// ```
+    ///
+    pub fn explain(&self) -> String {
+        let (name, layout) = match layout(self.record_type, self.sub_type) {
+            Some(x) => x,
+            None => {
+                return format!(
+                    "Unknown record of type {} and subtype {}: {} bytes that cannot be parsed",
+                    self.record_type, self.sub_type, self.length
+                )
+            }
+        };
+
+        // Types with an extended timestamp share the layout of their counterparts without one.
+        if self.has_extended_timestamp() {
+            let (family, sub_type) = name.split_at(name.find(' ').unwrap_or(name.len()));
+            return format!(
+                "{}_ET{} (type {}, subtype {}, {} bytes): microsecond_timestamp(u32), {}",
+                family, sub_type, self.record_type, self.sub_type, self.length, layout
+            );
+        }
+
+        format!(
+            "{} (type {}, subtype {}, {} bytes): {}",
+            name, self.record_type, self.sub_type, self.length, layout
+        )
+    }
+}
+
+/// The fields of the BGP4MP messages with 2-byte ASNs.
+const BGP4MP_MESSAGE: &str = "peer_as(u16), local_as(u16), interface(u16), afi(u16), \
+     peer_address(4 or 16 bytes), local_address(4 or 16 bytes), then the BGP message";
+
+/// The fields of the BGP4MP messages with 4-byte ASNs.
+const BGP4MP_MESSAGE_AS4: &str = "peer_as(u32), local_as(u32), interface(u16), afi(u16), \
+     peer_address(4 or 16 bytes), local_address(4 or 16 bytes), then the BGP message";
+
+/// The fields of the TABLE_DUMP_V2 RIB records for a specific address family.
+const RIB_AFI: &str = "sequence_number(u32), prefix_length(u8), prefix(prefix_length / 8 \
+     rounded up bytes), entry_count(u16), then entry_count times peer_index(u16), \
+     originated_time(u32), attribute_length(u16), attributes";
+
+/// The fields of the TABLE_DUMP_V2 RIB records for a specific address family using ADD-PATH.
+const RIB_AFI_ADDPATH: &str = "sequence_number(u32), prefix_length(u8), prefix(prefix_length \
+     / 8 rounded up bytes), entry_count(u16), then entry_count times peer_index(u16), \
+     originated_time(u32), path_identifier(u32), attribute_length(u16), attributes";
+
+/// Returns the name and the layout of the body of a record of the given type and subtype.
+fn layout(record_type: u16, sub_type: u16) -> Option<(&'static str, &'static str)> {
+    let deprecated = "deprecated record whose body is skipped";
+    let x = match (record_type, sub_type) {
+        (codes::NULL, _) => ("NULL", deprecated),
+        (codes::START, _) => ("START", deprecated),
+        (codes::DIE, _) => ("DIE", deprecated),
+        (codes::I_AM_DEAD, _) => ("I_AM_DEAD", deprecated),
+        (codes::IDRP, _) => ("IDRP", deprecated),
+        (codes::PEER_DOWN, _) => ("PEER_DOWN", "reason(u8), then the remaining bytes as data"),
+        (codes::BGP, x) | (codes::BGP4PLUS, x) | (codes::BGP4PLUS_01, x) => {
+            let ipv6 = record_type != codes::BGP;
+            let name = match record_type {
+                codes::BGP => "BGP",
+                codes::BGP4PLUS => "BGP4PLUS",
+                _ => "BGP4PLUS_01",
+            };
+            let layout = match x {
+                codes::bgp::NULL | codes::bgp::PREF_UPDATE => "no body",
+                codes::bgp::STATE_CHANGE if ipv6 => {
+                    "peer_as(u16), peer_ip(16 bytes), old_state(u16), new_state(u16)"
+                }
+                codes::bgp::STATE_CHANGE => {
+                    "peer_as(u16), peer_ip(4 bytes), old_state(u16), new_state(u16)"
+                }
+                codes::bgp::SYNC => "view_number(u16), then a NUL-terminated filename",
+                codes::bgp::UPDATE
+                | codes::bgp::OPEN
+                | codes::bgp::NOTIFY
+                | codes::bgp::KEEPALIVE
+                    if ipv6 =>
+                {
+                    "peer_as(u16), peer_ip(16 bytes), local_as(u16), local_ip(16 bytes), \
+                     then the BGP message"
+                }
+                codes::bgp::UPDATE
+                | codes::bgp::OPEN
+                | codes::bgp::NOTIFY
+                | codes::bgp::KEEPALIVE => {
+                    "peer_as(u16), peer_ip(4 bytes), local_as(u16), local_ip(4 bytes), \
+                     then the BGP message"
+                }
+                _ => return None,
+            };
+            (name, layout)
+        }
+        (codes::RIP, _) => (
+            "RIP",
+            "remote(4 bytes), local(4 bytes), then the RIP message",
+        ),
+        (codes::RIPNG, _) => (
+            "RIPNG",
+            "remote(16 bytes), local(16 bytes), then the RIPng message",
+        ),
+        (codes::OSPFV2, _) => (
+            "OSPFv2",
+            "remote(4 bytes), local(4 bytes), then the OSPF message",
+        ),
+        (codes::OSPFV3, _) | (codes::OSPFV3_ET, _) => (
+            "OSPFv3",
+            "afi(u16), remote(4 or 16 bytes), local(4 or 16 bytes), then the OSPF message",
+        ),
+        (codes::ISIS, _) | (codes::ISIS_ET, _) => ("ISIS", "the IS-IS PDU"),
+        (codes::TABLE_DUMP, codes::table_dump::AFI_IPV4) => (
+            "TABLE_DUMP AFI_IPV4",
+            "view_number(u16), sequence_number(u16), prefix(4 bytes), prefix_length(u8), \
+             status(u8), originated_time(u32), peer_address(4 bytes), peer_as(u16), \
+             attribute_length(u16), attributes",
+        ),
+        (codes::TABLE_DUMP, codes::table_dump::AFI_IPV6) => (
+            "TABLE_DUMP AFI_IPV6",
+            "view_number(u16), sequence_number(u16), prefix(16 bytes), prefix_length(u8), \
+             status(u8), originated_time(u32), peer_address(16 bytes), peer_as(u16), \
+             attribute_length(u16), attributes",
+        ),
+        (codes::TABLE_DUMP_V2, x) => match x {
+            codes::table_dump_v2::PEER_INDEX_TABLE => (
+                "TABLE_DUMP_V2 PEER_INDEX_TABLE",
+                "collector_id(u32), view_name_length(u16), view_name, peer_count(u16), then \
+                 peer_count times peer_type(u8), peer_bgp_id(u32), peer_ip_address(4 or 16 \
+                 bytes), peer_as(u16 or u32)",
+            ),
+            codes::table_dump_v2::RIB_IPV4_UNICAST => ("TABLE_DUMP_V2 RIB_IPV4_UNICAST", RIB_AFI),
+            codes::table_dump_v2::RIB_IPV4_MULTICAST => {
+                ("TABLE_DUMP_V2 RIB_IPV4_MULTICAST", RIB_AFI)
+            }
+            codes::table_dump_v2::RIB_IPV6_UNICAST => ("TABLE_DUMP_V2 RIB_IPV6_UNICAST", RIB_AFI),
+            codes::table_dump_v2::RIB_IPV6_MULTICAST => {
+                ("TABLE_DUMP_V2 RIB_IPV6_MULTICAST", RIB_AFI)
+            }
+            codes::table_dump_v2::RIB_GENERIC => (
+                "TABLE_DUMP_V2 RIB_GENERIC",
+                "sequence_number(u32), afi(u16), safi(u8), nlri, entry_count(u16), then \
+                 entry_count times peer_index(u16), originated_time(u32), \
+                 attribute_length(u16), attributes",
+            ),
+            codes::table_dump_v2::RIB_IPV4_UNICAST_ADDPATH => {
+                ("TABLE_DUMP_V2 RIB_IPV4_UNICAST_ADDPATH", RIB_AFI_ADDPATH)
+            }
+            codes::table_dump_v2::RIB_IPV4_MULTICAST_ADDPATH => {
+                ("TABLE_DUMP_V2 RIB_IPV4_MULTICAST_ADDPATH", RIB_AFI_ADDPATH)
+            }
+            codes::table_dump_v2::RIB_IPV6_UNICAST_ADDPATH => {
+                ("TABLE_DUMP_V2 RIB_IPV6_UNICAST_ADDPATH", RIB_AFI_ADDPATH)
+            }
+            codes::table_dump_v2::RIB_IPV6_MULTICAST_ADDPATH => {
+                ("TABLE_DUMP_V2 RIB_IPV6_MULTICAST_ADDPATH", RIB_AFI_ADDPATH)
+            }
+            codes::table_dump_v2::RIB_GENERIC_ADDPATH => (
+                "TABLE_DUMP_V2 RIB_GENERIC_ADDPATH",
+                "sequence_number(u32), afi(u16), safi(u8), nlri, entry_count(u16), then \
+                 entry_count times peer_index(u16), originated_time(u32), \
+                 path_identifier(u32), attribute_length(u16), attributes",
+            ),
+            _ => return None,
+        },
+        (codes::BGP4MP, x) | (codes::BGP4MP_ET, x) => match x {
+            codes::bgp4mp::STATE_CHANGE => (
+                "BGP4MP STATE_CHANGE",
+                "peer_as(u16), local_as(u16), interface(u16), afi(u16), peer_address(4 or 16 \
+                 bytes), local_address(4 or 16 bytes), old_state(u16), new_state(u16)",
+            ),
+            codes::bgp4mp::STATE_CHANGE_AS4 => (
+                "BGP4MP STATE_CHANGE_AS4",
+                "peer_as(u32), local_as(u32), interface(u16), afi(u16), peer_address(4 or 16 \
+                 bytes), local_address(4 or 16 bytes), old_state(u16), new_state(u16)",
+            ),
+            codes::bgp4mp::MESSAGE => ("BGP4MP MESSAGE", BGP4MP_MESSAGE),
+            codes::bgp4mp::MESSAGE_AS4 => ("BGP4MP MESSAGE_AS4", BGP4MP_MESSAGE_AS4),
+            codes::bgp4mp::MESSAGE_LOCAL => ("BGP4MP MESSAGE_LOCAL", BGP4MP_MESSAGE),
+            codes::bgp4mp::MESSAGE_AS4_LOCAL => ("BGP4MP MESSAGE_AS4_LOCAL", BGP4MP_MESSAGE_AS4),
+            codes::bgp4mp::MESSAGE_ADDPATH => ("BGP4MP MESSAGE_ADDPATH", BGP4MP_MESSAGE),
+            codes::bgp4mp::MESSAGE_AS4_ADDPATH => {
+                ("BGP4MP MESSAGE_AS4_ADDPATH", BGP4MP_MESSAGE_AS4)
+            }
+            codes::bgp4mp::MESSAGE_LOCAL_ADDPATH => {
+                ("BGP4MP MESSAGE_LOCAL_ADDPATH", BGP4MP_MESSAGE)
+            }
+            codes::bgp4mp::MESSAGE_AS4_LOCAL_ADDPATH => {
+                ("BGP4MP MESSAGE_AS4_LOCAL_ADDPATH", BGP4MP_MESSAGE_AS4)
+            }
+            codes::bgp4mp::ENTRY => (
+                "BGP4MP ENTRY",
+                "peer_as(u16), local_as(u16), interface(u16), afi(u16), peer_address(4 or 16 \
+                 bytes), local_address(4 or 16 bytes), view_number(u16), status(u16), \
+                 time_last_change(u32), afi(u16), safi(u8), next_hop_length(u8), next_hop, \
+                 prefix_length(u8), prefix, attribute_length(u16), attributes",
+            ),
+            codes::bgp4mp::SNAPSHOT => (
+                "BGP4MP SNAPSHOT",
+                "view_number(u16), then a NUL-terminated filename",
+            ),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(x)
+}
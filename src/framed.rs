@@ -0,0 +1,185 @@
+use std::io::{Error, ErrorKind, Read};
+
+use crate::reader::check_header;
+use crate::{Header, MrtError, ParseOptions, Record};
+
+/// The number of bytes that is requested from the stream by [`FramedReader::read_from`].
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+///
+/// Parses MRT records from bytes that arrive in arbitrary chunks, such as from a TCP socket.
+/// Bytes are buffered until an entire record is available, so records are never parsed from a
+/// partial body and no call ever blocks on the stream waiting for the rest of a record.
+///
+/// # Examples
+/// ```
+/// use mrt_rs::FramedReader;
+///
+/// let bytes = std::fs::read("res/bird-mrtdump_bgp").unwrap();
+/// let mut reader = FramedReader::new();
+/// let mut records = 0;
+///
+/// for chunk in bytes.chunks(100) {
+///     reader.push(chunk);
+///     while let Some((_header, _record)) = reader.next_record().unwrap() {
+///         records += 1;
+///     }
+/// }
+///
+/// assert_eq!(records, 27);
+/// assert_eq!(reader.buffered(), 0);
+/// ```
+///
+#[derive(Debug, Default)]
+pub struct FramedReader {
+    /// The options used while parsing records.
+    options: ParseOptions,
+
+    /// The bytes that have been received. The bytes before `position` have already been parsed.
+    buffer: Vec<u8>,
+
+    /// The offset in `buffer` of the first byte that has not been parsed yet.
+    position: usize,
+
+    /// The number of bytes of the body of a rejected record that are still to be discarded.
+    skip: usize,
+
+    /// The offset in the stream of the first byte that has not been parsed yet.
+    offset: u64,
+}
+
+impl FramedReader {
+    /// Creates a new FramedReader.
+    pub fn new() -> FramedReader {
+        FramedReader::with_options(ParseOptions::default())
+    }
+
+    /// Creates a new FramedReader that parses records using the given options.
+    pub fn with_options(options: ParseOptions) -> FramedReader {
+        FramedReader {
+            options,
+            buffer: Vec::new(),
+            position: 0,
+            skip: 0,
+            offset: 0,
+        }
+    }
+
+    /// Appends received bytes to the internal buffer.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.compact();
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    ///
+    /// Performs a single read on the given stream and appends the bytes to the internal buffer.
+    /// Returns the number of bytes read, where zero indicates that the stream has ended.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from the stream, including errors of kind
+    /// `WouldBlock` for non-blocking streams.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn read_from(&mut self, mut stream: impl Read) -> Result<usize, Error> {
+        self.compact();
+        let start = self.buffer.len();
+        self.buffer.resize(start + READ_CHUNK_SIZE, 0);
+        let result = stream.read(&mut self.buffer[start..]);
+        self.buffer.truncate(start + *result.as_ref().unwrap_or(&0));
+        result
+    }
+
+    /// Returns the number of bytes that have been received but not yet parsed.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len() - self.position
+    }
+
+    ///
+    /// Parses the next MRT record if it has been received entirely.
+    /// Returns `None` if more bytes are needed to parse the next record. Whether the stream has
+    /// ended is not known to the FramedReader; once it has, a non-zero `buffered` indicates that
+    /// the stream ended in the middle of a record.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// An error is returned if the record is ill-formatted. The record is discarded in that case,
+    /// such that the next call continues with the record that follows it. A header rejected by
+    /// the options, such as one exceeding `ParseOptions::max_record_size`, is reported as soon
+    /// as it has been received. Its body is discarded as it arrives.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn next_record(&mut self) -> Result<Option<(Header, Record)>, MrtError> {
+        self.discard();
+        if self.skip > 0 || self.buffered() < 12 {
+            return Ok(None);
+        }
+
+        let mut bytes = &self.buffer[self.position..self.position + 12];
+        let header =
+            crate::read_header(&mut bytes)?.ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))?;
+        let header = match check_header(header, &self.options) {
+            Ok(header) => header,
+            Err(e) => {
+                self.consume(12);
+                self.skip = header.length as usize;
+                self.discard();
+                return Err(e);
+            }
+        };
+
+        let total = (header.length as usize).saturating_add(12);
+        if self.buffered() < total {
+            return Ok(None);
+        }
+
+        let result = self.parse(header, total);
+        self.consume(total);
+        result.map(Some)
+    }
+
+    /// Discards as much of the body of a rejected record as has been received.
+    fn discard(&mut self) {
+        let length = self.skip.min(self.buffered());
+        self.consume(length);
+        self.skip -= length;
+    }
+
+    /// Marks the given number of bytes as parsed and compacts the buffer once more than half of
+    /// it has been parsed.
+    fn consume(&mut self, length: usize) {
+        self.position += length;
+        self.offset += length as u64;
+        if self.position == self.buffer.len() {
+            self.buffer.clear();
+            self.position = 0;
+        } else if self.position > self.buffer.len() / 2 {
+            self.compact();
+        }
+    }
+
+    /// Removes the bytes that have already been parsed from the buffer.
+    fn compact(&mut self) {
+        if self.position > 0 {
+            self.buffer.drain(..self.position);
+            self.position = 0;
+        }
+    }
+
+    /// Parses the body of the record with the given header, which occupies the first `total`
+    /// unparsed bytes of the buffer including the header.
+    fn parse(&self, mut header: Header, total: usize) -> Result<(Header, Record), MrtError> {
+        let mut bytes = &self.buffer[self.position + 12..self.position + total];
+        let record = crate::parse_record(&mut header, &mut bytes, &self.options)
+            .map_err(|e| MrtError::at_offset(e, self.offset))?;
+        Ok((header, record))
+    }
+}
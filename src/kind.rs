@@ -0,0 +1,289 @@
+use crate::bgp::BGP;
+use crate::bgp4mp::BGP4MP;
+use crate::bgp4plus::BGP4PLUS;
+use crate::tabledump::TABLE_DUMP_V2;
+use crate::Record;
+
+///
+/// The type and subtype of a [`Record`] flattened into a single value, which can be used as the
+/// key of a map. Records with an extended timestamp have the same kind as their counterparts
+/// without one, use [`Header::has_extended_timestamp`](crate::Header::has_extended_timestamp)
+/// to tell them apart.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum RecordKind {
+    /// A NULL record.
+    NULL,
+
+    /// A START record.
+    START,
+
+    /// A DIE record.
+    DIE,
+
+    /// An I_AM_DEAD record.
+    I_AM_DEAD,
+
+    /// A PEER_DOWN record.
+    PEER_DOWN,
+
+    /// A BGP record of subtype NULL.
+    BGP_NULL,
+
+    /// A BGP record of subtype UPDATE.
+    BGP_UPDATE,
+
+    /// A BGP record of subtype PREF_UPDATE.
+    BGP_PREF_UPDATE,
+
+    /// A BGP record of subtype STATE_CHANGE.
+    BGP_STATE_CHANGE,
+
+    /// A BGP record of subtype SYNC.
+    BGP_SYNC,
+
+    /// A BGP record of subtype OPEN.
+    BGP_OPEN,
+
+    /// A BGP record of subtype NOTIFY.
+    BGP_NOTIFY,
+
+    /// A BGP record of subtype KEEPALIVE.
+    BGP_KEEPALIVE,
+
+    /// A RIP record.
+    RIP,
+
+    /// An IDRP record.
+    IDRP,
+
+    /// A RIPNG record.
+    RIPNG,
+
+    /// A BGP4PLUS record of subtype NULL.
+    BGP4PLUS_NULL,
+
+    /// A BGP4PLUS record of subtype UPDATE.
+    BGP4PLUS_UPDATE,
+
+    /// A BGP4PLUS record of subtype PREF_UPDATE.
+    BGP4PLUS_PREF_UPDATE,
+
+    /// A BGP4PLUS record of subtype STATE_CHANGE.
+    BGP4PLUS_STATE_CHANGE,
+
+    /// A BGP4PLUS record of subtype SYNC.
+    BGP4PLUS_SYNC,
+
+    /// A BGP4PLUS record of subtype OPEN.
+    BGP4PLUS_OPEN,
+
+    /// A BGP4PLUS record of subtype NOTIFY.
+    BGP4PLUS_NOTIFY,
+
+    /// A BGP4PLUS record of subtype KEEPALIVE.
+    BGP4PLUS_KEEPALIVE,
+
+    /// A BGP4PLUS_01 record of subtype NULL.
+    BGP4PLUS_01_NULL,
+
+    /// A BGP4PLUS_01 record of subtype UPDATE.
+    BGP4PLUS_01_UPDATE,
+
+    /// A BGP4PLUS_01 record of subtype PREF_UPDATE.
+    BGP4PLUS_01_PREF_UPDATE,
+
+    /// A BGP4PLUS_01 record of subtype STATE_CHANGE.
+    BGP4PLUS_01_STATE_CHANGE,
+
+    /// A BGP4PLUS_01 record of subtype SYNC.
+    BGP4PLUS_01_SYNC,
+
+    /// A BGP4PLUS_01 record of subtype OPEN.
+    BGP4PLUS_01_OPEN,
+
+    /// A BGP4PLUS_01 record of subtype NOTIFY.
+    BGP4PLUS_01_NOTIFY,
+
+    /// A BGP4PLUS_01 record of subtype KEEPALIVE.
+    BGP4PLUS_01_KEEPALIVE,
+
+    /// An OSPFv2 record.
+    OSPFv2,
+
+    /// A TABLE_DUMP record.
+    TABLE_DUMP,
+
+    /// A TABLE_DUMP_V2 record of subtype PEER_INDEX_TABLE.
+    TABLE_DUMP_V2_PEER_INDEX_TABLE,
+
+    /// A TABLE_DUMP_V2 record of subtype RIB_IPV4_UNICAST.
+    TABLE_DUMP_V2_RIB_IPV4_UNICAST,
+
+    /// A TABLE_DUMP_V2 record of subtype RIB_IPV4_MULTICAST.
+    TABLE_DUMP_V2_RIB_IPV4_MULTICAST,
+
+    /// A TABLE_DUMP_V2 record of subtype RIB_IPV6_UNICAST.
+    TABLE_DUMP_V2_RIB_IPV6_UNICAST,
+
+    /// A TABLE_DUMP_V2 record of subtype RIB_IPV6_MULTICAST.
+    TABLE_DUMP_V2_RIB_IPV6_MULTICAST,
+
+    /// A TABLE_DUMP_V2 record of subtype RIB_GENERIC.
+    TABLE_DUMP_V2_RIB_GENERIC,
+
+    /// A TABLE_DUMP_V2 record of subtype RIB_IPV4_UNICAST_ADDPATH.
+    TABLE_DUMP_V2_RIB_IPV4_UNICAST_ADDPATH,
+
+    /// A TABLE_DUMP_V2 record of subtype RIB_IPV4_MULTICAST_ADDPATH.
+    TABLE_DUMP_V2_RIB_IPV4_MULTICAST_ADDPATH,
+
+    /// A TABLE_DUMP_V2 record of subtype RIB_IPV6_UNICAST_ADDPATH.
+    TABLE_DUMP_V2_RIB_IPV6_UNICAST_ADDPATH,
+
+    /// A TABLE_DUMP_V2 record of subtype RIB_IPV6_MULTICAST_ADDPATH.
+    TABLE_DUMP_V2_RIB_IPV6_MULTICAST_ADDPATH,
+
+    /// A TABLE_DUMP_V2 record of subtype RIB_GENERIC_ADDPATH.
+    TABLE_DUMP_V2_RIB_GENERIC_ADDPATH,
+
+    /// A BGP4MP or BGP4MP_ET record of subtype STATE_CHANGE.
+    BGP4MP_STATE_CHANGE,
+
+    /// A BGP4MP or BGP4MP_ET record of subtype MESSAGE.
+    BGP4MP_MESSAGE,
+
+    /// A BGP4MP or BGP4MP_ET record of subtype ENTRY.
+    BGP4MP_ENTRY,
+
+    /// A BGP4MP or BGP4MP_ET record of subtype SNAPSHOT.
+    BGP4MP_SNAPSHOT,
+
+    /// A BGP4MP or BGP4MP_ET record of subtype MESSAGE_AS4.
+    BGP4MP_MESSAGE_AS4,
+
+    /// A BGP4MP or BGP4MP_ET record of subtype STATE_CHANGE_AS4.
+    BGP4MP_STATE_CHANGE_AS4,
+
+    /// A BGP4MP or BGP4MP_ET record of subtype MESSAGE_LOCAL.
+    BGP4MP_MESSAGE_LOCAL,
+
+    /// A BGP4MP or BGP4MP_ET record of subtype MESSAGE_AS4_LOCAL.
+    BGP4MP_MESSAGE_AS4_LOCAL,
+
+    /// A BGP4MP or BGP4MP_ET record of subtype MESSAGE_ADDPATH.
+    BGP4MP_MESSAGE_ADDPATH,
+
+    /// A BGP4MP or BGP4MP_ET record of subtype MESSAGE_AS4_ADDPATH.
+    BGP4MP_MESSAGE_AS4_ADDPATH,
+
+    /// A BGP4MP or BGP4MP_ET record of subtype MESSAGE_LOCAL_ADDPATH.
+    BGP4MP_MESSAGE_LOCAL_ADDPATH,
+
+    /// A BGP4MP or BGP4MP_ET record of subtype MESSAGE_AS4_LOCAL_ADDPATH.
+    BGP4MP_MESSAGE_AS4_LOCAL_ADDPATH,
+
+    /// An ISIS or ISIS_ET record.
+    ISIS,
+
+    /// An OSPFv3 or OSPFv3_ET record.
+    OSPFv3,
+
+    /// A record of a type or subtype that is not supported by this crate.
+    UNKNOWN,
+}
+
+impl Record {
+    /// Returns the kind of this record.
+    pub fn kind(&self) -> RecordKind {
+        match self {
+            Record::NULL => RecordKind::NULL,
+            Record::START => RecordKind::START,
+            Record::DIE => RecordKind::DIE,
+            Record::I_AM_DEAD => RecordKind::I_AM_DEAD,
+            Record::PEER_DOWN(_) => RecordKind::PEER_DOWN,
+            Record::BGP(x) => match x {
+                BGP::NULL => RecordKind::BGP_NULL,
+                BGP::UPDATE(_) => RecordKind::BGP_UPDATE,
+                BGP::PREF_UPDATE => RecordKind::BGP_PREF_UPDATE,
+                BGP::STATE_CHANGE(_) => RecordKind::BGP_STATE_CHANGE,
+                BGP::SYNC(_) => RecordKind::BGP_SYNC,
+                BGP::OPEN(_) => RecordKind::BGP_OPEN,
+                BGP::NOTIFY(_) => RecordKind::BGP_NOTIFY,
+                BGP::KEEPALIVE(_) => RecordKind::BGP_KEEPALIVE,
+            },
+            Record::RIP(_) => RecordKind::RIP,
+            Record::IDRP => RecordKind::IDRP,
+            Record::RIPNG(_) => RecordKind::RIPNG,
+            Record::BGP4PLUS(x) => match x {
+                BGP4PLUS::NULL => RecordKind::BGP4PLUS_NULL,
+                BGP4PLUS::UPDATE(_) => RecordKind::BGP4PLUS_UPDATE,
+                BGP4PLUS::PREF_UPDATE => RecordKind::BGP4PLUS_PREF_UPDATE,
+                BGP4PLUS::STATE_CHANGE(_) => RecordKind::BGP4PLUS_STATE_CHANGE,
+                BGP4PLUS::SYNC(_) => RecordKind::BGP4PLUS_SYNC,
+                BGP4PLUS::OPEN(_) => RecordKind::BGP4PLUS_OPEN,
+                BGP4PLUS::NOTIFY(_) => RecordKind::BGP4PLUS_NOTIFY,
+                BGP4PLUS::KEEPALIVE(_) => RecordKind::BGP4PLUS_KEEPALIVE,
+            },
+            Record::BGP4PLUS_01(x) => match x {
+                BGP4PLUS::NULL => RecordKind::BGP4PLUS_01_NULL,
+                BGP4PLUS::UPDATE(_) => RecordKind::BGP4PLUS_01_UPDATE,
+                BGP4PLUS::PREF_UPDATE => RecordKind::BGP4PLUS_01_PREF_UPDATE,
+                BGP4PLUS::STATE_CHANGE(_) => RecordKind::BGP4PLUS_01_STATE_CHANGE,
+                BGP4PLUS::SYNC(_) => RecordKind::BGP4PLUS_01_SYNC,
+                BGP4PLUS::OPEN(_) => RecordKind::BGP4PLUS_01_OPEN,
+                BGP4PLUS::NOTIFY(_) => RecordKind::BGP4PLUS_01_NOTIFY,
+                BGP4PLUS::KEEPALIVE(_) => RecordKind::BGP4PLUS_01_KEEPALIVE,
+            },
+            Record::OSPFv2(_) => RecordKind::OSPFv2,
+            Record::TABLE_DUMP(_) => RecordKind::TABLE_DUMP,
+            Record::TABLE_DUMP_V2(x) => match x {
+                TABLE_DUMP_V2::PEER_INDEX_TABLE(_) => RecordKind::TABLE_DUMP_V2_PEER_INDEX_TABLE,
+                TABLE_DUMP_V2::RIB_IPV4_UNICAST(_) => RecordKind::TABLE_DUMP_V2_RIB_IPV4_UNICAST,
+                TABLE_DUMP_V2::RIB_IPV4_MULTICAST(_) => {
+                    RecordKind::TABLE_DUMP_V2_RIB_IPV4_MULTICAST
+                }
+                TABLE_DUMP_V2::RIB_IPV6_UNICAST(_) => RecordKind::TABLE_DUMP_V2_RIB_IPV6_UNICAST,
+                TABLE_DUMP_V2::RIB_IPV6_MULTICAST(_) => {
+                    RecordKind::TABLE_DUMP_V2_RIB_IPV6_MULTICAST
+                }
+                TABLE_DUMP_V2::RIB_GENERIC(_) => RecordKind::TABLE_DUMP_V2_RIB_GENERIC,
+                TABLE_DUMP_V2::RIB_IPV4_UNICAST_ADDPATH(_) => {
+                    RecordKind::TABLE_DUMP_V2_RIB_IPV4_UNICAST_ADDPATH
+                }
+                TABLE_DUMP_V2::RIB_IPV4_MULTICAST_ADDPATH(_) => {
+                    RecordKind::TABLE_DUMP_V2_RIB_IPV4_MULTICAST_ADDPATH
+                }
+                TABLE_DUMP_V2::RIB_IPV6_UNICAST_ADDPATH(_) => {
+                    RecordKind::TABLE_DUMP_V2_RIB_IPV6_UNICAST_ADDPATH
+                }
+                TABLE_DUMP_V2::RIB_IPV6_MULTICAST_ADDPATH(_) => {
+                    RecordKind::TABLE_DUMP_V2_RIB_IPV6_MULTICAST_ADDPATH
+                }
+                TABLE_DUMP_V2::RIB_GENERIC_ADDPATH(_) => {
+                    RecordKind::TABLE_DUMP_V2_RIB_GENERIC_ADDPATH
+                }
+            },
+            Record::BGP4MP(x) | Record::BGP4MP_ET(x) => match x {
+                BGP4MP::STATE_CHANGE(_) => RecordKind::BGP4MP_STATE_CHANGE,
+                BGP4MP::MESSAGE(_) => RecordKind::BGP4MP_MESSAGE,
+                BGP4MP::ENTRY(_) => RecordKind::BGP4MP_ENTRY,
+                BGP4MP::SNAPSHOT(_) => RecordKind::BGP4MP_SNAPSHOT,
+                BGP4MP::MESSAGE_AS4(_) => RecordKind::BGP4MP_MESSAGE_AS4,
+                BGP4MP::STATE_CHANGE_AS4(_) => RecordKind::BGP4MP_STATE_CHANGE_AS4,
+                BGP4MP::MESSAGE_LOCAL(_) => RecordKind::BGP4MP_MESSAGE_LOCAL,
+                BGP4MP::MESSAGE_AS4_LOCAL(_) => RecordKind::BGP4MP_MESSAGE_AS4_LOCAL,
+                BGP4MP::MESSAGE_ADDPATH(_) => RecordKind::BGP4MP_MESSAGE_ADDPATH,
+                BGP4MP::MESSAGE_AS4_ADDPATH(_) => RecordKind::BGP4MP_MESSAGE_AS4_ADDPATH,
+                BGP4MP::MESSAGE_LOCAL_ADDPATH(_) => RecordKind::BGP4MP_MESSAGE_LOCAL_ADDPATH,
+                BGP4MP::MESSAGE_AS4_LOCAL_ADDPATH(_) => {
+                    RecordKind::BGP4MP_MESSAGE_AS4_LOCAL_ADDPATH
+                }
+            },
+            Record::ISIS(_) | Record::ISIS_ET(_) => RecordKind::ISIS,
+            Record::OSPFv3(_) | Record::OSPFv3_ET(_) => RecordKind::OSPFv3,
+            Record::UNKNOWN(_) => RecordKind::UNKNOWN,
+        }
+    }
+}
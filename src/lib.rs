@@ -1,4 +1,20 @@
 #![deny(missing_docs)]
+// Parsers are fed untrusted input, which must result in errors instead of panics.
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable
+)]
+#![cfg_attr(
+    test,
+    allow(
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::panic,
+        clippy::unreachable
+    )
+)]
 
 //! The `mrt-rs` crate provides functionality to parse an MRT-formatted streams.
 //!
@@ -26,12 +42,18 @@
 //! }
 //! ```
 
-use byteorder::{BigEndian, ReadBytesExt};
-use std::io::{Error, ErrorKind, Read};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::io::{Error, ErrorKind, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 /// Contains the implementation of all MRT record types.
 pub mod records {
 
+    /// Contains the BGP path attributes carried by BGP messages and RIB entries.
+    pub mod attributes;
+
     /// Contains all BGP subtypes.
     pub mod bgp;
 
@@ -41,9 +63,15 @@ pub mod records {
     /// Contains all BGP4MP subtypes.
     pub mod bgp4mp;
 
+    /// Contains helpers to extract BGP messages from BGP Monitoring Protocol (BMP) messages.
+    pub mod bmp;
+
     /// Contains all ISIS subtypes.
     pub mod isis;
 
+    /// Contains helpers to decode the prefixes carried in NLRI fields.
+    pub mod nlri;
+
     /// Contains all OSPF subtypes such as OSPFv2 and OSPFv3.
     pub mod ospf;
 
@@ -54,51 +82,259 @@ pub mod records {
     pub mod tabledump;
 }
 
+/// Contains the numeric codes of all MRT record types and their subtypes.
+pub mod codes;
+
+/// Contains helpers to analyze the contents of MRT streams.
+mod analysis;
+
+/// Contains the formatting of routes in the output format of bgpdump.
+mod bgpdump;
+
+/// Contains the demultiplexing of a merged MRT stream into multiple streams.
+mod demux;
+
+/// Contains the error type returned by the Reader.
+mod error;
+
+/// Contains the iterator over the prefixes announced and withdrawn by BGP4MP records.
+mod events;
+
+/// Contains the description of the layout of MRT records.
+mod explain;
+
+/// Contains the FramedReader used to parse MRT records from chunks of bytes.
+mod framed;
+
+/// Contains the RecordKind used to classify MRT records.
+mod kind;
+
+/// Contains the k-way merge of multiple chronologically ordered MRT streams.
+mod merge;
+
+/// Contains the Reader used to read MRT records from a stream.
+mod reader;
+
+/// Contains the iterator over the routes of RIB dumps.
+mod routes;
+
+/// Contains the prefix trie used for longest-prefix-match lookups against RIB dumps.
+#[cfg(feature = "trie")]
+mod trie;
+
+/// Contains the semantic validation of parsed records.
+mod validate;
+
+/// Contains the TableDumpReader which groups TABLE_DUMP records by their view.
+mod views;
+
+/// Contains the RecordVisitor used to traverse MRT records.
+mod visitor;
+
+/// Contains the Writer used to write MRT records to a stream.
+mod writer;
+
 // Re-exports to allow users more convenient access.
+pub use analysis::{peer_distribution, PeerDistribution};
+pub use bgpdump::BgpdumpRoute;
+pub use demux::demux;
+pub use error::MrtError;
+pub use events::{RouteEvent, RouteEvents};
+pub use framed::FramedReader;
+pub use kind::RecordKind;
+pub use merge::{merge_sorted, MergeSorted};
+pub use reader::{Checkpoint, Dialect, Reader, RecordBody, Records};
+pub use records::attributes;
 pub use records::bgp;
 pub use records::bgp4mp;
 pub use records::bgp4plus;
+pub use records::bmp;
 pub use records::isis;
+pub use records::nlri;
 pub use records::ospf;
 pub use records::rip;
 pub use records::tabledump;
+pub use routes::{DedupByPrefix, Route, Routes};
+#[cfg(feature = "trie")]
+pub use trie::RibTrie;
+pub use validate::{ValidationContext, ValidationWarning};
+pub use views::TableDumpReader;
+pub use visitor::RecordVisitor;
+pub use writer::{HeaderBuilder, Writer};
 
-/// Represents an Address Family Idenfitier. Currently only IPv4 and IPv6 are supported.
-#[derive(Debug)]
+/// Represents an Address Family Idenfitier. Addresses are only supported for IPv4 and IPv6,
+/// while the other families are used to identify the NLRI of RIB_GENERIC records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(non_camel_case_types)]
 #[repr(u16)]
 pub enum AFI {
     /// Internet Protocol version 4 (32 bits)
     IPV4 = 1,
     /// Internet Protocol version 6 (128 bits)
     IPV6 = 2,
+    /// Layer-2 VPN as defined in [RFC4761](https://tools.ietf.org/html/rfc4761).
+    L2VPN = 25,
+    /// BGP Link-State as defined in [RFC7752](https://tools.ietf.org/html/rfc7752).
+    BGP_LS = 16388,
 }
 
 impl AFI {
+    /// Converts the code of an address family of which the addresses are IP addresses, as is
+    /// the case for the addresses of peers and the prefixes of most records.
     fn from(value: u16) -> Result<AFI, Error> {
         match value {
             1 => Ok(AFI::IPV4),
             2 => Ok(AFI::IPV6),
+            _ => Err(Error::other(error::UnknownAfi(value))),
+        }
+    }
+
+    /// Returns the size in bytes of the an instance of this address family type, or 0 if its
+    /// addresses are not IP addresses.
+    pub fn size(&self) -> u32 {
+        match self {
+            AFI::IPV4 => 4,
+            AFI::IPV6 => 16,
+            AFI::L2VPN | AFI::BGP_LS => 0,
+        }
+    }
+
+    /// Reads an address of this address family, which must be IPv4 or IPv6.
+    pub(crate) fn read_address(&self, mut stream: impl Read) -> Result<IpAddr, Error> {
+        match self {
+            AFI::IPV4 => Ok(IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?))),
+            AFI::IPV6 => Ok(IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?))),
+            AFI::L2VPN | AFI::BGP_LS => Err(Error::other(error::UnknownAfi(self.code()))),
+        }
+    }
+
+    /// Returns the IANA code of this address family, the inverse of `AFI::try_from`.
+    pub fn code(&self) -> u16 {
+        *self as u16
+    }
+}
+
+impl TryFrom<u16> for AFI {
+    type Error = Error;
+
+    /// Converts an IANA address family code, returning an error if it is not supported.
+    fn try_from(value: u16) -> Result<AFI, Error> {
+        match value {
+            25 => Ok(AFI::L2VPN),
+            16388 => Ok(AFI::BGP_LS),
+            x => AFI::from(x),
+        }
+    }
+}
+
+///
+/// Represents a Subsequent Address Family Identifier.
+/// More information can found in [RFC4760](https://tools.ietf.org/html/rfc4760#section-6).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(non_camel_case_types)]
+#[repr(u8)]
+pub enum SAFI {
+    /// Unicast forwarding.
+    UNICAST = 1,
+    /// Multicast forwarding.
+    MULTICAST = 2,
+    /// Labeled unicast as defined in [RFC8277](https://tools.ietf.org/html/rfc8277).
+    MPLS_LABEL = 4,
+    /// Virtual Private LAN Service as defined in [RFC4761](https://tools.ietf.org/html/rfc4761).
+    VPLS = 65,
+    /// Ethernet VPN as defined in [RFC7432](https://tools.ietf.org/html/rfc7432).
+    EVPN = 70,
+    /// BGP Link-State as defined in [RFC7752](https://tools.ietf.org/html/rfc7752).
+    BGP_LS = 71,
+    /// BGP Link-State VPN as defined in [RFC7752](https://tools.ietf.org/html/rfc7752).
+    BGP_LS_VPN = 72,
+    /// Layer-3 VPN as defined in [RFC4364](https://tools.ietf.org/html/rfc4364).
+    MPLS_VPN = 128,
+    /// Flow specification as defined in [RFC8955](https://tools.ietf.org/html/rfc8955).
+    FLOWSPEC = 133,
+    /// Flow specification for VPNs as defined in [RFC8955](https://tools.ietf.org/html/rfc8955).
+    FLOWSPEC_VPN = 134,
+}
+
+impl SAFI {
+    fn from(value: u8) -> Result<SAFI, Error> {
+        match value {
+            1 => Ok(SAFI::UNICAST),
+            2 => Ok(SAFI::MULTICAST),
+            4 => Ok(SAFI::MPLS_LABEL),
+            65 => Ok(SAFI::VPLS),
+            70 => Ok(SAFI::EVPN),
+            71 => Ok(SAFI::BGP_LS),
+            72 => Ok(SAFI::BGP_LS_VPN),
+            128 => Ok(SAFI::MPLS_VPN),
+            133 => Ok(SAFI::FLOWSPEC),
+            134 => Ok(SAFI::FLOWSPEC_VPN),
             _ => {
                 let msg = format!(
-                    "Number {} does not represent a valid address family.",
+                    "Number {} does not represent a valid subsequent address family.",
                     value
                 );
-                Err(std::io::Error::new(std::io::ErrorKind::Other, msg))
+                Err(std::io::Error::other(msg))
             }
         }
     }
 
-    /// Returns the size in bytes of the an instance of this address family type.
+    /// Returns the IANA code of this subsequent address family, the inverse of `SAFI::try_from`.
+    pub fn code(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for SAFI {
+    type Error = Error;
+
+    /// Converts an IANA subsequent address family code, returning an error if it is not supported.
+    fn try_from(value: u8) -> Result<SAFI, Error> {
+        SAFI::from(value)
+    }
+}
+
+/// The number of bytes used to encode an Autonomous System Number (ASN).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsnSize {
+    /// 2-byte ASNs as originally defined in [RFC4271](https://tools.ietf.org/html/rfc4271).
+    Two,
+    /// 4-byte ASNs as defined in [RFC6793](https://tools.ietf.org/html/rfc6793).
+    Four,
+}
+
+impl AsnSize {
+    ///
+    /// Returns the ASN size used by the given BGP4MP (or BGP4MP_ET) sub-type.
+    /// Sub-types that do not carry ASNs, or are unknown, use 2-byte ASNs.
+    ///
+    pub fn for_bgp4mp_subtype(sub_type: u16) -> AsnSize {
+        match sub_type {
+            4 | 5 | 7 | 9 | 11 => AsnSize::Four,
+            _ => AsnSize::Two,
+        }
+    }
+
+    /// Returns the number of bytes used to encode an ASN of this size.
     pub fn size(&self) -> u32 {
         match self {
-            AFI::IPV4 => 4,
-            AFI::IPV6 => 16,
+            AsnSize::Two => 2,
+            AsnSize::Four => 4,
+        }
+    }
+
+    /// Reads a single ASN of this size from the stream.
+    pub(crate) fn read(&self, mut stream: impl Read) -> Result<u32, Error> {
+        match self {
+            AsnSize::Two => Ok(u32::from(stream.read_u16::<BigEndian>()?)),
+            AsnSize::Four => stream.read_u32::<BigEndian>(),
         }
     }
 }
 
 /// Represents the MRT header accompanying every MRT record.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Header {
     /// The time at which this message was generated. Represented in UNIX time.
     pub timestamp: u32,
@@ -116,8 +352,278 @@ pub struct Header {
     pub length: u32,
 }
 
+impl Header {
+    ///
+    /// Returns true if the record type uses the extended timestamp layout (BGP4MP_ET, ISIS_ET
+    /// and OSPFv3_ET). The body of these records starts with a 4-byte microsecond timestamp,
+    /// which is included in `length`.
+    ///
+    pub fn has_extended_timestamp(&self) -> bool {
+        matches!(
+            self.record_type,
+            codes::BGP4MP_ET | codes::ISIS_ET | codes::OSPFV3_ET
+        )
+    }
+
+    ///
+    /// Returns the microseconds elapsed since `timestamp` for record types with an extended
+    /// timestamp, and `None` for all other record types. Values beyond 999999 are clamped, such
+    /// that the result is always less than a second. Such values are rejected while parsing
+    /// unless `ParseOptions::strict` is disabled.
+    ///
+    pub fn subsec_micros(&self) -> Option<u32> {
+        if self.has_extended_timestamp() {
+            Some(self.extended.min(999_999))
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Compares the moments at which two records were generated, taking the microseconds of
+    /// extended timestamps into account. Records without an extended timestamp are considered
+    /// to be generated at the start of their second.
+    ///
+    pub fn cmp_time(&self, other: &Header) -> Ordering {
+        self.time_key().cmp(&other.time_key())
+    }
+
+    /// Returns the seconds and microseconds at which this record was generated.
+    fn time_key(&self) -> (u32, u32) {
+        (self.timestamp, self.subsec_micros().unwrap_or(0))
+    }
+}
+
+/// The default maximum number of ASNs in a single AS_PATH or AS4_PATH attribute.
+pub const DEFAULT_MAX_AS_PATH_LENGTH: usize = 2048;
+
+/// The default maximum number of records that [`Reader::read_all`] collects.
+pub const DEFAULT_RECORD_LIMIT: usize = 1_000_000;
+
+/// Options that control how MRT records are parsed.
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Whether the hard invariants of the MRT format, such as the maximum prefix length of an
+    /// address family, are validated while parsing. Enabled by default.
+    pub strict: bool,
+
+    /// Whether records of an unknown type are returned as `Record::UNKNOWN` containing the raw
+    /// record body instead of returning an error. Disabled by default.
+    pub skip_unknown: bool,
+
+    /// Whether records of a supported type with an unknown subtype are returned as
+    /// `Record::UNKNOWN` containing the raw record body instead of returning an error.
+    /// Disabled by default.
+    pub skip_unknown_subtypes: bool,
+
+    /// Whether text fields such as the view name of a PEER_INDEX_TABLE must be valid UTF-8.
+    /// If disabled invalid bytes are replaced by U+FFFD. Disabled by default.
+    pub strict_utf8: bool,
+
+    /// The inclusive range of timestamps a record header may contain. Records outside of this
+    /// range are reported as `MrtError::TimestampOutOfRange`, which often indicates that the
+    /// stream is misaligned. Not checked by default.
+    pub timestamp_bounds: Option<(u32, u32)>,
+
+    /// The maximum length of a record body in bytes. Records declaring a longer body are
+    /// reported as `MrtError::RecordTooLarge` before the body is read, which bounds the memory
+    /// allocated for a single record of an untrusted stream. Not checked by default.
+    pub max_record_size: Option<u32>,
+
+    /// The maximum number of ASNs in a single AS_PATH or AS4_PATH attribute. Longer paths are
+    /// rejected, as real AS paths are short and long ones indicate a crafted or corrupt input.
+    /// Defaults to [`DEFAULT_MAX_AS_PATH_LENGTH`].
+    pub max_as_path_length: usize,
+
+    /// The maximum number of records collected by `Reader::read_all` and
+    /// `Reader::group_by_type`, which report more records as `MrtError::RecordLimitExceeded`.
+    /// Defaults to [`DEFAULT_RECORD_LIMIT`].
+    pub record_limit: usize,
+
+    /// The total number of bytes a `Reader` may read from the stream, which bounds the work done
+    /// for untrusted streams regardless of how they are framed. Once reading the next record
+    /// would exceed the budget `MrtError::BudgetExceeded` is returned without reading its body.
+    /// Bytes read before a call to `Reader::seek_to` remain counted, while `Reader::reset` starts
+    /// counting anew. Not limited by default.
+    pub byte_budget: Option<u64>,
+
+    /// Whether a `Reader` keeps the bytes of the most recently read record, which are returned
+    /// by `Reader::raw_bytes`. Disabled by default.
+    pub keep_raw: bool,
+
+    /// Whether BGP4MP messages with an AFI of 0 are accepted as containing no peer and local
+    /// addresses, as written by some encoders for the locally generated subtypes. The
+    /// addresses of such messages are set to `0.0.0.0`. Disabled by default, in which case
+    /// these records are reported as `MrtError::InvalidAfi`.
+    pub allow_missing_afi: bool,
+
+    /// Whether the RIB_AFI and RIB_AFI_ADDPATH records of TABLE_DUMP_V2 must consist of exactly
+    /// the number of bytes declared by the record header. A mismatch, such as an entry with an
+    /// incorrect attribute length, is reported as an error of kind `InvalidData` instead of
+    /// being silently ignored. Disabled by default.
+    ///
+    /// Bytes left after the RIB entries are also rejected by `reject_trailing_bytes`, which
+    /// applies to all records. This option additionally reports entries reaching beyond the
+    /// record as `InvalidData` instead of `UnexpectedEof`, which tells an incorrect length
+    /// apart from a truncated stream.
+    pub exact_rib_length: bool,
+
+    /// Whether bytes following the last field of a record are reported as an error of kind
+    /// `InvalidData`. Disabled by default, in which case these bytes are skipped as padding
+    /// such that the next record is still parsed. Records reading beyond their body are
+    /// reported as `UnexpectedEof` regardless of this option.
+    pub reject_trailing_bytes: bool,
+
+    /// Whether bytes following the last field that is parsed of a record are kept in its `extra`
+    /// field, which currently applies to the PEER_INDEX_TABLE. Disabled by default, in which
+    /// case these bytes are skipped.
+    pub keep_extra: bool,
+
+    /// Whether IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`) of peers and local endpoints are
+    /// converted into IPv4 addresses using [`normalize_addr`], such that a peer encoded either
+    /// way is represented by the same address. This applies to the addresses of BGP4MP, OSPFv3
+    /// and TABLE_DUMP records and of the entries of the PEER_INDEX_TABLE. Disabled by default.
+    ///
+    /// Records of which addresses have been converted are written with the IPv4 address, and
+    /// cannot be written at all if only one of the addresses of a BGP4MP record was converted.
+    pub unmap_ipv4: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            strict: true,
+            skip_unknown: false,
+            skip_unknown_subtypes: false,
+            strict_utf8: false,
+            timestamp_bounds: None,
+            max_record_size: None,
+            max_as_path_length: DEFAULT_MAX_AS_PATH_LENGTH,
+            record_limit: DEFAULT_RECORD_LIMIT,
+            byte_budget: None,
+            keep_raw: false,
+            allow_missing_afi: false,
+            exact_rib_length: false,
+            reject_trailing_bytes: false,
+            keep_extra: false,
+            unmap_ipv4: false,
+        }
+    }
+}
+
+///
+/// Builder methods, which allow configuring the options fluently starting from the defaults:
+///
+/// ```
+/// let options = mrt_rs::ParseOptions::default()
+///     .max_record_size(1 << 20)
+///     .strict(true);
+/// let reader = mrt_rs::Reader::with_options(std::io::empty(), options);
+/// ```
+///
+impl ParseOptions {
+    /// Sets `ParseOptions::strict`.
+    pub fn strict(mut self, strict: bool) -> ParseOptions {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets `ParseOptions::skip_unknown`.
+    pub fn skip_unknown(mut self, skip_unknown: bool) -> ParseOptions {
+        self.skip_unknown = skip_unknown;
+        self
+    }
+
+    /// Sets `ParseOptions::skip_unknown_subtypes`.
+    pub fn skip_unknown_subtypes(mut self, skip_unknown_subtypes: bool) -> ParseOptions {
+        self.skip_unknown_subtypes = skip_unknown_subtypes;
+        self
+    }
+
+    /// Sets `ParseOptions::strict_utf8`.
+    pub fn strict_utf8(mut self, strict_utf8: bool) -> ParseOptions {
+        self.strict_utf8 = strict_utf8;
+        self
+    }
+
+    /// Sets `ParseOptions::timestamp_bounds` to the inclusive range between `min` and `max`.
+    pub fn timestamp_bounds(mut self, min: u32, max: u32) -> ParseOptions {
+        self.timestamp_bounds = Some((min, max));
+        self
+    }
+
+    /// Sets `ParseOptions::max_record_size`.
+    pub fn max_record_size(mut self, max_record_size: u32) -> ParseOptions {
+        self.max_record_size = Some(max_record_size);
+        self
+    }
+
+    /// Sets `ParseOptions::max_as_path_length`.
+    pub fn max_as_path_length(mut self, max_as_path_length: usize) -> ParseOptions {
+        self.max_as_path_length = max_as_path_length;
+        self
+    }
+
+    /// Sets `ParseOptions::record_limit`.
+    pub fn record_limit(mut self, record_limit: usize) -> ParseOptions {
+        self.record_limit = record_limit;
+        self
+    }
+
+    /// Sets `ParseOptions::byte_budget`.
+    pub fn byte_budget(mut self, byte_budget: u64) -> ParseOptions {
+        self.byte_budget = Some(byte_budget);
+        self
+    }
+
+    /// Sets `ParseOptions::keep_raw`.
+    pub fn keep_raw(mut self, keep_raw: bool) -> ParseOptions {
+        self.keep_raw = keep_raw;
+        self
+    }
+
+    /// Sets `ParseOptions::allow_missing_afi`.
+    pub fn allow_missing_afi(mut self, allow_missing_afi: bool) -> ParseOptions {
+        self.allow_missing_afi = allow_missing_afi;
+        self
+    }
+
+    /// Sets `ParseOptions::exact_rib_length`.
+    pub fn exact_rib_length(mut self, exact_rib_length: bool) -> ParseOptions {
+        self.exact_rib_length = exact_rib_length;
+        self
+    }
+
+    /// Sets `ParseOptions::reject_trailing_bytes`.
+    pub fn reject_trailing_bytes(mut self, reject_trailing_bytes: bool) -> ParseOptions {
+        self.reject_trailing_bytes = reject_trailing_bytes;
+        self
+    }
+
+    /// Sets `ParseOptions::keep_extra`.
+    pub fn keep_extra(mut self, keep_extra: bool) -> ParseOptions {
+        self.keep_extra = keep_extra;
+        self
+    }
+
+    /// Sets `ParseOptions::unmap_ipv4`.
+    pub fn unmap_ipv4(mut self, unmap_ipv4: bool) -> ParseOptions {
+        self.unmap_ipv4 = unmap_ipv4;
+        self
+    }
+}
+
+///
 /// Represents a single MRT record.
-#[derive(Debug)]
+///
+/// The deprecated record types NULL, START, DIE, I_AM_DEAD and IDRP are represented by unit
+/// variants. Any body they declare is skipped while reading.
+///
+/// BGP4PLUS and BGP4PLUS_01 records share the same encoding and are both parsed into a
+/// [`BGP4PLUS`](records::bgp4plus::BGP4PLUS). BGP4PLUS_01 was introduced for a later draft of
+/// the Multiprotocol Extensions to BGP-4 which only differs in the BGP messages it contains.
+///
+#[derive(Debug, PartialEq)]
 #[allow(missing_docs)]
 #[allow(non_camel_case_types)]
 pub enum Record {
@@ -125,7 +631,7 @@ pub enum Record {
     START,
     DIE,
     I_AM_DEAD,
-    PEER_DOWN,
+    PEER_DOWN(PeerDown),
     BGP(records::bgp::BGP),
     RIP(records::rip::RIP),
     IDRP,
@@ -141,6 +647,191 @@ pub enum Record {
     ISIS_ET(Vec<u8>),
     OSPFv3(records::ospf::OSPFv3),
     OSPFv3_ET(records::ospf::OSPFv3),
+
+    /// The body of a record type or subtype that is not supported by this crate, excluding the
+    /// extended timestamp of a subtype of BGP4MP_ET. Only returned by a `Reader` that has been
+    /// configured to skip unknown record types or subtypes.
+    UNKNOWN(Vec<u8>),
+}
+
+impl Record {
+    ///
+    /// Parses a single MRT record from the front of a byte slice.
+    /// Returns the header, the record and the number of bytes occupied by the record.
+    ///
+    /// # Examples
+    /// ```
+    /// let bytes = std::fs::read("res/bird-mrtdump_bgp").unwrap();
+    /// let (_, _, consumed) = mrt_rs::Record::parse_one(&bytes).unwrap();
+    /// let (header, _, _) = mrt_rs::Record::parse_one(&bytes[consumed..]).unwrap();
+    /// assert_eq!(header.record_type, 16);
+    /// ```
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// An error of kind `UnexpectedEof` is returned if the slice does not contain an entire record.
+    /// If an ill-formatted record is provided behavior will be undefined.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn parse_one(bytes: &[u8]) -> Result<(Header, Record, usize), MrtError> {
+        let mut stream = bytes;
+        let mut header = match read_header(&mut stream)? {
+            Some(header) => header,
+            None => return Err(Error::from(ErrorKind::UnexpectedEof).into()),
+        };
+
+        let length = header.length as usize;
+        if stream.len() < length {
+            return Err(Error::from(ErrorKind::UnexpectedEof).into());
+        }
+
+        let mut body = &stream[..length];
+        let record = parse_record(&mut header, &mut body, &ParseOptions::default())?;
+        Ok((header, record, bytes.len() - stream.len() + length))
+    }
+
+    ///
+    /// # Summary
+    /// Encodes this record, including its MRT header, into the form in which it appears in a
+    /// stream. For a record that was read using default options this reproduces the original
+    /// bytes, which allows validating the parser against other implementations.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// An error of kind `InvalidInput` is returned if the record does not retain enough data to
+    /// reproduce the `length` of the given header. This is the case for the deprecated record
+    /// types whose body is skipped and for records followed by padding. A header with a length
+    /// of zero, as constructed by a `HeaderBuilder`, is never checked.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn to_bytes(&self, header: &Header) -> Result<Vec<u8>, MrtError> {
+        let mut writer = Writer::new(Vec::new());
+        let written = writer.write(header, self)?;
+        if header.length != 0 && written.length != header.length {
+            let msg = format!(
+                "Record of type {} and subtype {} encodes to {} instead of {} bytes.",
+                header.record_type, header.sub_type, written.length, header.length
+            );
+            return Err(Error::new(ErrorKind::InvalidInput, msg).into());
+        }
+        Ok(writer.stream)
+    }
+
+    ///
+    /// # Summary
+    /// Returns the number of bytes of the body of this record when it is written, which is the
+    /// `length` of its MRT header. For record types with an extended timestamp this includes the
+    /// 4 bytes of the timestamp. The record is encoded without allocating a buffer.
+    ///
+    /// The deprecated record types whose body is skipped while reading cannot be encoded
+    /// losslessly, so like `to_bytes` an error is returned for them.
+    ///
+    /// # Examples
+    /// ```
+    /// use mrt_rs::Reader;
+    ///
+    /// let mut reader = Reader::new(std::fs::File::open("res/quagga_bgp").unwrap());
+    /// while let Some((header, record)) = reader.read().unwrap() {
+    ///     assert_eq!(record.wire_len().unwrap(), header.length);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// An error of kind `InvalidInput` is returned if the record cannot be serialized, such as
+    /// when a field exceeds the size that can be encoded or the body exceeds 4 GiB, and for the
+    /// NULL, START, DIE, I_AM_DEAD and IDRP records of which the body is not retained.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn wire_len(&self) -> Result<u32, MrtError> {
+        if let Record::NULL | Record::START | Record::DIE | Record::I_AM_DEAD | Record::IDRP = self
+        {
+            let msg = format!("{:?} records do not retain their body.", self);
+            return Err(Error::new(ErrorKind::InvalidInput, msg).into());
+        }
+
+        // The extended timestamp is taken from the header, but its value does not affect the length.
+        let header = HeaderBuilder::new(0, 0).build();
+        let mut counter = writer::ByteCounter::default();
+        write_record(&header, self, &mut counter)?;
+        let length = u32::try_from(counter.count)
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "MRT record is too large."))?;
+        Ok(length)
+    }
+
+    ///
+    /// # Summary
+    /// Returns the type and subtype of the MRT header of this record, being the inverse of the
+    /// dispatch done while reading. Record types of which the subtype is not used while parsing
+    /// return subtype 0, and TABLE_DUMP records return the subtype matching the address family of
+    /// their prefix. `None` is returned for `Record::UNKNOWN`, of which the type and subtype are
+    /// not retained.
+    ///
+    /// The subtypes of the BGP, BGP4PLUS, BGP4MP and TABLE_DUMP_V2 records are also returned by
+    /// the `sub_type` method of their enums, which cannot tell the record type as it is shared
+    /// between record types such as BGP4MP and BGP4MP_ET.
+    ///
+    /// # Examples
+    /// ```
+    /// use mrt_rs::{HeaderBuilder, Reader, Writer};
+    ///
+    /// let mut reader = Reader::new(std::fs::File::open("res/quagga_bgp").unwrap());
+    /// let mut writer = Writer::new(Vec::new());
+    /// while let Some((header, record)) = reader.read().unwrap() {
+    ///     let (record_type, sub_type) = record.wire_type().unwrap();
+    ///     let builder = HeaderBuilder::new(record_type, sub_type).timestamp(header.timestamp);
+    ///     writer.write(&builder.build(), &record).unwrap();
+    /// }
+    /// assert_eq!(writer.stream, std::fs::read("res/quagga_bgp").unwrap());
+    /// ```
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn wire_type(&self) -> Option<(u16, u16)> {
+        let wire_type = match self {
+            Record::NULL => (codes::NULL, 0),
+            Record::START => (codes::START, 0),
+            Record::DIE => (codes::DIE, 0),
+            Record::I_AM_DEAD => (codes::I_AM_DEAD, 0),
+            Record::PEER_DOWN(_) => (codes::PEER_DOWN, 0),
+            Record::BGP(x) => (codes::BGP, x.sub_type()),
+            Record::RIP(_) => (codes::RIP, 0),
+            Record::IDRP => (codes::IDRP, 0),
+            Record::RIPNG(_) => (codes::RIPNG, 0),
+            Record::BGP4PLUS(x) => (codes::BGP4PLUS, x.sub_type()),
+            Record::BGP4PLUS_01(x) => (codes::BGP4PLUS_01, x.sub_type()),
+            Record::OSPFv2(_) => (codes::OSPFV2, 0),
+            Record::TABLE_DUMP(x) => match x.prefix {
+                IpAddr::V4(_) => (codes::TABLE_DUMP, codes::table_dump::AFI_IPV4),
+                IpAddr::V6(_) => (codes::TABLE_DUMP, codes::table_dump::AFI_IPV6),
+            },
+            Record::TABLE_DUMP_V2(x) => (codes::TABLE_DUMP_V2, x.sub_type()),
+            Record::BGP4MP(x) => (codes::BGP4MP, x.sub_type()),
+            Record::BGP4MP_ET(x) => (codes::BGP4MP_ET, x.sub_type()),
+            Record::ISIS(_) => (codes::ISIS, 0),
+            Record::ISIS_ET(_) => (codes::ISIS_ET, 0),
+            Record::OSPFv3(_) => (codes::OSPFV3, 0),
+            Record::OSPFv3_ET(_) => (codes::OSPFV3_ET, 0),
+            Record::UNKNOWN(_) => return None,
+        };
+        Some(wire_type)
+    }
 }
 
 ///
@@ -157,6 +848,17 @@ pub enum Record {
 /// This function does not make use of unsafe code.
 ///
 pub fn read(mut stream: &mut impl Read) -> Result<Option<(Header, Record)>, Error> {
+    let mut header = match read_header(&mut stream)? {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+
+    let record = parse_record(&mut header, &mut stream, &ParseOptions::default())?;
+    Ok(Some((header, record)))
+}
+
+/// Reads the next MRT header in the stream. Returns None if the end of the stream has been reached.
+pub(crate) fn read_header(stream: &mut impl Read) -> Result<Option<Header>, Error> {
     let result = stream.read_u32::<BigEndian>();
 
     // Check if an EOF has occurred at the beginning of the stream and return None
@@ -168,83 +870,394 @@ pub fn read(mut stream: &mut impl Read) -> Result<Option<(Header, Record)>, Erro
     };
 
     // Parse the MRTHeader
-    let mut header = Header {
+    Ok(Some(Header {
         timestamp,
         extended: 0,
         record_type: stream.read_u16::<BigEndian>()?,
         sub_type: stream.read_u16::<BigEndian>()?,
         length: stream.read_u32::<BigEndian>()?,
-    };
+    }))
+}
+
+/// Represents the data contained in the deprecated PEER_DOWN record type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerDown {
+    /// The reason why the peer went down.
+    pub reason: u8,
+
+    /// Any additional data describing why the peer went down.
+    pub data: Vec<u8>,
+}
+
+impl PeerDown {
+    ///
+    /// # Summary
+    /// Used to parse PEER_DOWN MRT records.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from the stream.
+    /// An error of kind `InvalidData` is returned if the record has no body.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn parse(header: &Header, mut stream: impl Read) -> Result<PeerDown, Error> {
+        let length = header.length.checked_sub(1).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                "PEER_DOWN record does not contain a reason.",
+            )
+        })?;
+
+        let reason = stream.read_u8()?;
+        let mut data = vec![0; length as usize];
+        stream.read_exact(&mut data)?;
+        Ok(PeerDown { reason, data })
+    }
+
+    pub(crate) fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u8(self.reason)?;
+        stream.write_all(&self.data)
+    }
+}
+
+/// Returns an error if bytes remain after parsing a structure using `from_bytes`.
+pub(crate) fn ensure_consumed(remaining: &[u8], name: &str) -> Result<(), Error> {
+    if remaining.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("{} bytes remain after parsing {}.", remaining.len(), name),
+        ))
+    }
+}
+
+/// Returns the length of the message following the fixed fields of the given size. The length of
+/// the header excludes the extended timestamp of ET records, which has already been read.
+pub(crate) fn message_length(header: &Header, fixed: u32) -> Result<usize, Error> {
+    match header.length.checked_sub(fixed) {
+        Some(length) => Ok(length as usize),
+        None => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Record length {} is shorter than the {} bytes of its fixed fields.",
+                header.length, fixed
+            ),
+        )),
+    }
+}
+
+/// Reads a NUL-terminated string of at most `length` bytes and returns it without the NUL,
+/// together with whether the NUL was present. A string that is not terminated within `length`
+/// bytes ends after `length` bytes, such that a missing terminator cannot make the parser read
+/// beyond the end of the record.
+pub(crate) fn read_nul_terminated(
+    stream: &mut impl Read,
+    length: u32,
+) -> Result<(Vec<u8>, bool), Error> {
+    let mut bytes = Vec::new();
+    for _ in 0..length {
+        match stream.read_u8()? {
+            b'\0' => return Ok((bytes, true)),
+            byte => bytes.push(byte),
+        }
+    }
+    Ok((bytes, false))
+}
+
+/// Consumes the body of a deprecated record type whose contents are not represented.
+fn skip_body(header: &Header, stream: &mut impl Read) -> Result<(), Error> {
+    let length = u64::from(header.length);
+    let skipped = std::io::copy(&mut stream.take(length), &mut std::io::sink())?;
+    if skipped != length {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "Stream ended in the middle of an MRT record.",
+        ));
+    }
+    Ok(())
+}
+
+/// Parses the body of the MRT record described by the given header.
+pub(crate) fn parse_record(
+    header: &mut Header,
+    stream: &mut impl Read,
+    options: &ParseOptions,
+) -> Result<Record, Error> {
+    // Some encoders pad records with fixed layouts, so any bytes the parser did not consume are
+    // skipped to keep the stream aligned at the start of the next record.
+    let mut body = stream.take(u64::from(header.length));
+    let mut record = parse_timestamped(header, &mut body, options)?;
+    if options.reject_trailing_bytes && body.limit() != 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "MRT record contains {} bytes after its last field.",
+                body.limit()
+            ),
+        ));
+    }
+    std::io::copy(&mut body, &mut std::io::sink())?;
+    if body.limit() != 0 {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "Stream ended in the middle of an MRT record.",
+        ));
+    }
+    if options.unmap_ipv4 {
+        unmap_ipv4(&mut record);
+    }
+    Ok(record)
+}
+
+///
+/// Converts an IPv4-mapped IPv6 address (`::ffff:a.b.c.d`) into the IPv4 address it represents.
+/// All other addresses are returned unchanged.
+///
+/// # Examples
+/// ```
+/// use std::net::IpAddr;
+///
+/// let mapped: IpAddr = "::ffff:192.0.2.1".parse().unwrap();
+/// assert_eq!(mrt_rs::normalize_addr(mapped), "192.0.2.1".parse::<IpAddr>().unwrap());
+/// ```
+///
+pub fn normalize_addr(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(x) => match x.to_ipv4_mapped() {
+            Some(ipv4) => IpAddr::V4(ipv4),
+            None => addr,
+        },
+        IpAddr::V4(_) => addr,
+    }
+}
+
+/// Converts the IPv4-mapped IPv6 addresses of the peers and local endpoints of a record.
+fn unmap_ipv4(record: &mut Record) {
+    use records::bgp4mp::BGP4MP;
+    use records::tabledump::TABLE_DUMP_V2;
+
+    fn unmap(addr: &mut IpAddr) {
+        *addr = normalize_addr(*addr);
+    }
+
+    match record {
+        Record::BGP4MP(x) | Record::BGP4MP_ET(x) => match x {
+            BGP4MP::STATE_CHANGE(y) => {
+                unmap(&mut y.peer_address);
+                unmap(&mut y.local_address);
+            }
+            BGP4MP::STATE_CHANGE_AS4(y) => {
+                unmap(&mut y.peer_address);
+                unmap(&mut y.local_address);
+            }
+            BGP4MP::MESSAGE(y)
+            | BGP4MP::MESSAGE_LOCAL(y)
+            | BGP4MP::MESSAGE_ADDPATH(y)
+            | BGP4MP::MESSAGE_LOCAL_ADDPATH(y) => {
+                unmap(&mut y.peer_address);
+                unmap(&mut y.local_address);
+            }
+            BGP4MP::MESSAGE_AS4(y)
+            | BGP4MP::MESSAGE_AS4_LOCAL(y)
+            | BGP4MP::MESSAGE_AS4_ADDPATH(y)
+            | BGP4MP::MESSAGE_AS4_LOCAL_ADDPATH(y) => {
+                unmap(&mut y.peer_address);
+                unmap(&mut y.local_address);
+            }
+            BGP4MP::ENTRY(y) => {
+                unmap(&mut y.peer_address);
+                unmap(&mut y.local_address);
+            }
+            BGP4MP::SNAPSHOT(_) => (),
+        },
+        Record::OSPFv3(x) | Record::OSPFv3_ET(x) => {
+            unmap(&mut x.remote);
+            unmap(&mut x.local);
+        }
+        Record::TABLE_DUMP(x) => unmap(&mut x.peer_address),
+        Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(x)) => {
+            for entry in &mut x.peer_entries {
+                unmap(&mut entry.peer_ip_address);
+                // The peer type tells whether the address is encoded as an IPv6 address.
+                if entry.peer_ip_address.is_ipv4() {
+                    entry.peer_type &= !1;
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Parses the extended timestamp, if present, followed by the body of the record.
+fn parse_timestamped(
+    header: &mut Header,
+    stream: &mut impl Read,
+    options: &ParseOptions,
+) -> Result<Record, Error> {
+    if header.has_extended_timestamp() {
+        if header.length < 4 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "MRT record is too short to contain an extended timestamp.",
+            ));
+        }
+        header.extended = stream.read_u32::<BigEndian>()?;
+        if options.strict && header.extended > 999_999 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Extended timestamp contains more than a second of microseconds: {}",
+                    header.extended
+                ),
+            ));
+        }
+
+        // The parsers of the record bodies expect the length to exclude the extended timestamp.
+        let body = Header {
+            timestamp: header.timestamp,
+            extended: header.extended,
+            record_type: header.record_type,
+            sub_type: header.sub_type,
+            length: header.length - 4,
+        };
+        return parse_body(&body, stream, options);
+    }
+
+    parse_body(header, stream, options)
+}
+
+/// Parses the body of a record, excluding the extended timestamp, described by the header.
+fn parse_body(
+    header: &Header,
+    mut stream: &mut impl Read,
+    options: &ParseOptions,
+) -> Result<Record, Error> {
+    if options.skip_unknown_subtypes && !is_known_sub_type(header.record_type, header.sub_type) {
+        let mut body = vec![0; header.length as usize];
+        stream.read_exact(&mut body)?;
+        return Ok(Record::UNKNOWN(body));
+    }
 
     match header.record_type {
-        0 => Ok(Some((header, Record::NULL))),
-        1 => Ok(Some((header, Record::START))),
-        2 => Ok(Some((header, Record::DIE))),
-        3 => Ok(Some((header, Record::I_AM_DEAD))),
-        4 => Ok(Some((header, Record::PEER_DOWN))),
-        5 => {
-            let record = records::bgp::BGP::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::BGP(record))))
-        }
-        6 => {
-            let record = records::rip::RIP::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::RIP(record))))
-        }
-        7 => Ok(Some((header, Record::IDRP))),
-        8 => {
-            let record = records::rip::RIPNG::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::RIPNG(record))))
-        }
-        9 => {
-            let record = records::bgp4plus::BGP4PLUS::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::BGP4PLUS(record))))
-        }
-        10 => {
-            let record = records::bgp4plus::BGP4PLUS::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::BGP4PLUS_01(record))))
-        }
-        11 => {
-            let record = records::ospf::OSPFv2::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::OSPFv2(record))))
-        }
-        12 => {
-            let record = records::tabledump::TABLE_DUMP::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::TABLE_DUMP(record))))
-        }
-        13 => {
-            let record = records::tabledump::TABLE_DUMP_V2::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::TABLE_DUMP_V2(record))))
-        }
-        16 => {
-            let record = records::bgp4mp::BGP4MP::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::BGP4MP(record))))
-        }
-        17 => {
-            header.extended = stream.read_u32::<BigEndian>()?;
-            let record = records::bgp4mp::BGP4MP::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::BGP4MP_ET(record))))
-        }
-        32 => {
-            let record = records::isis::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::ISIS(record))))
-        }
-        33 => {
-            header.extended = stream.read_u32::<BigEndian>()?;
-            let record = records::isis::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::ISIS_ET(record))))
-        }
-        48 => {
-            let record = records::ospf::OSPFv3::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::OSPFv3(record))))
-        }
-        49 => {
-            header.extended = stream.read_u32::<BigEndian>()?;
-            let record = records::ospf::OSPFv3::parse(&header, &mut stream)?;
-            Ok(Some((header, Record::OSPFv3_ET(record))))
-        }
-        x => Err(Error::new(
-            ErrorKind::Other,
-            format!("Unknown record type found in MRT header: {}", x),
+        codes::NULL => skip_body(header, stream).map(|_| Record::NULL),
+        codes::START => skip_body(header, stream).map(|_| Record::START),
+        codes::DIE => skip_body(header, stream).map(|_| Record::DIE),
+        codes::I_AM_DEAD => skip_body(header, stream).map(|_| Record::I_AM_DEAD),
+        codes::PEER_DOWN => Ok(Record::PEER_DOWN(PeerDown::parse(header, &mut stream)?)),
+        codes::BGP => Ok(Record::BGP(records::bgp::BGP::parse(header, &mut stream)?)),
+        codes::RIP => Ok(Record::RIP(records::rip::RIP::parse(header, &mut stream)?)),
+        codes::IDRP => skip_body(header, stream).map(|_| Record::IDRP),
+        codes::RIPNG => Ok(Record::RIPNG(records::rip::RIPNG::parse(
+            header,
+            &mut stream,
+        )?)),
+        codes::BGP4PLUS => Ok(Record::BGP4PLUS(records::bgp4plus::BGP4PLUS::parse(
+            header,
+            &mut stream,
+        )?)),
+        codes::BGP4PLUS_01 => Ok(Record::BGP4PLUS_01(records::bgp4plus::BGP4PLUS::parse(
+            header,
+            &mut stream,
+        )?)),
+        codes::OSPFV2 => Ok(Record::OSPFv2(records::ospf::OSPFv2::parse(
+            header,
+            &mut stream,
+        )?)),
+        codes::TABLE_DUMP => Ok(Record::TABLE_DUMP(records::tabledump::TABLE_DUMP::parse(
+            header,
+            &mut stream,
+        )?)),
+        codes::TABLE_DUMP_V2 => Ok(Record::TABLE_DUMP_V2(
+            records::tabledump::TABLE_DUMP_V2::parse_with_options(header, &mut stream, options)?,
         )),
+        codes::BGP4MP => Ok(Record::BGP4MP(records::bgp4mp::BGP4MP::parse(
+            header,
+            &mut stream,
+            options,
+        )?)),
+        codes::BGP4MP_ET => Ok(Record::BGP4MP_ET(records::bgp4mp::BGP4MP::parse(
+            header,
+            &mut stream,
+            options,
+        )?)),
+        codes::ISIS => Ok(Record::ISIS(records::isis::parse(header, &mut stream)?)),
+        codes::ISIS_ET => Ok(Record::ISIS_ET(records::isis::parse(header, &mut stream)?)),
+        codes::OSPFV3 => Ok(Record::OSPFv3(records::ospf::OSPFv3::parse(
+            header,
+            &mut stream,
+        )?)),
+        codes::OSPFV3_ET => Ok(Record::OSPFv3_ET(records::ospf::OSPFv3::parse(
+            header,
+            &mut stream,
+        )?)),
+        _ if options.skip_unknown => {
+            // The layout of unknown record types is unknown, including whether an extended
+            // timestamp is present, so the entire body is consumed as-is.
+            let mut body = vec![0; header.length as usize];
+            stream.read_exact(&mut body)?;
+            Ok(Record::UNKNOWN(body))
+        }
+        x => Err(Error::other(format!(
+            "Unknown record type found in MRT header: {}",
+            x
+        ))),
+    }
+}
+
+/// Returns false if the record type is supported but does not define the given subtype.
+fn is_known_sub_type(record_type: u16, sub_type: u16) -> bool {
+    use codes::{bgp, bgp4mp, table_dump, table_dump_v2};
+
+    match record_type {
+        codes::BGP | codes::BGP4PLUS | codes::BGP4PLUS_01 => sub_type <= bgp::KEEPALIVE,
+        codes::TABLE_DUMP => matches!(sub_type, table_dump::AFI_IPV4 | table_dump::AFI_IPV6),
+        codes::TABLE_DUMP_V2 => matches!(
+            sub_type,
+            table_dump_v2::PEER_INDEX_TABLE..=table_dump_v2::RIB_GENERIC
+                | table_dump_v2::RIB_IPV4_UNICAST_ADDPATH..=table_dump_v2::RIB_GENERIC_ADDPATH
+        ),
+        codes::BGP4MP | codes::BGP4MP_ET => sub_type <= bgp4mp::MESSAGE_AS4_LOCAL_ADDPATH,
+        _ => true,
+    }
+}
+
+/// Serializes the body of an MRT record, including the extended timestamp if present.
+pub(crate) fn write_record(
+    header: &Header,
+    record: &Record,
+    mut stream: &mut impl Write,
+) -> Result<(), Error> {
+    match record {
+        Record::NULL | Record::START | Record::DIE | Record::I_AM_DEAD => Ok(()),
+        Record::IDRP => Ok(()),
+        Record::PEER_DOWN(x) => x.write(&mut stream),
+        Record::BGP(x) => x.write(&mut stream),
+        Record::RIP(x) => x.write(&mut stream),
+        Record::RIPNG(x) => x.write(&mut stream),
+        Record::BGP4PLUS(x) | Record::BGP4PLUS_01(x) => x.write(&mut stream),
+        Record::OSPFv2(x) => x.write(&mut stream),
+        Record::TABLE_DUMP(x) => x.write(&mut stream),
+        Record::TABLE_DUMP_V2(x) => x.write(&mut stream),
+        Record::BGP4MP(x) => x.write(&mut stream),
+        Record::BGP4MP_ET(x) => {
+            stream.write_u32::<BigEndian>(header.extended)?;
+            x.write(&mut stream)
+        }
+        Record::ISIS(x) => stream.write_all(x),
+        Record::ISIS_ET(x) => {
+            stream.write_u32::<BigEndian>(header.extended)?;
+            stream.write_all(x)
+        }
+        Record::OSPFv3(x) => x.write(&mut stream),
+        Record::OSPFv3_ET(x) => {
+            stream.write_u32::<BigEndian>(header.extended)?;
+            x.write(&mut stream)
+        }
+        Record::UNKNOWN(x) => stream.write_all(x),
     }
 }
@@ -0,0 +1,118 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+use std::io::Read;
+
+use crate::{Header, MrtError, Reader, Record};
+
+///
+/// Merges multiple MRT streams that are each ordered by time into a single chronologically
+/// ordered stream, as used to combine the files of multiple collectors. Records with the same
+/// time are returned in the order of the readers they were read from. Only the next record of
+/// every reader is kept in memory.
+///
+/// # Examples
+/// ```
+/// use std::fs::File;
+/// use mrt_rs::Reader;
+///
+/// let readers = vec![
+///     Reader::new(File::open("res/bird_bgp").unwrap()),
+///     Reader::new(File::open("res/bird6_bgp").unwrap()),
+/// ];
+/// for record in mrt_rs::merge_sorted(readers) {
+///     let (header, _) = record.unwrap();
+///     println!("{}", header.timestamp);
+/// }
+/// ```
+///
+pub fn merge_sorted<R: Read>(readers: Vec<Reader<R>>) -> MergeSorted<R> {
+    MergeSorted {
+        readers,
+        heads: Vec::new(),
+        queue: BinaryHeap::new(),
+        errors: VecDeque::new(),
+        started: false,
+    }
+}
+
+///
+/// An iterator over the records of multiple MRT streams in chronological order, as returned by
+/// [`merge_sorted`]. A reader that returns an error is not read any further, while the records
+/// of the other readers are still returned.
+///
+#[derive(Debug)]
+pub struct MergeSorted<R: Read> {
+    readers: Vec<Reader<R>>,
+
+    /// The next record of every reader, or `None` if the reader is exhausted.
+    heads: Vec<Option<(Header, Record)>>,
+
+    /// The indices of the readers with a pending record, ordered by the time of that record.
+    queue: BinaryHeap<Reverse<Position>>,
+
+    /// Errors that have not been returned yet.
+    errors: VecDeque<MrtError>,
+
+    started: bool,
+}
+
+impl<R: Read> MergeSorted<R> {
+    /// Reads the next record of the reader with the given index into its head.
+    fn advance(&mut self, index: usize) {
+        match self.readers[index].read() {
+            Ok(Some((header, record))) => {
+                self.queue.push(Reverse(Position { header, index }));
+                self.heads[index] = Some((header, record));
+            }
+            Ok(None) => self.heads[index] = None,
+            Err(e) => {
+                self.heads[index] = None;
+                self.errors.push_back(e);
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for MergeSorted<R> {
+    type Item = Result<(Header, Record), MrtError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            self.heads = (0..self.readers.len()).map(|_| None).collect();
+            for index in 0..self.readers.len() {
+                self.advance(index);
+            }
+        }
+
+        if let Some(e) = self.errors.pop_front() {
+            return Some(Err(e));
+        }
+
+        let Reverse(Position { index, .. }) = self.queue.pop()?;
+        let head = self.heads[index].take();
+        self.advance(index);
+        head.map(Ok)
+    }
+}
+
+/// The time of the next record of a reader, ordered by time and then by the index of the reader.
+#[derive(Debug, PartialEq, Eq)]
+struct Position {
+    header: Header,
+    index: usize,
+}
+
+impl Ord for Position {
+    fn cmp(&self, other: &Position) -> Ordering {
+        self.header
+            .cmp_time(&other.header)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+impl PartialOrd for Position {
+    fn partial_cmp(&self, other: &Position) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
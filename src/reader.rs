@@ -0,0 +1,1260 @@
+use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
+use std::collections::HashMap;
+use std::io::{BufRead, Cursor, Error, ErrorKind, Read, Seek, SeekFrom, Take};
+
+use crate::tabledump::{PEER_INDEX_TABLE, TABLE_DUMP_V2};
+use crate::{
+    codes, AsnSize, Header, MrtError, ParseOptions, Record, RecordKind, RecordVisitor, RouteEvents,
+    Routes,
+};
+
+/// The number of records whose headers are inspected by [`Reader::detect_dialect`].
+const DIALECT_SAMPLE_SIZE: usize = 32;
+
+///
+/// The family of record types used by a stream and the optional features it uses, as detected
+/// by [`Reader::detect_dialect`] from the record headers. Collectors differ in the record types
+/// they emit and in their use of 4-byte ASNs, ADD-PATH and extended timestamps. The collector
+/// itself is not identified, as collectors writing the same record types cannot be told apart by
+/// their headers.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// BGP messages and state changes as written by BIRD, OpenBGPD, Quagga and FRR.
+    Bgp4mp {
+        /// Whether records of type BGP4MP_ET with microsecond timestamps are used.
+        extended_timestamp: bool,
+
+        /// Whether sub-types carrying 4-byte ASNs are used.
+        as4: bool,
+
+        /// Whether the ADD-PATH sub-types of [RFC8050](https://tools.ietf.org/html/rfc8050) are used.
+        add_path: bool,
+    },
+
+    /// RIB dumps in the TABLE_DUMP_V2 format.
+    TableDumpV2 {
+        /// Whether the ADD-PATH sub-types of [RFC8050](https://tools.ietf.org/html/rfc8050) are used.
+        add_path: bool,
+    },
+
+    /// RIB dumps in the legacy TABLE_DUMP format, as written by older versions of OpenBGPD.
+    TableDump,
+
+    /// The stream is empty or starts with a record type that is not recognized. Such streams
+    /// may still be read using `ParseOptions::skip_unknown`.
+    Unknown,
+}
+
+///
+/// Reads MRT records from an underlying stream.
+///
+/// # Examples
+/// ```
+/// use std::fs::File;
+/// use mrt_rs::Reader;
+///
+/// let file = File::open("res/bird-mrtdump_bgp").unwrap();
+/// let mut reader = Reader::new(file);
+///
+/// while let Some((header, record)) = reader.read().unwrap() {
+///     println!("{:?}: {:?}", header, record);
+/// }
+/// ```
+///
+/// Any `Read` stream, such as a decompressing decoder, supports reading records in order.
+/// Random access using `seek_to`, `reset`, `detect_dialect` and `headers` is only available
+/// if the stream also implements `Seek`, such as a `File`:
+/// ```
+/// use std::fs::{self, File};
+/// use mrt_rs::Reader;
+///
+/// // A byte slice can only be read sequentially.
+/// let bytes = fs::read("res/bird-mrtdump_bgp").unwrap();
+/// let mut reader = Reader::new(bytes.as_slice());
+/// assert!(reader.read().unwrap().is_some());
+///
+/// // A file can be indexed and records can be read at any offset.
+/// let mut reader = Reader::new(File::open("res/bird-mrtdump_bgp").unwrap());
+/// let headers = reader.headers().unwrap();
+/// reader.seek_to(headers[3].0).unwrap();
+/// assert_eq!(reader.read().unwrap().unwrap().0, headers[3].1);
+/// ```
+///
+/// Seeking in a stream that does not implement `Seek` is rejected at compile time:
+/// ```compile_fail
+/// let bytes = std::fs::read("res/bird-mrtdump_bgp").unwrap();
+/// let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+/// reader.seek_to(0).unwrap();
+/// ```
+///
+#[derive(Debug)]
+pub struct Reader<T: Read> {
+    /// The stream from which MRT records are read.
+    pub stream: T,
+
+    /// The options used while parsing records.
+    options: ParseOptions,
+
+    /// Scratch buffer holding the body of the current record. It is reused between records and
+    /// keeps the capacity of the largest record seen.
+    buffer: Vec<u8>,
+
+    /// The offset in the stream of the next record.
+    offset: u64,
+
+    /// The header of the most recently read record.
+    last_header: Option<Header>,
+
+    /// The number of bytes that may be read from the stream in total.
+    budget: ByteBudget,
+
+    /// The header and body of the most recently read record if `ParseOptions::keep_raw` is set.
+    raw: Vec<u8>,
+
+    /// The offset of the most recently read PEER_INDEX_TABLE record.
+    peer_index_table: Option<u64>,
+}
+
+impl<T: Read> Reader<T> {
+    /// Creates a new Reader that reads MRT records from the given stream.
+    pub fn new(stream: T) -> Reader<T> {
+        Reader::with_options(stream, ParseOptions::default())
+    }
+
+    /// Creates a new Reader that reads MRT records from the given stream using the given options.
+    pub fn with_options(stream: T, options: ParseOptions) -> Reader<T> {
+        Reader {
+            stream,
+            buffer: Vec::new(),
+            offset: 0,
+            last_header: None,
+            budget: ByteBudget {
+                limit: options.byte_budget,
+                used: 0,
+            },
+            options,
+            raw: Vec::new(),
+            peer_index_table: None,
+        }
+    }
+
+    ///
+    /// Returns the offset in the stream of the next record, being the number of bytes consumed
+    /// by this Reader since it was created. After `seek_to` it is relative to the start of the
+    /// stream instead.
+    ///
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the options used while parsing records.
+    pub(crate) fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    ///
+    /// Returns the position of this Reader, which allows continuing to read the stream later
+    /// using `Reader::resume`, for example after a restart of the process. Should be called
+    /// between records, as the position after a failed read may lie within a record.
+    ///
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            offset: self.offset,
+            peer_index_table: self.peer_index_table,
+        }
+    }
+
+    ///
+    /// Returns the header of the most recently read record, or `None` if no record has been
+    /// read yet. The header is stored before the body is parsed, so after a read failed it
+    /// identifies the offending record. The extended timestamp of BGP4MP_ET, ISIS_ET and
+    /// OSPFv3_ET records is part of the body and is therefore not included.
+    ///
+    pub fn last_header(&self) -> Option<&Header> {
+        self.last_header.as_ref()
+    }
+
+    ///
+    /// Returns the bytes of the header and body of the most recently read record exactly as
+    /// they appeared in the stream, which allows forwarding the record unmodified. Only available
+    /// if `ParseOptions::keep_raw` is set, and like `last_header` also after parsing the body of
+    /// the record failed. Returns `None` after `read_header_then_body`, which does not read the
+    /// body itself.
+    ///
+    pub fn raw_bytes(&self) -> Option<&[u8]> {
+        if self.raw.is_empty() {
+            None
+        } else {
+            Some(&self.raw)
+        }
+    }
+
+    ///
+    /// Stores the header of the record starting at the current offset, which is about to be read.
+    fn set_last_header(&mut self, header: Header) {
+        self.last_header = Some(header);
+        if is_peer_index_table(&header) {
+            self.peer_index_table = Some(self.offset);
+        }
+    }
+
+    ///
+    /// Pre-allocates the internal buffer holding record bodies to the given number of bytes.
+    /// Setting this to the size of the largest expected record avoids reallocations while reading.
+    ///
+    pub fn with_capacity_hint(mut self, bytes: usize) -> Reader<T> {
+        self.buffer.reserve(bytes);
+        self
+    }
+
+    ///
+    /// Reads the next MRT record in the stream.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from the stream.
+    /// `MrtError::TruncatedRecord` is returned if the stream ends in the middle of a record.
+    /// If an ill-formatted stream provided behavior will be undefined.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn read(&mut self) -> Result<Option<(Header, Record)>, MrtError> {
+        let offset = self.offset;
+        let header = match crate::read_header(&mut self.stream)? {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+
+        self.set_last_header(header);
+        self.raw.clear();
+        self.budget.charge(12 + u64::from(header.length))?;
+        let mut header = check_header(header, &self.options)?;
+        self.read_body(&header)?;
+        let record = parse_traced(offset, &mut header, self.buffer.as_slice(), &self.options)?;
+        Ok(Some((header, record)))
+    }
+
+    ///
+    /// Reads the header of the next MRT record in the stream and returns it together with a
+    /// reader over exactly the `header.length` bytes of its body. This allows decoding the body
+    /// lazily or only partially using a custom parser. Any bytes of the body that have not been
+    /// read when the `RecordBody` is dropped are skipped, such that the stream is positioned at
+    /// the start of the next record.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from the stream.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn read_header_then_body(
+        &mut self,
+    ) -> Result<Option<(Header, RecordBody<'_, T>)>, MrtError> {
+        let header = match crate::read_header(&mut self.stream)? {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+
+        self.set_last_header(header);
+        self.raw.clear();
+        self.budget.charge(12 + u64::from(header.length))?;
+        let header = check_header(header, &self.options)?;
+        self.offset += 12 + u64::from(header.length);
+        let body = RecordBody {
+            inner: (&mut self.stream).take(u64::from(header.length)),
+        };
+        Ok(Some((header, body)))
+    }
+
+    ///
+    /// Reads all remaining MRT records and passes them to the given visitor. Records for which
+    /// `RecordVisitor::visit_header` returns false are skipped without parsing their body, which
+    /// is read and discarded. Use `drive_seek` to seek over these bodies instead.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from the stream.
+    /// If an ill-formatted stream provided behavior will be undefined.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn drive<V: RecordVisitor>(&mut self, visitor: &mut V) -> Result<(), MrtError> {
+        self.drive_with(visitor, discard_body)
+    }
+
+    /// Drives the visitor, skipping the bodies of rejected records using the given function.
+    fn drive_with<V, S>(&mut self, visitor: &mut V, mut skip: S) -> Result<(), MrtError>
+    where
+        V: RecordVisitor,
+        S: FnMut(&mut T, &Header) -> Result<(), MrtError>,
+    {
+        loop {
+            let offset = self.offset;
+            let header = match crate::read_header(&mut self.stream)? {
+                Some(header) => header,
+                None => break,
+            };
+
+            self.set_last_header(header);
+            self.raw.clear();
+            self.budget.charge(12 + u64::from(header.length))?;
+            let mut header = check_header(header, &self.options)?;
+            if !visitor.visit_header(&header) {
+                skip(&mut self.stream, &header)?;
+                self.offset += 12 + u64::from(header.length);
+                continue;
+            }
+
+            self.read_body(&header)?;
+
+            let record = parse_traced(offset, &mut header, self.buffer.as_slice(), &self.options)?;
+            crate::visitor::dispatch(visitor, &header, &record);
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// # Summary
+    /// Counts the remaining MRT records in the stream without parsing them. The bodies of the
+    /// records, including the extended timestamps of the _ET record types, are read and
+    /// discarded, such that the stream is consumed afterwards.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::fs::File;
+    /// use mrt_rs::Reader;
+    ///
+    /// let mut reader = Reader::new(File::open("res/bird-mrtdump_bgp").unwrap());
+    /// assert_eq!(reader.count().unwrap(), 27);
+    /// ```
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from the stream.
+    /// `MrtError::TruncatedRecord` is returned if the stream ends in the middle of a record.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn count(&mut self) -> Result<usize, MrtError> {
+        let mut count = 0;
+        while let Some(header) = crate::read_header(&mut self.stream)? {
+            self.set_last_header(header);
+            self.raw.clear();
+            self.budget.charge(12 + u64::from(header.length))?;
+            let header = check_header(header, &self.options)?;
+            discard_body(&mut self.stream, &header)?;
+            self.offset += 12 + u64::from(header.length);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Returns an iterator over the routes of all remaining TABLE_DUMP_V2 records.
+    pub fn routes(&mut self) -> Routes<'_, T> {
+        Routes::new(self)
+    }
+
+    /// Returns an iterator over the prefixes announced and withdrawn by all remaining BGP4MP records.
+    pub fn route_events(&mut self) -> RouteEvents<'_, T> {
+        RouteEvents::new(self)
+    }
+
+    ///
+    /// Reads all remaining MRT records until the end of the stream has been reached.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Returns `MrtError::RecordLimitExceeded` if the stream contains more records than the
+    /// configured record limit. Any IO error will be returned while reading from the stream.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn read_all(&mut self) -> Result<Vec<(Header, Record)>, MrtError> {
+        let mut records = Vec::new();
+        while let Some(record) = self.read()? {
+            if records.len() == self.options.record_limit {
+                return Err(MrtError::RecordLimitExceeded(self.options.record_limit));
+            }
+            records.push(record);
+        }
+
+        Ok(records)
+    }
+
+    ///
+    /// Reads all remaining MRT records and groups them by their kind. All records are kept in
+    /// memory, which for RIB dumps may require many times the size of the file. Large files
+    /// are better processed one record at a time using `read`.
+    ///
+    /// # Examples
+    /// ```
+    /// use mrt_rs::{Reader, RecordKind};
+    ///
+    /// let mut reader = Reader::new(std::fs::File::open("res/quagga_rib").unwrap());
+    /// let groups = reader.group_by_type().unwrap();
+    /// assert_eq!(groups[&RecordKind::TABLE_DUMP_V2_PEER_INDEX_TABLE].len(), 1);
+    /// ```
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Returns `MrtError::RecordLimitExceeded` if the stream contains more records than the
+    /// configured record limit. Any IO error will be returned while reading from the stream.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn group_by_type(
+        &mut self,
+    ) -> Result<HashMap<RecordKind, Vec<(Header, Record)>>, MrtError> {
+        let mut groups: HashMap<RecordKind, Vec<(Header, Record)>> = HashMap::new();
+        let mut count = 0;
+        while let Some((header, record)) = self.read()? {
+            if count == self.options.record_limit {
+                return Err(MrtError::RecordLimitExceeded(self.options.record_limit));
+            }
+            count += 1;
+            groups
+                .entry(record.kind())
+                .or_default()
+                .push((header, record));
+        }
+
+        Ok(groups)
+    }
+
+    ///
+    /// Reads the next MRT record in the stream together with the SHA-256 digest of the bytes
+    /// of its header and body as they appeared in the stream. Identical records produce identical
+    /// digests, which allows deduplicating records from multiple sources.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from the stream.
+    /// If an ill-formatted stream provided behavior will be undefined.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    #[cfg(feature = "digest")]
+    pub fn read_with_digest(&mut self) -> Result<Option<(Header, Record, [u8; 32])>, MrtError> {
+        use sha2::{Digest, Sha256};
+
+        let mut tee = Tee {
+            inner: &mut self.stream,
+            bytes: Vec::new(),
+        };
+
+        let header = match crate::read_header(&mut tee)? {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+        let bytes = tee.bytes;
+
+        self.set_last_header(header);
+        self.raw.clear();
+        self.budget.charge(12 + u64::from(header.length))?;
+        let mut header = check_header(header, &self.options)?;
+        let mut tee = Tee {
+            inner: &mut self.stream,
+            bytes,
+        };
+        let offset = self.offset;
+        self.offset += 12 + u64::from(header.length);
+        let record = parse_traced(offset, &mut header, &mut tee, &self.options);
+        if record.is_err() {
+            // Consume the remainder of the body to find out whether the record was truncated.
+            let total = 12 + u64::from(header.length);
+            let remaining = total.saturating_sub(tee.bytes.len() as u64);
+            std::io::copy(&mut (&mut tee).take(remaining), &mut std::io::sink())?;
+            if (tee.bytes.len() as u64) < total {
+                return Err(MrtError::TruncatedRecord {
+                    header,
+                    bytes_available: tee.bytes.len() as u32 - 12,
+                });
+            }
+        }
+        if self.options.keep_raw {
+            self.raw.extend_from_slice(&tee.bytes);
+        }
+        let record = record?;
+        let digest = Sha256::digest(&tee.bytes);
+        Ok(Some((header, record, digest.into())))
+    }
+
+    ///
+    /// Reads the body of the record described by the header into the internal buffer and
+    /// advances the offset past the record.
+    ///
+    fn read_body(&mut self, header: &Header) -> Result<(), MrtError> {
+        // Reading the entire body first keeps the stream aligned at record boundaries.
+        self.buffer.clear();
+        (&mut self.stream)
+            .take(u64::from(header.length))
+            .read_to_end(&mut self.buffer)?;
+        if self.buffer.len() != header.length as usize {
+            return Err(MrtError::TruncatedRecord {
+                header: *header,
+                bytes_available: self.buffer.len() as u32,
+            });
+        }
+
+        self.offset += 12 + u64::from(header.length);
+        if self.options.keep_raw {
+            self.raw.write_u32::<BigEndian>(header.timestamp)?;
+            self.raw.write_u16::<BigEndian>(header.record_type)?;
+            self.raw.write_u16::<BigEndian>(header.sub_type)?;
+            self.raw.write_u32::<BigEndian>(header.length)?;
+            self.raw.extend_from_slice(&self.buffer);
+        }
+        Ok(())
+    }
+}
+
+impl<T: BufRead> Reader<T> {
+    ///
+    /// Reads the next MRT record in the stream like `read`, but parses the record directly from
+    /// the internal buffer of the stream when it contains the entire record. This avoids copying
+    /// the record body when reading from a `BufReader` with a buffer that is large compared to
+    /// the records. Records that cross the end of the buffer are read using `read`. The
+    /// `throughput` benchmark compares both methods.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from the stream.
+    /// If an ill-formatted stream provided behavior will be undefined.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn read_buffered(&mut self) -> Result<Option<(Header, Record)>, MrtError> {
+        let header = match self.stream.fill_buf()? {
+            available if available.len() >= 12 => {
+                let length = BigEndian::read_u32(&available[8..12]) as usize;
+                if available.len() < 12 + length {
+                    return self.read();
+                }
+                crate::read_header(&mut &available[..12])?
+            }
+            _ => return self.read(),
+        };
+        let header = match header {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+
+        self.set_last_header(header);
+        self.raw.clear();
+        let checked = self
+            .budget
+            .charge(12 + u64::from(header.length))
+            .and_then(|_| check_header(header, &self.options));
+        let mut header = match checked {
+            Ok(header) => header,
+            Err(e) => {
+                // Like `read`, the header of the rejected record is consumed.
+                self.stream.consume(12);
+                self.offset += 12;
+                return Err(e);
+            }
+        };
+
+        let total = 12 + header.length as usize;
+        let available = self.stream.fill_buf()?;
+        if self.options.keep_raw {
+            self.raw.extend_from_slice(&available[..total]);
+        }
+        let record = parse_traced(
+            self.offset,
+            &mut header,
+            &available[12..total],
+            &self.options,
+        );
+        self.stream.consume(total);
+        self.offset += total as u64;
+        Ok(Some((header, record?)))
+    }
+}
+
+impl<'a> Reader<Cursor<&'a [u8]>> {
+    ///
+    /// Creates a new Reader that reads MRT records from bytes that are already in memory.
+    /// The Reader borrows the bytes and can therefore not outlive them.
+    ///
+    /// # Examples
+    /// ```
+    /// use mrt_rs::Reader;
+    ///
+    /// let bytes = std::fs::read("res/quagga_bgp").unwrap();
+    /// let mut reader = Reader::from_bytes(&bytes);
+    /// while let Some((header, record)) = reader.read().unwrap() {
+    ///     println!("{:?}: {:?}", header, record);
+    /// }
+    /// ```
+    ///
+    pub fn from_bytes(bytes: &'a [u8]) -> Reader<Cursor<&'a [u8]>> {
+        Reader::new(Cursor::new(bytes))
+    }
+}
+
+impl Reader<Cursor<Vec<u8>>> {
+    ///
+    /// Creates a new Reader that takes ownership of bytes that are already in memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use mrt_rs::Reader;
+    ///
+    /// let bytes = std::fs::read("res/quagga_rib").unwrap();
+    /// let mut reader = Reader::from_vec(bytes);
+    /// assert_eq!(reader.read_all().unwrap().len(), 7);
+    /// ```
+    ///
+    pub fn from_vec(bytes: Vec<u8>) -> Reader<Cursor<Vec<u8>>> {
+        Reader::new(Cursor::new(bytes))
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl<R: Read> Reader<flate2::read::GzDecoder<R>> {
+    ///
+    /// Creates a new Reader that reads MRT records from a GZIP compressed stream.
+    /// Only available when the `gzip` feature is enabled.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::fs::File;
+    /// use std::io::BufReader;
+    /// use mrt_rs::Reader;
+    ///
+    /// let file = File::open("updates.20200101.0000.gz").unwrap();
+    /// let mut reader = Reader::gzip(BufReader::new(file));
+    /// while let Some((header, record)) = reader.read().unwrap() {
+    ///     println!("{:?}: {:?}", header, record);
+    /// }
+    /// ```
+    ///
+    pub fn gzip(inner: R) -> Reader<flate2::read::GzDecoder<R>> {
+        Reader::new(flate2::read::GzDecoder::new(inner))
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl<R: Read> Reader<zstd::stream::read::Decoder<'static, std::io::BufReader<R>>> {
+    ///
+    /// # Summary
+    /// Creates a new Reader that reads MRT records from a zstandard compressed stream.
+    /// Only available when the `zstd` feature is enabled.
+    ///
+    /// The stream is buffered internally, so unlike for `Reader::gzip` it does not need to be
+    /// wrapped in a `BufReader`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::fs::File;
+    /// use mrt_rs::Reader;
+    ///
+    /// let mut reader = Reader::zstd(File::open("updates.20200101.0000.zst").unwrap()).unwrap();
+    /// while let Some((header, record)) = reader.read().unwrap() {
+    ///     println!("{:?}: {:?}", header, record);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// An IO error is returned if the decompression context cannot be created.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn zstd(
+        inner: R,
+    ) -> Result<Reader<zstd::stream::read::Decoder<'static, std::io::BufReader<R>>>, MrtError> {
+        Ok(Reader::new(zstd::stream::read::Decoder::new(inner)?))
+    }
+}
+
+impl<T: Read + Seek> Reader<T> {
+    ///
+    /// Repositions the stream at the given byte offset, which should be the start of a record.
+    /// The next call to `read` will read the record starting at that offset. The state kept
+    /// from the previously read record is cleared, such that `last_header` and `raw_bytes`
+    /// return `None` until the next record is read.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while seeking in the stream.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn seek_to(&mut self, offset: u64) -> Result<(), MrtError> {
+        self.stream.seek(SeekFrom::Start(offset))?;
+        self.offset = offset;
+        self.last_header = None;
+        self.raw.clear();
+        self.buffer.clear();
+        Ok(())
+    }
+
+    ///
+    /// # Summary
+    /// Creates a Reader that continues reading the given stream at a position previously
+    /// returned by `Reader::checkpoint`. The stream should contain the same data as the stream
+    /// from which the checkpoint was taken. Use `read_peer_index_table` to recover the peers
+    /// referenced by the RIB entries that follow.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::fs::File;
+    /// use mrt_rs::Reader;
+    ///
+    /// let mut reader = Reader::new(File::open("res/quagga_rib").unwrap());
+    /// reader.read().unwrap();
+    /// let checkpoint = reader.checkpoint();
+    ///
+    /// let mut reader = Reader::resume(File::open("res/quagga_rib").unwrap(), checkpoint).unwrap();
+    /// let table = reader.read_peer_index_table().unwrap().unwrap();
+    /// assert_eq!(table.peer_entries.len(), 2);
+    /// ```
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while seeking in the stream.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn resume(stream: T, checkpoint: Checkpoint) -> Result<Reader<T>, MrtError> {
+        Reader::resume_with_options(stream, checkpoint, ParseOptions::default())
+    }
+
+    ///
+    /// # Summary
+    /// Creates a Reader like `Reader::resume` that parses records using the given options, which
+    /// should be the options of the Reader the checkpoint was taken from. Limits such as
+    /// `ParseOptions::byte_budget` only count the bytes read after resuming.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while seeking in the stream.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn resume_with_options(
+        stream: T,
+        checkpoint: Checkpoint,
+        options: ParseOptions,
+    ) -> Result<Reader<T>, MrtError> {
+        let mut reader = Reader::with_options(stream, options);
+        reader.seek_to(checkpoint.offset)?;
+        reader.peer_index_table = checkpoint.peer_index_table;
+        Ok(reader)
+    }
+
+    ///
+    /// # Summary
+    /// Reads the most recent PEER_INDEX_TABLE record preceding the current position again,
+    /// after which the stream is repositioned at the current offset. Returns `None` if no
+    /// PEER_INDEX_TABLE has been read by this Reader or by the Reader its checkpoint was taken from.
+    /// The record is not counted against the byte budget and does not replace `last_header` or
+    /// `raw_bytes`.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while seeking in or reading from the stream, and an error
+    /// is returned if the record at the remembered offset is not a valid PEER_INDEX_TABLE.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn read_peer_index_table(&mut self) -> Result<Option<PEER_INDEX_TABLE>, MrtError> {
+        let offset = match self.peer_index_table {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+
+        self.stream.seek(SeekFrom::Start(offset))?;
+        let record = read_detached(&mut self.stream, offset, &self.options);
+        self.stream.seek(SeekFrom::Start(self.offset))?;
+
+        match record? {
+            Some((_, Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(x)))) => Ok(Some(x)),
+            _ => Err(MrtError::Io(Error::new(
+                ErrorKind::InvalidData,
+                format!("No PEER_INDEX_TABLE found at offset {}.", offset),
+            ))),
+        }
+    }
+
+    ///
+    /// Repositions the stream at its start and clears any state kept from previously read
+    /// records, such that all records can be read again using the same Reader. This includes
+    /// the bytes counted against the byte budget, which applies to every pass separately.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while seeking in the stream.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn reset(&mut self) -> Result<(), MrtError> {
+        self.peer_index_table = None;
+        self.budget.used = 0;
+        self.seek_to(0)
+    }
+
+    ///
+    /// Detects the dialect of the stream by inspecting the types and sub-types of the next
+    /// records. Only the record headers are read, after which the stream is repositioned at its
+    /// current offset such that no records are consumed.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while seeking in the stream.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn detect_dialect(&mut self) -> Result<Dialect, MrtError> {
+        let start = self.stream.stream_position()?;
+
+        let mut dialect = Dialect::Unknown;
+        for _ in 0..DIALECT_SAMPLE_SIZE {
+            // Detection is best-effort, so a malformed header simply ends the sample.
+            let header = match crate::read_header(&mut self.stream) {
+                Ok(Some(header)) => header,
+                _ => break,
+            };
+
+            dialect = match (dialect, header.record_type) {
+                (Dialect::Unknown, codes::TABLE_DUMP) => Dialect::TableDump,
+                (Dialect::Unknown, codes::TABLE_DUMP_V2) => {
+                    Dialect::TableDumpV2 { add_path: false }
+                }
+                (Dialect::Unknown, codes::BGP4MP) | (Dialect::Unknown, codes::BGP4MP_ET) => {
+                    Dialect::Bgp4mp {
+                        extended_timestamp: false,
+                        as4: false,
+                        add_path: false,
+                    }
+                }
+                (x, _) => x,
+            };
+
+            match &mut dialect {
+                Dialect::TableDumpV2 { add_path } if header.record_type == codes::TABLE_DUMP_V2 => {
+                    *add_path |= (codes::table_dump_v2::RIB_IPV4_UNICAST_ADDPATH
+                        ..=codes::table_dump_v2::RIB_GENERIC_ADDPATH)
+                        .contains(&header.sub_type);
+                }
+                Dialect::Bgp4mp {
+                    extended_timestamp,
+                    as4,
+                    add_path,
+                } if header.record_type == codes::BGP4MP
+                    || header.record_type == codes::BGP4MP_ET =>
+                {
+                    *extended_timestamp |= header.record_type == codes::BGP4MP_ET;
+                    *as4 |= AsnSize::for_bgp4mp_subtype(header.sub_type) == AsnSize::Four;
+                    *add_path |= (codes::bgp4mp::MESSAGE_ADDPATH
+                        ..=codes::bgp4mp::MESSAGE_AS4_LOCAL_ADDPATH)
+                        .contains(&header.sub_type);
+                }
+                _ => (),
+            }
+
+            self.stream
+                .seek(SeekFrom::Current(i64::from(header.length)))?;
+        }
+
+        self.stream.seek(SeekFrom::Start(start))?;
+        Ok(dialect)
+    }
+
+    ///
+    /// # Summary
+    /// Reads all remaining MRT records and passes them to the given visitor, like `drive`. The
+    /// bodies of records for which `RecordVisitor::visit_header` returns false are skipped by
+    /// seeking over them instead of reading them, which makes filtering out most records of a
+    /// file nearly free.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::fs::File;
+    /// use mrt_rs::{Header, Reader, RecordVisitor};
+    /// use mrt_rs::tabledump::PEER_INDEX_TABLE;
+    ///
+    /// struct PeerCounter(usize);
+    ///
+    /// impl RecordVisitor for PeerCounter {
+    ///     fn visit_header(&mut self, header: &Header) -> bool {
+    ///         header.record_type == 13 && header.sub_type == 1
+    ///     }
+    ///
+    ///     fn visit_peer_index_table(&mut self, _: &Header, table: &PEER_INDEX_TABLE) {
+    ///         self.0 += table.peer_entries.len();
+    ///     }
+    /// }
+    ///
+    /// let mut counter = PeerCounter(0);
+    /// let mut reader = Reader::new(File::open("res/quagga_rib").unwrap());
+    /// reader.drive_seek(&mut counter).unwrap();
+    /// assert_eq!(counter.0, 2);
+    /// ```
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from or seeking in the stream. A truncated
+    /// last record is not detected if it is skipped, as its body is not read.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn drive_seek<V: RecordVisitor>(&mut self, visitor: &mut V) -> Result<(), MrtError> {
+        self.drive_with(visitor, |stream, header| {
+            stream.seek(SeekFrom::Current(i64::from(header.length)))?;
+            Ok(())
+        })
+    }
+
+    ///
+    /// # Summary
+    /// Reads the headers of all remaining records together with their offsets, skipping the
+    /// record bodies by seeking over them. Afterwards the stream is positioned at its end, and
+    /// any of the records can be read by passing its offset to `seek_to`.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from or seeking in the stream. A truncated
+    /// last record is not detected, as its body is not read.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn headers(&mut self) -> Result<Vec<(u64, Header)>, MrtError> {
+        let mut headers = Vec::new();
+        while let Some(header) = crate::read_header(&mut self.stream)? {
+            let header = check_header(header, &self.options)?;
+            headers.push((self.offset, header));
+            self.set_last_header(header);
+            self.offset += 12 + u64::from(header.length);
+            self.stream
+                .seek(SeekFrom::Current(i64::from(header.length)))?;
+        }
+        Ok(headers)
+    }
+}
+
+impl<T: Read + Seek + Clone> IntoIterator for &Reader<T> {
+    type Item = Result<(Header, Record), MrtError>;
+    type IntoIter = Records<T>;
+
+    ///
+    /// Iterates over all records of the stream from its start, using a clone of the stream and
+    /// the options of this Reader. This requires the stream to implement `Seek` and `Clone`, as
+    /// a `Cursor` over a slice does, and leaves this Reader and its position untouched, such that
+    /// the records can be iterated repeatedly.
+    ///
+    /// # Examples
+    /// ```
+    /// use mrt_rs::Reader;
+    ///
+    /// let bytes = std::fs::read("res/quagga_bgp").unwrap();
+    /// let reader = Reader::from_bytes(&bytes);
+    /// let first = (&reader).into_iter().count();
+    /// let second = (&reader).into_iter().count();
+    /// assert_eq!(first, second);
+    /// ```
+    ///
+    fn into_iter(self) -> Records<T> {
+        let mut reader = Reader::with_options(self.stream.clone(), self.options.clone());
+        let error = reader.seek_to(0).err();
+        Records {
+            reader,
+            error,
+            done: false,
+        }
+    }
+}
+
+///
+/// An iterator over the records of a stream, as returned by iterating over a `&Reader`. The
+/// iterator ends after the first error.
+///
+#[derive(Debug)]
+pub struct Records<T: Read> {
+    reader: Reader<T>,
+
+    /// An error that occurred before the first record was read.
+    error: Option<MrtError>,
+
+    done: bool,
+}
+
+impl<T: Read> Iterator for Records<T> {
+    type Item = Result<(Header, Record), MrtError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if let Some(e) = self.error.take() {
+            self.done = true;
+            return Some(Err(e));
+        }
+
+        match self.reader.read() {
+            Ok(Some(x)) => Some(Ok(x)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+///
+/// A reader over the body of a single MRT record, as returned by
+/// [`Reader::read_header_then_body`]. Reading stops at the end of the body. Unread bytes are
+/// skipped when it is dropped, or explicitly using `finish` which also reports IO errors.
+///
+#[derive(Debug)]
+pub struct RecordBody<'a, R: Read> {
+    inner: Take<&'a mut R>,
+}
+
+impl<R: Read> RecordBody<'_, R> {
+    /// Returns the number of bytes of the body that have not been read yet.
+    pub fn remaining(&self) -> u64 {
+        self.inner.limit()
+    }
+
+    ///
+    /// Skips the remainder of the body such that the stream is positioned at the next record.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from the stream. An error of kind
+    /// `UnexpectedEof` is returned if the stream ends before the end of the body.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn finish(mut self) -> Result<(), Error> {
+        self.skip()
+    }
+
+    fn skip(&mut self) -> Result<(), Error> {
+        std::io::copy(&mut self.inner, &mut std::io::sink())?;
+        if self.inner.limit() != 0 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "Stream ended in the middle of an MRT record.",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for RecordBody<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R: Read> Drop for RecordBody<'_, R> {
+    fn drop(&mut self) {
+        // Errors cannot be reported here; they resurface when reading the next record.
+        let _ = self.skip();
+    }
+}
+
+///
+/// Parses the body of a record. When the `tracing` feature is enabled this happens within a span
+/// describing the record, and an event is emitted if the record cannot be parsed.
+///
+fn parse_traced(
+    offset: u64,
+    header: &mut Header,
+    mut stream: impl Read,
+    options: &ParseOptions,
+) -> Result<Record, MrtError> {
+    #[cfg(feature = "tracing")]
+    let span = tracing::debug_span!(
+        "record",
+        offset,
+        record_type = header.record_type,
+        sub_type = header.sub_type,
+        length = header.length
+    );
+    #[cfg(feature = "tracing")]
+    let _entered = span.enter();
+
+    let record = crate::parse_record(header, &mut stream, options);
+    #[cfg(feature = "tracing")]
+    if let Err(e) = &record {
+        tracing::error!(error = %e, "Failed to parse MRT record.");
+    }
+
+    record.map_err(|e| MrtError::at_offset(e, offset))
+}
+
+///
+/// The position of a [`Reader`] in its stream, as returned by [`Reader::checkpoint`]. It can be
+/// stored and later passed to [`Reader::resume`] to continue reading where the Reader left off.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    /// The offset in the stream of the next record.
+    pub offset: u64,
+
+    /// The offset of the most recent PEER_INDEX_TABLE record preceding `offset`, which provides
+    /// the peers of the RIB entries following it.
+    pub peer_index_table: Option<u64>,
+}
+
+/// Returns true if the header belongs to a TABLE_DUMP_V2 PEER_INDEX_TABLE record.
+fn is_peer_index_table(header: &Header) -> bool {
+    header.record_type == codes::TABLE_DUMP_V2
+        && header.sub_type == codes::table_dump_v2::PEER_INDEX_TABLE
+}
+
+/// Skips the body of a record by reading and discarding it.
+fn discard_body<T: Read>(stream: &mut T, header: &Header) -> Result<(), MrtError> {
+    let skipped = std::io::copy(
+        &mut stream.take(u64::from(header.length)),
+        &mut std::io::sink(),
+    )?;
+    if skipped != u64::from(header.length) {
+        return Err(MrtError::TruncatedRecord {
+            header: *header,
+            bytes_available: skipped as u32,
+        });
+    }
+    Ok(())
+}
+
+/// Reads and parses the record at the current position of the stream without updating a Reader.
+fn read_detached<T: Read>(
+    stream: &mut T,
+    offset: u64,
+    options: &ParseOptions,
+) -> Result<Option<(Header, Record)>, MrtError> {
+    let header = match crate::read_header(&mut *stream)? {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+
+    let mut header = check_header(header, options)?;
+    let mut body = Vec::new();
+    stream
+        .take(u64::from(header.length))
+        .read_to_end(&mut body)?;
+    if body.len() != header.length as usize {
+        return Err(MrtError::TruncatedRecord {
+            header,
+            bytes_available: body.len() as u32,
+        });
+    }
+
+    let record = parse_traced(offset, &mut header, body.as_slice(), options)?;
+    Ok(Some((header, record)))
+}
+
+/// Tracks the number of bytes read by a Reader against `ParseOptions::byte_budget`.
+#[derive(Debug, Default)]
+struct ByteBudget {
+    limit: Option<u64>,
+    used: u64,
+}
+
+impl ByteBudget {
+    /// Accounts for a record of the given size, returning an error if it exceeds the limit.
+    fn charge(&mut self, bytes: u64) -> Result<(), MrtError> {
+        self.used += bytes;
+        match self.limit {
+            Some(limit) if self.used > limit => Err(MrtError::BudgetExceeded(limit)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns an error if the timestamp or length of the header lies outside of the configured bounds.
+pub(crate) fn check_header(header: Header, options: &ParseOptions) -> Result<Header, MrtError> {
+    match options.timestamp_bounds {
+        Some((min, max)) if header.timestamp < min || header.timestamp > max => {
+            return Err(MrtError::TimestampOutOfRange(header))
+        }
+        _ => (),
+    }
+    match options.max_record_size {
+        Some(max) if header.length > max => Err(MrtError::RecordTooLarge(header)),
+        _ => Ok(header),
+    }
+}
+
+/// Wraps a stream and keeps a copy of all bytes that have been read from it.
+#[cfg(feature = "digest")]
+struct Tee<'a, T: Read> {
+    inner: &'a mut T,
+    bytes: Vec<u8>,
+}
+
+#[cfg(feature = "digest")]
+impl<T: Read> Read for Tee<'_, T> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
@@ -0,0 +1,905 @@
+use byteorder::{BigEndian, ReadBytesExt};
+use std::fmt;
+use std::io::{Error, ErrorKind, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::records::nlri::{self, Prefix};
+use crate::{AsnSize, MrtError, ParseOptions, AFI};
+
+/// The reserved 2-octet ASN used as a placeholder for 4-octet ASNs in 2-octet AS_PATHs.
+/// More information can found in [RFC6793](https://tools.ietf.org/html/rfc6793#section-9).
+pub const AS_TRANS: u32 = 23456;
+
+/// Represents a single BGP path attribute.
+/// More information can found in [RFC4271](https://tools.ietf.org/html/rfc4271#section-5).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum PathAttribute {
+    /// Defines the origin of the path information.
+    ORIGIN(Origin),
+
+    /// The ASes through which the routing information has passed.
+    AS_PATH(AsPath),
+
+    /// The address of the router that should be used as the next hop to the destinations.
+    /// Usually an IPv4 address, but some dumps carry an IPv6 address of 16 bytes instead.
+    NEXT_HOP(IpAddr),
+
+    /// Used to discriminate among multiple entry or exit points to a neighboring AS.
+    MULTI_EXIT_DISC(u32),
+
+    /// The degree of preference for an advertised route within an AS.
+    LOCAL_PREF(u32),
+
+    /// Indicates that the route has been aggregated with loss of path information.
+    ATOMIC_AGGREGATE,
+
+    /// The ASN and IPv4 address of the BGP speaker that formed the aggregate route.
+    AGGREGATOR(Aggregator),
+
+    /// The communities this route belongs to.
+    /// More information can found in [RFC1997](https://tools.ietf.org/html/rfc1997).
+    COMMUNITY(Vec<u32>),
+
+    /// The BGP identifier of the originator of the route inside the local AS.
+    /// More information can found in [RFC4456](https://tools.ietf.org/html/rfc4456#section-8).
+    ORIGINATOR_ID(Ipv4Addr),
+
+    /// The cluster identifiers of the reflection path the route has passed.
+    /// More information can found in [RFC4456](https://tools.ietf.org/html/rfc4456#section-8).
+    CLUSTER_LIST(Vec<u32>),
+
+    /// The raw value of the Multiprotocol Reachable NLRI attribute.
+    /// More information can found in [RFC4760](https://tools.ietf.org/html/rfc4760#section-3).
+    MP_REACH_NLRI(Vec<u8>),
+
+    /// The raw value of the Multiprotocol Unreachable NLRI attribute.
+    /// More information can found in [RFC4760](https://tools.ietf.org/html/rfc4760#section-4).
+    MP_UNREACH_NLRI(Vec<u8>),
+
+    /// The extended communities this route belongs to.
+    /// More information can found in [RFC4360](https://tools.ietf.org/html/rfc4360).
+    EXTENDED_COMMUNITIES(Vec<ExtendedCommunity>),
+
+    /// The AS_PATH using 4-octet ASNs sent along a 2-octet AS_PATH.
+    /// More information can found in [RFC6793](https://tools.ietf.org/html/rfc6793#section-3).
+    AS4_PATH(AsPath),
+
+    /// The AGGREGATOR using a 4-octet ASN sent along a 2-octet AGGREGATOR.
+    /// More information can found in [RFC6793](https://tools.ietf.org/html/rfc6793#section-3).
+    AS4_AGGREGATOR(Aggregator),
+
+    /// A path attribute that is not supported, containing its type code and raw value.
+    UNKNOWN(u8, Vec<u8>),
+}
+
+impl PathAttribute {
+    ///
+    /// # Summary
+    /// Parses a buffer containing exactly one BGP path attribute including its flags, type code
+    /// and length.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// An error is returned if the attribute is ill-formatted. An error of kind `InvalidData` is
+    /// returned if bytes remain after the attribute.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn from_bytes(mut bytes: &[u8], asn_size: AsnSize) -> Result<PathAttribute, Error> {
+        let attribute = PathAttribute::parse(&mut bytes, asn_size, &ParseOptions::default())?;
+        crate::ensure_consumed(bytes, "the path attribute")?;
+        Ok(attribute)
+    }
+
+    ///
+    /// # Summary
+    /// Used to parse a single BGP path attribute including its flags, type code and length.
+    /// Unless `ParseOptions::strict` is set, a MULTI_EXIT_DISC or LOCAL_PREF attribute with a
+    /// length other than 4 bytes is read as a big-endian number that is zero-extended or
+    /// truncated to 32 bits.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from the stream.
+    /// An error of kind `InvalidData` is returned if the attribute value is ill-formatted.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn parse(
+        mut stream: impl Read,
+        asn_size: AsnSize,
+        options: &ParseOptions,
+    ) -> Result<PathAttribute, Error> {
+        let flags = stream.read_u8()?;
+        let type_code = stream.read_u8()?;
+
+        // The Extended Length bit determines whether the length is encoded in one or two bytes.
+        let length = if flags & 0x10 != 0 {
+            stream.read_u16::<BigEndian>()?
+        } else {
+            u16::from(stream.read_u8()?)
+        };
+
+        let mut value = vec![0; length as usize];
+        stream.read_exact(&mut value)?;
+        let mut slice: &[u8] = &value;
+
+        match type_code {
+            1 => Ok(PathAttribute::ORIGIN(Origin::from(slice.read_u8()?)?)),
+            2 => Ok(PathAttribute::AS_PATH(AsPath::parse(
+                slice, length, asn_size, options,
+            )?)),
+            3 => match length {
+                4 => Ok(PathAttribute::NEXT_HOP(IpAddr::V4(Ipv4Addr::from(
+                    slice.read_u32::<BigEndian>()?,
+                )))),
+                16 => Ok(PathAttribute::NEXT_HOP(IpAddr::V6(Ipv6Addr::from(
+                    slice.read_u128::<BigEndian>()?,
+                )))),
+                x => Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("NEXT_HOP attribute has an invalid length: {}", x),
+                )),
+            },
+            4 => Ok(PathAttribute::MULTI_EXIT_DISC(read_u32_value(
+                "MULTI_EXIT_DISC",
+                &value,
+                options,
+            )?)),
+            5 => Ok(PathAttribute::LOCAL_PREF(read_u32_value(
+                "LOCAL_PREF",
+                &value,
+                options,
+            )?)),
+            6 if length == 0 => Ok(PathAttribute::ATOMIC_AGGREGATE),
+            6 => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "ATOMIC_AGGREGATE attribute has a non-zero length: {}",
+                    length
+                ),
+            )),
+            7 => Ok(PathAttribute::AGGREGATOR(Aggregator::parse(slice, length)?)),
+            8 => Ok(PathAttribute::COMMUNITY(read_u32s(slice)?)),
+            9 => Ok(PathAttribute::ORIGINATOR_ID(Ipv4Addr::from(
+                slice.read_u32::<BigEndian>()?,
+            ))),
+            10 => Ok(PathAttribute::CLUSTER_LIST(read_u32s(slice)?)),
+            14 => Ok(PathAttribute::MP_REACH_NLRI(value)),
+            15 => Ok(PathAttribute::MP_UNREACH_NLRI(value)),
+            16 => {
+                let mut communities = Vec::with_capacity(value.len() / 8);
+                while !slice.is_empty() {
+                    communities.push(ExtendedCommunity::parse(&mut slice)?);
+                }
+                Ok(PathAttribute::EXTENDED_COMMUNITIES(communities))
+            }
+            17 => Ok(PathAttribute::AS4_PATH(AsPath::parse(
+                slice,
+                length,
+                AsnSize::Four,
+                options,
+            )?)),
+            18 => Ok(PathAttribute::AS4_AGGREGATOR(Aggregator::parse(
+                slice, length,
+            )?)),
+            x => Ok(PathAttribute::UNKNOWN(x, value)),
+        }
+    }
+}
+
+///
+/// # Summary
+/// Used to parse all path attributes contained in a buffer such as the attributes of a RIB entry.
+/// See `PathAttribute::parse` for the effect of the options.
+///
+/// # Panics
+/// This function does not panic.
+///
+/// # Errors
+/// An error is returned if any of the path attributes is ill-formatted. An error of kind
+/// `InvalidData` is returned if the length of an attribute exceeds the remaining bytes.
+///
+/// # Safety
+/// This function does not make use of unsafe code.
+///
+pub fn parse(
+    mut bytes: &[u8],
+    asn_size: AsnSize,
+    options: &ParseOptions,
+) -> Result<Vec<PathAttribute>, Error> {
+    let mut attributes = Vec::new();
+    while !bytes.is_empty() {
+        let (_, attribute) = split_attribute(&mut bytes)?;
+        attributes.push(PathAttribute::parse(attribute, asn_size, options)?);
+    }
+
+    Ok(attributes)
+}
+
+///
+/// Splits the next path attribute including its header off the front of the buffer and returns
+/// its type code, after checking the declared length against the remaining bytes.
+///
+fn split_attribute<'a>(bytes: &mut &'a [u8]) -> Result<(u8, &'a [u8]), Error> {
+    let (header_length, length) = match *bytes {
+        [flags, _, high, low, ..] if flags & 0x10 != 0 => (4, u16::from_be_bytes([*high, *low])),
+        [flags, _, length, ..] if flags & 0x10 == 0 => (3, u16::from(*length)),
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Path attribute header exceeds the length of all path attributes.",
+            ))
+        }
+    };
+    if header_length + usize::from(length) > bytes.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Path attribute of type {} has a length of {} bytes while only {} bytes remain.",
+                bytes[1],
+                length,
+                bytes.len() - header_length
+            ),
+        ));
+    }
+
+    let (attribute, remainder) = bytes.split_at(header_length + usize::from(length));
+    *bytes = remainder;
+    Ok((attribute[1], attribute))
+}
+
+///
+/// The raw path attributes of a route, of which individual attributes are only decoded when they
+/// are requested. This is cheaper than `parse` when only a few attributes such as the AS_PATH are
+/// needed.
+///
+/// # Examples
+/// ```
+/// use std::fs::File;
+/// use mrt_rs::attributes::{LazyAttributes, PathAttribute};
+/// use mrt_rs::{AsnSize, Reader};
+///
+/// let mut reader = Reader::new(File::open("res/openbgpd_rib_table-v2").unwrap());
+/// for route in reader.routes() {
+///     let attributes = LazyAttributes::new(route.unwrap().attributes, AsnSize::Four);
+///     if let Some(PathAttribute::AS_PATH(path)) = attributes.get(2).unwrap() {
+///         println!("{}", path);
+///     }
+/// }
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LazyAttributes {
+    bytes: Vec<u8>,
+    asn_size: AsnSize,
+}
+
+impl LazyAttributes {
+    /// Wraps the raw path attributes, which are decoded using the given ASN size.
+    pub fn new(bytes: Vec<u8>, asn_size: AsnSize) -> LazyAttributes {
+        LazyAttributes { bytes, asn_size }
+    }
+
+    /// Returns the raw path attributes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    ///
+    /// # Summary
+    /// Decodes the first path attribute with the given type code, skipping over all other
+    /// attributes without decoding them. Returns `None` if no such attribute is present.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// An error is returned if the requested attribute is ill-formatted, or if the length of an
+    /// attribute preceding it exceeds the remaining bytes.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn get(&self, type_code: u8) -> Result<Option<PathAttribute>, MrtError> {
+        let mut bytes = &self.bytes[..];
+        while !bytes.is_empty() {
+            let (code, attribute) = split_attribute(&mut bytes)?;
+            if code == type_code {
+                return Ok(Some(PathAttribute::from_bytes(attribute, self.asn_size)?));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+///
+/// Reads the value of an attribute that is defined to be a 4-byte number. In strict mode any
+/// other length is rejected, otherwise the value is zero-extended or truncated to 32 bits.
+///
+fn read_u32_value(name: &str, value: &[u8], options: &ParseOptions) -> Result<u32, Error> {
+    if value.len() != 4 && options.strict {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "{} attribute has a length of {} bytes instead of 4.",
+                name,
+                value.len()
+            ),
+        ));
+    }
+
+    Ok(value.iter().fold(0, |x, byte| (x << 8) | u32::from(*byte)))
+}
+
+/// Reads 4-byte values until the end of the buffer has been reached.
+fn read_u32s(mut bytes: &[u8]) -> Result<Vec<u32>, Error> {
+    let mut values = Vec::with_capacity(bytes.len() / 4);
+    while !bytes.is_empty() {
+        values.push(bytes.read_u32::<BigEndian>()?);
+    }
+
+    Ok(values)
+}
+
+/// Defines the origin of the path information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+#[repr(u8)]
+pub enum Origin {
+    /// The path information originated from an Interior Gateway Protocol.
+    IGP = 0,
+
+    /// The path information has been learned via the Exterior Gateway Protocol.
+    EGP = 1,
+
+    /// The path information has been learned by some other means.
+    INCOMPLETE = 2,
+}
+
+impl Origin {
+    fn from(value: u8) -> Result<Origin, Error> {
+        match value {
+            0 => Ok(Origin::IGP),
+            1 => Ok(Origin::EGP),
+            2 => Ok(Origin::INCOMPLETE),
+            x => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unknown ORIGIN value found: {}", x),
+            )),
+        }
+    }
+}
+
+/// Represents the value of the AGGREGATOR and AS4_AGGREGATOR attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aggregator {
+    /// The ASN of the BGP speaker that formed the aggregate route.
+    pub asn: u32,
+
+    /// The IPv4 address of the BGP speaker that formed the aggregate route.
+    pub ip: Ipv4Addr,
+}
+
+impl Aggregator {
+    fn parse(mut stream: impl Read, length: u16) -> Result<Aggregator, Error> {
+        // The width of the ASN is determined by the attribute length, since a 2-octet
+        // AGGREGATOR might be received from a speaker that does not support 4-octet ASNs.
+        let asn = match length {
+            6 => u32::from(stream.read_u16::<BigEndian>()?),
+            8 => stream.read_u32::<BigEndian>()?,
+            x => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid AGGREGATOR length found: {}", x),
+                ));
+            }
+        };
+
+        Ok(Aggregator {
+            asn,
+            ip: Ipv4Addr::from(stream.read_u32::<BigEndian>()?),
+        })
+    }
+}
+
+/// Represents the type of a segment inside an AS_PATH attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+#[repr(u8)]
+pub enum SegmentType {
+    /// An unordered set of ASes a route in the UPDATE message has traversed.
+    AS_SET = 1,
+
+    /// An ordered set of ASes a route in the UPDATE message has traversed.
+    AS_SEQUENCE = 2,
+
+    /// An ordered set of Member AS Numbers in the local confederation that the UPDATE message has traversed.
+    /// More information can found in [RFC5065](https://tools.ietf.org/html/rfc5065#section-3).
+    AS_CONFED_SEQUENCE = 3,
+
+    /// An unordered set of Member AS Numbers in the local confederation that the UPDATE message has traversed.
+    /// More information can found in [RFC5065](https://tools.ietf.org/html/rfc5065#section-3).
+    AS_CONFED_SET = 4,
+}
+
+impl SegmentType {
+    fn from(value: u8) -> Result<SegmentType, Error> {
+        match value {
+            1 => Ok(SegmentType::AS_SET),
+            2 => Ok(SegmentType::AS_SEQUENCE),
+            3 => Ok(SegmentType::AS_CONFED_SEQUENCE),
+            4 => Ok(SegmentType::AS_CONFED_SET),
+            x => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unknown AS_PATH segment type found: {}", x),
+            )),
+        }
+    }
+
+    /// Returns true if this segment type describes a path within a confederation.
+    pub fn is_confederation(self) -> bool {
+        matches!(
+            self,
+            SegmentType::AS_CONFED_SEQUENCE | SegmentType::AS_CONFED_SET
+        )
+    }
+}
+
+/// Represents a single segment of an AS_PATH attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// The type of this segment.
+    pub segment_type: SegmentType,
+
+    /// The ASNs contained in this segment.
+    pub asns: Vec<u32>,
+}
+
+/// Represents the AS_PATH path attribute.
+/// More information can found in [RFC4271](https://tools.ietf.org/html/rfc4271#section-5.1.2).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsPath {
+    /// The segments of which this AS_PATH consists.
+    pub segments: Vec<Segment>,
+}
+
+impl AsPath {
+    ///
+    /// # Summary
+    /// Used to parse the value of an AS_PATH attribute.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from the stream.
+    /// An error of kind `InvalidData` is returned if an unknown segment type is encountered, if
+    /// a segment claims more ASNs than the attribute contains or if the path contains more
+    /// ASNs than `ParseOptions::max_as_path_length` allows.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn parse(
+        mut stream: impl Read,
+        length: u16,
+        asn_size: AsnSize,
+        options: &ParseOptions,
+    ) -> Result<AsPath, Error> {
+        let mut buffer = vec![0; length as usize];
+        stream.read_exact(&mut buffer)?;
+
+        let mut segments = Vec::new();
+        let mut total = 0;
+        let mut remaining: &[u8] = &buffer;
+        while !remaining.is_empty() {
+            let segment_type = SegmentType::from(remaining.read_u8()?)?;
+            let count = remaining.read_u8()?;
+
+            // Both checks happen before allocating, so the claimed counts cannot cause any work.
+            if usize::from(count) * asn_size.size() as usize > remaining.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "AS_PATH segment claims {} ASNs but only {} bytes remain",
+                        count,
+                        remaining.len()
+                    ),
+                ));
+            }
+            total += usize::from(count);
+            if total > options.max_as_path_length {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "AS_PATH contains more than {} ASNs",
+                        options.max_as_path_length
+                    ),
+                ));
+            }
+
+            let mut asns = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                asns.push(asn_size.read(&mut remaining)?);
+            }
+
+            segments.push(Segment { segment_type, asns });
+        }
+
+        Ok(AsPath { segments })
+    }
+
+    /// Returns all ASNs of this AS_PATH in order of appearance.
+    pub fn asns(&self) -> Vec<u32> {
+        self.segments
+            .iter()
+            .flat_map(|segment| segment.asns.iter().cloned())
+            .collect()
+    }
+
+    ///
+    /// Returns the ASN that originated the route, being the last ASN of the last AS_SEQUENCE.
+    /// Trailing AS_SET and confederation segments are skipped, as the origin of an aggregate
+    /// is ambiguous. Returns `None` if the AS_PATH contains no AS_SEQUENCE.
+    ///
+    pub fn origin(&self) -> Option<u32> {
+        self.segments
+            .iter()
+            .rev()
+            .filter(|segment| segment.segment_type == SegmentType::AS_SEQUENCE)
+            .find_map(|segment| segment.asns.last().cloned())
+    }
+
+    ///
+    /// Returns the length of the AS_PATH as used by the route selection process of
+    /// [RFC4271](https://tools.ietf.org/html/rfc4271#section-9.1.2.2). Every ASN of an
+    /// AS_SEQUENCE counts as one, an AS_SET counts as one regardless of its size and
+    /// confederation segments are not counted.
+    ///
+    pub fn len(&self) -> usize {
+        path_length(self.segments.iter())
+    }
+
+    /// Returns true if the length of the AS_PATH, as returned by [`AsPath::len`], is zero.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///
+    /// Returns every ASN of the AS_PATH together with the number of consecutive times it
+    /// appears, across segment boundaries. A count above one indicates that the AS prepended
+    /// itself, such that `7018 7018 7018 174` results in `[(7018, 3), (174, 1)]`.
+    ///
+    pub fn prepend_counts(&self) -> Vec<(u32, usize)> {
+        let mut counts: Vec<(u32, usize)> = Vec::new();
+        for asn in self.segments.iter().flat_map(|segment| segment.asns.iter()) {
+            match counts.last_mut() {
+                Some((last, count)) if last == asn => *count += 1,
+                _ => counts.push((*asn, 1)),
+            }
+        }
+        counts
+    }
+
+    /// Returns all ASNs of this AS_PATH in order of appearance with prepended ASNs collapsed.
+    pub fn dedup_prepends(&self) -> Vec<u32> {
+        let mut asns = self.asns();
+        asns.dedup();
+        asns
+    }
+}
+
+///
+/// Formats the AS_PATH in the notation used by `bgpdump` and most routers. ASNs are separated
+/// by spaces, an AS_SET is written as `{1,2}`, an AS_CONFED_SEQUENCE as `(1 2)` and an
+/// AS_CONFED_SET as `[1,2]`.
+///
+impl fmt::Display for AsPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+
+            let (open, separator, close) = match segment.segment_type {
+                SegmentType::AS_SEQUENCE => ("", " ", ""),
+                SegmentType::AS_SET => ("{", ",", "}"),
+                SegmentType::AS_CONFED_SEQUENCE => ("(", " ", ")"),
+                SegmentType::AS_CONFED_SET => ("[", ",", "]"),
+            };
+
+            write!(f, "{}", open)?;
+            for (j, asn) in segment.asns.iter().enumerate() {
+                if j > 0 {
+                    write!(f, "{}", separator)?;
+                }
+                write!(f, "{}", asn)?;
+            }
+            write!(f, "{}", close)?;
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// Reconstructs the AS path of a route received from a 2-octet ASN speaker by merging its
+/// AS_PATH, which contains AS_TRANS placeholders, with its AS4_PATH.
+/// More information can found in [RFC6793](https://tools.ietf.org/html/rfc6793#section-4.2.3).
+///
+/// The number of ASNs in a path is counted as in the route selection process: an AS_SET counts
+/// as a single ASN and confederation segments are not counted at all. Confederation segments in
+/// the AS4_PATH are discarded. If the AS4_PATH then contains more ASNs than the AS_PATH, the
+/// AS4_PATH is ignored and the AS_PATH is returned. Otherwise as many leading segments and ASNs
+/// of the AS_PATH as necessary are prepended to the AS4_PATH such that the result contains the
+/// same number of ASNs as the AS_PATH. Leading confederation segments of the AS_PATH are kept.
+///
+pub fn reconstruct_as_path(as_path: &AsPath, as4_path: &AsPath) -> AsPath {
+    let as4_segments: Vec<&Segment> = as4_path
+        .segments
+        .iter()
+        .filter(|segment| !segment.segment_type.is_confederation())
+        .collect();
+
+    let length = path_length(as_path.segments.iter());
+    let as4_length = path_length(as4_segments.iter().cloned());
+    if as4_length > length {
+        return as_path.clone();
+    }
+
+    let mut remaining = length - as4_length;
+    let mut segments = Vec::new();
+    for segment in &as_path.segments {
+        match segment.segment_type {
+            x if x.is_confederation() => segments.push(segment.clone()),
+            SegmentType::AS_SET if remaining > 0 => {
+                segments.push(segment.clone());
+                remaining -= 1;
+            }
+            SegmentType::AS_SET => break,
+            _ => {
+                let taken = remaining.min(segment.asns.len());
+                if taken > 0 {
+                    segments.push(Segment {
+                        segment_type: SegmentType::AS_SEQUENCE,
+                        asns: segment.asns[..taken].to_vec(),
+                    });
+                }
+
+                remaining -= taken;
+                if taken < segment.asns.len() {
+                    break;
+                }
+            }
+        }
+    }
+
+    for segment in as4_segments {
+        match segments.last_mut() {
+            // Join the AS_SEQUENCE that was split off the AS_PATH with the one of the AS4_PATH.
+            Some(last)
+                if last.segment_type == SegmentType::AS_SEQUENCE
+                    && segment.segment_type == SegmentType::AS_SEQUENCE =>
+            {
+                last.asns.extend(&segment.asns)
+            }
+            _ => segments.push(segment.clone()),
+        }
+    }
+
+    AsPath { segments }
+}
+
+/// Counts the ASNs of the given segments as done in the route selection process.
+fn path_length<'a>(segments: impl Iterator<Item = &'a Segment>) -> usize {
+    segments
+        .map(|segment| match segment.segment_type {
+            SegmentType::AS_SEQUENCE => segment.asns.len(),
+            SegmentType::AS_SET => 1,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Represents the value of the MP_REACH_NLRI path attribute.
+/// More information can found in [RFC4760](https://tools.ietf.org/html/rfc4760#section-3).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MpReachNlri {
+    /// The AFI of the next hop and the NLRI. `None` if the attribute uses the abbreviated form
+    /// of TABLE_DUMP_V2 RIB entries, which only contains the next hop.
+    pub afi: Option<u16>,
+
+    /// The SAFI of the NLRI. `None` if the attribute uses the abbreviated form.
+    pub safi: Option<u8>,
+
+    /// The address(es) of the next hop.
+    pub next_hop: Vec<u8>,
+
+    /// The announced prefixes, including their path identifier if ADD-PATH is used.
+    pub prefixes: Vec<Prefix>,
+}
+
+impl MpReachNlri {
+    ///
+    /// # Summary
+    /// Parses the value of an MP_REACH_NLRI attribute. Whether the prefixes are preceded by a
+    /// path identifier is not encoded in the attribute and must be derived from its context, such
+    /// as the subtype of the BGP4MP or TABLE_DUMP_V2 record containing it.
+    ///
+    /// The abbreviated form used by TABLE_DUMP_V2 RIB entries, which only consists of the length
+    /// of the next hop followed by the next hop, is recognized as described in
+    /// [RFC6396](https://tools.ietf.org/html/rfc6396#section-4.3.4).
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// An error of kind `InvalidData` is returned if the attribute is ill-formatted, contains
+    /// NLRI of an address family other than IPv4 or IPv6 unicast or multicast, or if
+    /// `options.strict` is set and a prefix is longer than the addresses of its family.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn parse(
+        mut value: &[u8],
+        add_path: bool,
+        options: &ParseOptions,
+    ) -> Result<MpReachNlri, Error> {
+        if !value.is_empty() && usize::from(value[0]) == value.len() - 1 {
+            return Ok(MpReachNlri {
+                afi: None,
+                safi: None,
+                next_hop: value[1..].to_vec(),
+                prefixes: Vec::new(),
+            });
+        }
+
+        let afi = value.read_u16::<BigEndian>()?;
+        let safi = value.read_u8()?;
+        let next_hop_length = value.read_u8()?;
+        let mut next_hop = vec![0; usize::from(next_hop_length)];
+        value.read_exact(&mut next_hop)?;
+        let _reserved = value.read_u8()?;
+
+        let prefixes = match safi {
+            1 | 2 => nlri::read_prefixes(value, AFI::from(afi)?, add_path, options)?,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("NLRI of AFI {} and SAFI {} are not supported.", afi, safi),
+                ))
+            }
+        };
+
+        Ok(MpReachNlri {
+            afi: Some(afi),
+            safi: Some(safi),
+            next_hop,
+            prefixes,
+        })
+    }
+}
+
+///
+/// Finds and parses the MP_REACH_NLRI attribute among the attributes of a RIB entry, which
+/// always use 4-byte ASNs. Returns `None` if the attribute is not present.
+///
+pub(crate) fn find_mp_reach_nlri(
+    attributes: &[u8],
+    add_path: bool,
+) -> Result<Option<MpReachNlri>, Error> {
+    let options = ParseOptions::default();
+    for attribute in parse(attributes, AsnSize::Four, &options)? {
+        if let PathAttribute::MP_REACH_NLRI(value) = attribute {
+            return MpReachNlri::parse(&value, add_path, &options).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+/// Represents the Global Administrator field of an extended community.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Administrator {
+    /// A 2-octet ASN as used by the Two-Octet AS Specific extended communities.
+    TwoOctetAs(u16),
+
+    /// An IPv4 address as used by the IPv4 Address Specific extended communities.
+    Ipv4(Ipv4Addr),
+
+    /// A 4-octet ASN as used by the Four-Octet AS Specific extended communities.
+    FourOctetAs(u32),
+}
+
+/// Represents a single extended community classified by its type and sub-type.
+/// More information can found in [RFC4360](https://tools.ietf.org/html/rfc4360) and
+/// [RFC5668](https://tools.ietf.org/html/rfc5668).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtendedCommunity {
+    /// Identifies the set of sites to which a route may be distributed.
+    RouteTarget {
+        /// The Global Administrator of this Route Target.
+        administrator: Administrator,
+
+        /// The Local Administrator assigned number of this Route Target.
+        assigned_number: u32,
+    },
+
+    /// Identifies the site(s) from which a route has originated.
+    RouteOrigin {
+        /// The Global Administrator of this Route Origin.
+        administrator: Administrator,
+
+        /// The Local Administrator assigned number of this Route Origin.
+        assigned_number: u32,
+    },
+
+    /// An extended community of a type or sub-type that is not classified.
+    Unknown([u8; 8]),
+}
+
+impl ExtendedCommunity {
+    ///
+    /// # Summary
+    /// Used to parse a single 8-byte extended community.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while reading from the stream.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn parse(mut stream: impl Read) -> Result<ExtendedCommunity, Error> {
+        let mut bytes = [0; 8];
+        stream.read_exact(&mut bytes)?;
+        Ok(ExtendedCommunity::from(bytes))
+    }
+}
+
+impl From<[u8; 8]> for ExtendedCommunity {
+    fn from(bytes: [u8; 8]) -> ExtendedCommunity {
+        let two_octet = |x: &[u8]| u16::from_be_bytes([x[0], x[1]]);
+        let four_octet = |x: &[u8]| u32::from_be_bytes([x[0], x[1], x[2], x[3]]);
+
+        // The high-order type octet determines the layout of the value field.
+        let (administrator, assigned_number) = match bytes[0] {
+            0x00 => (
+                Administrator::TwoOctetAs(two_octet(&bytes[2..4])),
+                four_octet(&bytes[4..8]),
+            ),
+            0x01 => (
+                Administrator::Ipv4(Ipv4Addr::new(bytes[2], bytes[3], bytes[4], bytes[5])),
+                u32::from(two_octet(&bytes[6..8])),
+            ),
+            0x02 => (
+                Administrator::FourOctetAs(four_octet(&bytes[2..6])),
+                u32::from(two_octet(&bytes[6..8])),
+            ),
+            _ => return ExtendedCommunity::Unknown(bytes),
+        };
+
+        match bytes[1] {
+            0x02 => ExtendedCommunity::RouteTarget {
+                administrator,
+                assigned_number,
+            },
+            0x03 => ExtendedCommunity::RouteOrigin {
+                administrator,
+                assigned_number,
+            },
+            _ => ExtendedCommunity::Unknown(bytes),
+        }
+    }
+}
@@ -1,11 +1,19 @@
-use byteorder::{BigEndian, ReadBytesExt};
-use std::io::{Error, ErrorKind, Read};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::borrow::Cow;
+use std::io::{Error, ErrorKind, Read, Write};
 use std::net::Ipv4Addr;
 
+use crate::attributes::{self, PathAttribute};
+use crate::codes;
+use crate::nlri::{self, Prefix};
+use crate::AsnSize;
 use crate::Header;
+use crate::MrtError;
+use crate::ParseOptions;
+use crate::{AFI, SAFI};
 
 /// The BGP enum represents all possible subtypes of the BGP record type.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(missing_docs)]
 #[allow(non_camel_case_types)]
 pub enum BGP {
@@ -21,26 +29,46 @@ pub enum BGP {
 
 /// Used for the deprecated BGP message type.
 impl BGP {
+    /// Returns the subtype of the MRT record from which this message is parsed.
+    pub fn sub_type(&self) -> u16 {
+        match self {
+            BGP::NULL => codes::bgp::NULL,
+            BGP::UPDATE(_) => codes::bgp::UPDATE,
+            BGP::PREF_UPDATE => codes::bgp::PREF_UPDATE,
+            BGP::STATE_CHANGE(_) => codes::bgp::STATE_CHANGE,
+            BGP::SYNC(_) => codes::bgp::SYNC,
+            BGP::OPEN(_) => codes::bgp::OPEN,
+            BGP::NOTIFY(_) => codes::bgp::NOTIFY,
+            BGP::KEEPALIVE(_) => codes::bgp::KEEPALIVE,
+        }
+    }
+
     pub(crate) fn parse(header: &Header, stream: impl Read) -> Result<BGP, Error> {
         match header.sub_type {
-            0 => Ok(BGP::NULL),
-            1 => Ok(BGP::UPDATE(MESSAGE::parse(header, stream)?)),
-            2 => Ok(BGP::PREF_UPDATE),
-            3 => Ok(BGP::STATE_CHANGE(STATE_CHANGE::parse(stream)?)),
-            4 => Ok(BGP::SYNC(SYNC::parse(stream)?)),
-            5 => Ok(BGP::OPEN(MESSAGE::parse(header, stream)?)),
-            6 => Ok(BGP::NOTIFY(MESSAGE::parse(header, stream)?)),
-            7 => Ok(BGP::KEEPALIVE(MESSAGE::parse(header, stream)?)),
-            _ => Err(Error::new(
-                ErrorKind::Other,
-                "Unknown record subtype found in MRT header",
-            )),
+            codes::bgp::NULL => Ok(BGP::NULL),
+            codes::bgp::UPDATE => Ok(BGP::UPDATE(MESSAGE::parse(header, stream)?)),
+            codes::bgp::PREF_UPDATE => Ok(BGP::PREF_UPDATE),
+            codes::bgp::STATE_CHANGE => Ok(BGP::STATE_CHANGE(STATE_CHANGE::parse(stream)?)),
+            codes::bgp::SYNC => Ok(BGP::SYNC(SYNC::parse(header, stream)?)),
+            codes::bgp::OPEN => Ok(BGP::OPEN(MESSAGE::parse(header, stream)?)),
+            codes::bgp::NOTIFY => Ok(BGP::NOTIFY(MESSAGE::parse(header, stream)?)),
+            codes::bgp::KEEPALIVE => Ok(BGP::KEEPALIVE(MESSAGE::parse(header, stream)?)),
+            _ => Err(Error::other("Unknown record subtype found in MRT header")),
+        }
+    }
+
+    pub(crate) fn write(&self, stream: impl Write) -> Result<(), Error> {
+        match self {
+            BGP::NULL | BGP::PREF_UPDATE => Ok(()),
+            BGP::UPDATE(x) | BGP::OPEN(x) | BGP::NOTIFY(x) | BGP::KEEPALIVE(x) => x.write(stream),
+            BGP::STATE_CHANGE(x) => x.write(stream),
+            BGP::SYNC(x) => x.write(stream),
         }
     }
 }
 
 /// Represents the UPDATE, OPEN, NOTIFY and KEEPALIVE messages.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct MESSAGE {
     /// The peer ASN from which the BGP message has been received.
@@ -66,8 +94,8 @@ impl MESSAGE {
         let local_as = stream.read_u16::<BigEndian>()?;
         let local_ip = Ipv4Addr::from(stream.read_u32::<BigEndian>()?);
 
-        let length = header.length - 12;
-        let mut message = vec![0; length as usize];
+        let length = crate::message_length(header, 12)?;
+        let mut message = vec![0; length];
         stream.read_exact(&mut message)?;
 
         Ok(MESSAGE {
@@ -78,13 +106,21 @@ impl MESSAGE {
             message,
         })
     }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(self.peer_as)?;
+        stream.write_u32::<BigEndian>(u32::from(self.peer_ip))?;
+        stream.write_u16::<BigEndian>(self.local_as)?;
+        stream.write_u32::<BigEndian>(u32::from(self.local_ip))?;
+        stream.write_all(&self.message)
+    }
 }
 
 ///
 /// Represents a state change in the BGP Finite State Machine (FSM).
 /// More information can found in [RFC4271](https://tools.ietf.org/html/rfc4271#section-8).
 ///
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct STATE_CHANGE {
     /// The peer ASN from which the BGP message has been received.
@@ -109,33 +145,582 @@ impl STATE_CHANGE {
             new_state: stream.read_u16::<BigEndian>()?,
         })
     }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(self.peer_as)?;
+        stream.write_u32::<BigEndian>(u32::from(self.peer_ip))?;
+        stream.write_u16::<BigEndian>(self.old_state)?;
+        stream.write_u16::<BigEndian>(self.new_state)
+    }
 }
 
 /// Deprecated: Used to record RIB entries in a file.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct SYNC {
     /// The associated view number.
     pub view_number: u16,
 
-    /// The NULL-terminated filename of the file where RIB entries are recorded.
+    /// The filename of the file where RIB entries are recorded. Stored without the terminating
+    /// NUL, which is written again if `nul_terminated` is set.
     pub filename: Vec<u8>,
+
+    /// Whether the filename was terminated by a NUL within the record. Some encoders omit it,
+    /// in which case the filename ends with the record.
+    pub nul_terminated: bool,
 }
 
 impl SYNC {
-    fn parse(mut stream: impl Read) -> Result<SYNC, Error> {
+    fn parse(header: &Header, mut stream: impl Read) -> Result<SYNC, Error> {
         let view_number = stream.read_u16::<BigEndian>()?;
-        let mut filename = Vec::new();
-
-        let mut buffer = stream.read_u8()?;
-        while buffer != b'\0' {
-            filename.push(buffer);
-            buffer = stream.read_u8()?;
-        }
+        let (filename, nul_terminated) =
+            crate::read_nul_terminated(&mut stream, header.length.saturating_sub(2))?;
 
         Ok(SYNC {
             view_number,
             filename,
+            nul_terminated,
         })
     }
+
+    /// Returns the filename of the file where RIB entries are recorded as raw bytes, excluding the NUL terminator.
+    pub fn filename_bytes(&self) -> &[u8] {
+        &self.filename
+    }
+
+    /// Returns the filename of the file where RIB entries are recorded, replacing invalid UTF-8 sequences by U+FFFD.
+    pub fn filename_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.filename)
+    }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(self.view_number)?;
+        stream.write_all(&self.filename)?;
+        if self.nul_terminated {
+            stream.write_u8(b'\0')?;
+        }
+        Ok(())
+    }
+}
+
+/// The length of the header preceding every BGP message.
+const BGP_HEADER_LENGTH: usize = 19;
+
+/// The message type code of a BGP OPEN message.
+const OPEN_TYPE: u8 = 1;
+
+/// The message type code of a BGP UPDATE message.
+pub(crate) const UPDATE_TYPE: u8 = 2;
+
+/// The message type code of a BGP ROUTE-REFRESH message.
+const ROUTE_REFRESH_TYPE: u8 = 5;
+
+///
+/// Represents a decoded BGP UPDATE message.
+/// More information can found in [RFC4271](https://tools.ietf.org/html/rfc4271#section-4.3).
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Update {
+    /// The prefixes that are no longer reachable, as (prefix length, prefix bytes) pairs.
+    pub withdrawn_routes: Vec<(u8, Vec<u8>)>,
+
+    /// The path attributes that apply to all announced prefixes.
+    pub attributes: Vec<PathAttribute>,
+
+    /// The announced prefixes at the end of the message, as (prefix length, prefix bytes) pairs.
+    pub nlri: Vec<(u8, Vec<u8>)>,
+}
+
+impl Update {
+    /// Returns true if this UPDATE neither withdraws nor announces any IPv4 prefixes.
+    /// Such an UPDATE without path attributes is used as the End-of-RIB marker.
+    pub fn is_empty(&self) -> bool {
+        self.withdrawn_routes.is_empty() && self.nlri.is_empty()
+    }
+}
+
+///
+/// # Summary
+/// Used to parse a BGP UPDATE message, such as the `message` field of a BGP4MP MESSAGE record.
+/// The message is expected to start with the 19-byte BGP header. The messages of sessions
+/// using ADD-PATH are parsed using `parse_update_add_path` instead. The options apply to the
+/// path attributes and the prefixes of the message.
+///
+/// # Panics
+/// This function does not panic.
+///
+/// # Errors
+/// An error of kind `InvalidData` is returned if the message is not an UPDATE message, if
+/// the lengths of its sections do not match the size of the message, or if `options.strict` is
+/// set and a prefix is longer than 32 bits.
+///
+/// # Safety
+/// This function does not make use of unsafe code.
+///
+pub fn parse_update(
+    message: &[u8],
+    asn_size: AsnSize,
+    options: &ParseOptions,
+) -> Result<Update, Error> {
+    let mut body = message_body(message, UPDATE_TYPE)?;
+
+    let withdrawn_length = body.read_u16::<BigEndian>()? as usize;
+    let withdrawn = take(&mut body, withdrawn_length)?;
+    let attribute_length = body.read_u16::<BigEndian>()? as usize;
+    let attributes = take(&mut body, attribute_length)?;
+
+    Ok(Update {
+        withdrawn_routes: read_prefixes(withdrawn, options)?,
+        attributes: attributes::parse(attributes, asn_size, options)?,
+        nlri: read_prefixes(body, options)?,
+    })
+}
+
+///
+/// Represents a decoded BGP UPDATE message of a session using ADD-PATH, of which every prefix
+/// is preceded by a path identifier.
+/// More information can found in [RFC7911](https://tools.ietf.org/html/rfc7911#section-3).
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateAddPath {
+    /// The prefixes that are no longer reachable, as (path identifier, prefix length, prefix
+    /// bytes) tuples.
+    pub withdrawn_routes: Vec<Prefix>,
+
+    /// The path attributes that apply to all announced prefixes.
+    pub attributes: Vec<PathAttribute>,
+
+    /// The announced prefixes at the end of the message, as (path identifier, prefix length,
+    /// prefix bytes) tuples.
+    pub nlri: Vec<Prefix>,
+}
+
+///
+/// # Summary
+/// Used to parse a BGP UPDATE message of a session using ADD-PATH, such as the `message` field
+/// of a BGP4MP MESSAGE_ADDPATH or MESSAGE_AS4_ADDPATH record. The message is expected to start
+/// with the 19-byte BGP header. The options apply to the path attributes and the prefixes of
+/// the message.
+///
+/// # Panics
+/// This function does not panic.
+///
+/// # Errors
+/// An error of kind `InvalidData` is returned if the message is not an UPDATE message, if
+/// the lengths of its sections do not match the size of the message, or if `options.strict` is
+/// set and a prefix is longer than 32 bits.
+///
+/// # Safety
+/// This function does not make use of unsafe code.
+///
+pub fn parse_update_add_path(
+    message: &[u8],
+    asn_size: AsnSize,
+    options: &ParseOptions,
+) -> Result<UpdateAddPath, Error> {
+    let mut body = message_body(message, UPDATE_TYPE)?;
+
+    let withdrawn_length = body.read_u16::<BigEndian>()? as usize;
+    let withdrawn = take(&mut body, withdrawn_length)?;
+    let attribute_length = body.read_u16::<BigEndian>()? as usize;
+    let attributes = take(&mut body, attribute_length)?;
+
+    Ok(UpdateAddPath {
+        withdrawn_routes: nlri::read_prefixes(withdrawn, AFI::IPV4, true, options)?,
+        attributes: attributes::parse(attributes, asn_size, options)?,
+        nlri: nlri::read_prefixes(body, AFI::IPV4, true, options)?,
+    })
+}
+
+/// Returns the body of a BGP message after checking its header and that it is of the expected type.
+fn message_body(message: &[u8], message_type: u8) -> Result<&[u8], Error> {
+    let header = MessageHeader::read(message)?;
+    if header.message_type != message_type {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("BGP message is not of type {}.", message_type),
+        ));
+    }
+
+    Ok(&message[header.body_offset..])
+}
+
+///
+/// Represents the header preceding every BGP message, of which the marker has been validated.
+/// More information can found in [RFC4271](https://tools.ietf.org/html/rfc4271#section-4.1).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageHeader {
+    /// The length of the entire BGP message including this header.
+    pub length: u16,
+
+    /// The type code of the message, such as 2 for an UPDATE or 4 for a KEEPALIVE message.
+    pub message_type: u8,
+
+    /// The offset of the body of the message, directly following this header.
+    pub body_offset: usize,
+}
+
+impl MessageHeader {
+    ///
+    /// # Summary
+    /// Parses the 19-byte header at the start of a BGP message, such as the `message` field of a
+    /// BGP4MP MESSAGE record.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// An error of kind `UnexpectedEof` is returned if the buffer is shorter than the header.
+    /// An error of kind `InvalidData` is returned if the marker is not all ones, or if the
+    /// declared length of the message does not match the length of the buffer.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn parse(bytes: &[u8]) -> Result<MessageHeader, MrtError> {
+        Ok(MessageHeader::read(bytes)?)
+    }
+
+    /// Parses and validates the header at the start of a BGP message.
+    fn read(bytes: &[u8]) -> Result<MessageHeader, Error> {
+        if bytes.len() < BGP_HEADER_LENGTH {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "BGP message is shorter than the BGP header.",
+            ));
+        }
+        if bytes[..16].iter().any(|x| *x != 0xFF) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "BGP message does not start with a marker of all ones.",
+            ));
+        }
+
+        let length = u16::from_be_bytes([bytes[16], bytes[17]]);
+        if usize::from(length) != bytes.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "BGP message has a length of {} bytes while {} bytes are present.",
+                    length,
+                    bytes.len()
+                ),
+            ));
+        }
+
+        Ok(MessageHeader {
+            length,
+            message_type: bytes[18],
+            body_offset: BGP_HEADER_LENGTH,
+        })
+    }
+}
+
+/// Splits the given number of bytes off the front of the buffer.
+fn take<'a>(bytes: &mut &'a [u8], length: usize) -> Result<&'a [u8], Error> {
+    if length > bytes.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Section length exceeds the size of the BGP message.",
+        ));
+    }
+
+    let (head, tail) = bytes.split_at(length);
+    *bytes = tail;
+    Ok(head)
+}
+
+/// Reads (prefix length, prefix bytes) pairs until the end of the buffer has been reached.
+fn read_prefixes(bytes: &[u8], options: &ParseOptions) -> Result<Vec<(u8, Vec<u8>)>, Error> {
+    let prefixes = nlri::read_prefixes(bytes, AFI::IPV4, false, options)?;
+    Ok(prefixes
+        .into_iter()
+        .map(|(_, length, prefix)| (length, prefix))
+        .collect())
+}
+
+///
+/// Represents a decoded BGP OPEN message.
+/// More information can found in [RFC4271](https://tools.ietf.org/html/rfc4271#section-4.2).
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Open {
+    /// The BGP protocol version, which is 4 for current BGP speakers.
+    pub version: u8,
+
+    /// The 2-byte ASN of the sender. Set to AS_TRANS if the sender has a 4-byte ASN.
+    pub my_as: u16,
+
+    /// The proposed number of seconds between KEEPALIVE or UPDATE messages.
+    pub hold_time: u16,
+
+    /// The BGP identifier of the sender.
+    pub bgp_identifier: Ipv4Addr,
+
+    /// The capabilities advertised in the optional parameters. Other optional parameters are ignored.
+    pub capabilities: Vec<Capability>,
+}
+
+impl Open {
+    /// Returns the ASN of the sender, taking the 4-octet AS number capability into account.
+    pub fn asn(&self) -> u32 {
+        self.capabilities
+            .iter()
+            .find_map(|capability| match capability {
+                Capability::FourOctetAs(asn) => Some(*asn),
+                _ => None,
+            })
+            .unwrap_or_else(|| u32::from(self.my_as))
+    }
+
+    /// Returns the ASN size the sender supports, based on the 4-octet AS number capability.
+    pub fn asn_size(&self) -> AsnSize {
+        if self
+            .capabilities
+            .iter()
+            .any(|capability| matches!(capability, Capability::FourOctetAs(_)))
+        {
+            AsnSize::Four
+        } else {
+            AsnSize::Two
+        }
+    }
+}
+
+///
+/// Represents a capability advertised in a BGP OPEN message.
+/// More information can found in [RFC5492](https://tools.ietf.org/html/rfc5492).
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Capability {
+    /// Support for the given AFI and SAFI as defined in [RFC4760](https://tools.ietf.org/html/rfc4760#section-8).
+    Multiprotocol {
+        /// The Address Family Identifier (AFI).
+        afi: u16,
+        /// The Subsequent Address Family Identifier (SAFI).
+        safi: u8,
+    },
+
+    /// Support for the ROUTE-REFRESH message as defined in [RFC2918](https://tools.ietf.org/html/rfc2918).
+    RouteRefresh,
+
+    /// Support for Graceful Restart as defined in [RFC4724](https://tools.ietf.org/html/rfc4724#section-3).
+    GracefulRestart(GracefulRestart),
+
+    /// Support for 4-byte ASNs containing the ASN of the sender, as defined in [RFC6793](https://tools.ietf.org/html/rfc6793).
+    FourOctetAs(u32),
+
+    /// Support for advertising multiple paths as defined in [RFC7911](https://tools.ietf.org/html/rfc7911#section-4).
+    AddPath(Vec<AddPathFamily>),
+
+    /// A capability that is not supported, containing its code and raw value.
+    Unknown(u8, Vec<u8>),
+}
+
+impl Capability {
+    fn parse(code: u8, mut value: &[u8]) -> Result<Capability, Error> {
+        match code {
+            1 => {
+                let afi = value.read_u16::<BigEndian>()?;
+                value.read_u8()?;
+                let safi = value.read_u8()?;
+                Ok(Capability::Multiprotocol { afi, safi })
+            }
+            2 => Ok(Capability::RouteRefresh),
+            64 => {
+                let flags_and_time = value.read_u16::<BigEndian>()?;
+                let mut families = Vec::with_capacity(value.len() / 4);
+                while !value.is_empty() {
+                    families.push(GracefulRestartFamily {
+                        afi: value.read_u16::<BigEndian>()?,
+                        safi: value.read_u8()?,
+                        forwarding_preserved: value.read_u8()? & 0x80 != 0,
+                    });
+                }
+
+                Ok(Capability::GracefulRestart(GracefulRestart {
+                    restart_flags: (flags_and_time >> 12) as u8,
+                    restart_time: flags_and_time & 0x0FFF,
+                    families,
+                }))
+            }
+            65 => Ok(Capability::FourOctetAs(value.read_u32::<BigEndian>()?)),
+            69 => {
+                let mut families = Vec::with_capacity(value.len() / 4);
+                while !value.is_empty() {
+                    let afi = value.read_u16::<BigEndian>()?;
+                    let safi = value.read_u8()?;
+                    let direction = match value.read_u8()? {
+                        1 => AddPathDirection::Receive,
+                        2 => AddPathDirection::Send,
+                        3 => AddPathDirection::Both,
+                        x => {
+                            return Err(Error::new(
+                                ErrorKind::InvalidData,
+                                format!("Invalid Send/Receive value in ADD-PATH capability: {}", x),
+                            ))
+                        }
+                    };
+                    families.push(AddPathFamily {
+                        afi,
+                        safi,
+                        direction,
+                    });
+                }
+
+                Ok(Capability::AddPath(families))
+            }
+            x => Ok(Capability::Unknown(x, value.to_vec())),
+        }
+    }
+}
+
+/// The Graceful Restart parameters advertised by a BGP speaker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GracefulRestart {
+    /// The four Restart Flags bits. The most significant bit (0x8) indicates a restart.
+    pub restart_flags: u8,
+
+    /// The estimated number of seconds it takes for the BGP session to be re-established.
+    pub restart_time: u16,
+
+    /// The address families for which Graceful Restart is supported.
+    pub families: Vec<GracefulRestartFamily>,
+}
+
+/// An address family for which Graceful Restart is supported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GracefulRestartFamily {
+    /// The Address Family Identifier (AFI).
+    pub afi: u16,
+
+    /// The Subsequent Address Family Identifier (SAFI).
+    pub safi: u8,
+
+    /// Whether the forwarding state has been preserved during the restart.
+    pub forwarding_preserved: bool,
+}
+
+/// An address family for which multiple paths can be advertised.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddPathFamily {
+    /// The Address Family Identifier (AFI).
+    pub afi: u16,
+
+    /// The Subsequent Address Family Identifier (SAFI).
+    pub safi: u8,
+
+    /// Whether the sender is able to send, receive or both send and receive multiple paths.
+    pub direction: AddPathDirection,
+}
+
+/// Indicates whether multiple paths can be received, sent or both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddPathDirection {
+    /// The sender is able to receive multiple paths.
+    Receive = 1,
+
+    /// The sender is able to send multiple paths.
+    Send = 2,
+
+    /// The sender is able to both send and receive multiple paths.
+    Both = 3,
+}
+
+///
+/// # Summary
+/// Used to parse a BGP OPEN message, such as the `message` field of a BGP4MP MESSAGE record.
+/// The message is expected to start with the 19-byte BGP header.
+///
+/// # Panics
+/// This function does not panic.
+///
+/// # Errors
+/// An error of kind `InvalidData` is returned if the message is not an OPEN message, or if
+/// the lengths of its optional parameters or capabilities do not match the size of the message.
+///
+/// # Safety
+/// This function does not make use of unsafe code.
+///
+pub fn parse_open(message: &[u8]) -> Result<Open, Error> {
+    let mut body = message_body(message, OPEN_TYPE)?;
+
+    let version = body.read_u8()?;
+    let my_as = body.read_u16::<BigEndian>()?;
+    let hold_time = body.read_u16::<BigEndian>()?;
+    let bgp_identifier = Ipv4Addr::from(body.read_u32::<BigEndian>()?);
+    let parameters_length = body.read_u8()?;
+    let mut parameters = take(&mut body, usize::from(parameters_length))?;
+
+    let mut capabilities = Vec::new();
+    while !parameters.is_empty() {
+        let parameter_type = parameters.read_u8()?;
+        let parameter_length = parameters.read_u8()?;
+        let mut parameter = take(&mut parameters, usize::from(parameter_length))?;
+
+        // Only the Capabilities parameter is decoded, which may contain multiple capabilities.
+        if parameter_type != 2 {
+            continue;
+        }
+        while !parameter.is_empty() {
+            let code = parameter.read_u8()?;
+            let length = parameter.read_u8()?;
+            let value = take(&mut parameter, usize::from(length))?;
+            capabilities.push(Capability::parse(code, value)?);
+        }
+    }
+
+    Ok(Open {
+        version,
+        my_as,
+        hold_time,
+        bgp_identifier,
+        capabilities,
+    })
+}
+
+///
+/// Represents a decoded BGP ROUTE-REFRESH message.
+/// More information can found in [RFC2918](https://tools.ietf.org/html/rfc2918#section-3) and
+/// [RFC7313](https://tools.ietf.org/html/rfc7313#section-3.2).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteRefresh {
+    /// The address family of the routes that should be advertised again.
+    pub afi: AFI,
+
+    /// The subsequent address family of the routes that should be advertised again.
+    pub safi: SAFI,
+
+    /// The message subtype of Enhanced Route Refresh, being 0 for a normal route refresh
+    /// request, 1 for a Beginning-of-RIB-Route-Refresh and 2 for an End-of-RIB-Route-Refresh.
+    /// Always 0 for speakers that do not support Enhanced Route Refresh.
+    pub subtype: u8,
+}
+
+///
+/// # Summary
+/// Used to parse a BGP ROUTE-REFRESH message, such as the `message` field of a BGP4MP MESSAGE
+/// record. The message is expected to start with the 19-byte BGP header. Outbound Route Filters
+/// following the fixed fields are ignored.
+///
+/// # Panics
+/// This function does not panic.
+///
+/// # Errors
+/// An error of kind `InvalidData` is returned if the message is not a ROUTE-REFRESH message.
+/// An error is returned if the message is truncated or if its AFI or SAFI is not supported.
+///
+/// # Safety
+/// This function does not make use of unsafe code.
+///
+pub fn parse_route_refresh(message: &[u8]) -> Result<RouteRefresh, Error> {
+    let mut body = message_body(message, ROUTE_REFRESH_TYPE)?;
+
+    let afi = AFI::from(body.read_u16::<BigEndian>()?)?;
+    let subtype = body.read_u8()?;
+    let safi = SAFI::from(body.read_u8()?)?;
+
+    Ok(RouteRefresh { afi, safi, subtype })
 }
@@ -1,14 +1,23 @@
-use byteorder::{BigEndian, ReadBytesExt};
-use std::io::{Error, ErrorKind, Read};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::io::{Error, ErrorKind, Read, Write};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
+use crate::codes;
+use crate::records::attributes::{self, PathAttribute};
+use crate::records::nlri;
+use crate::writer::{afi_of, write_address, write_u16_prefixed};
+use crate::AsnSize;
 use crate::Header;
+use crate::MrtError;
+use crate::ParseOptions;
 use crate::AFI;
 
 ///
 /// The BGP4MP enum represents all possible subtypes of the BGP4MP record type.
 ///
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub enum BGP4MP {
     /// Represents a state change of the BGP collector using 16 bit ASN.
@@ -56,7 +65,7 @@ pub enum BGP4MP {
 /// Represents a state change in the BGP Finite State Machine (FSM).
 /// More information can found in [RFC4271](https://tools.ietf.org/html/rfc4271#section-8).
 ///
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct STATE_CHANGE {
     /// The peer ASN from which the BGP message has been received.
@@ -65,13 +74,15 @@ pub struct STATE_CHANGE {
     /// The ASN of the AS that received this BGP message.
     pub local_as: u16,
 
-    /// The interface identifier to which this message applies.
+    /// The interface identifier to which this message applies, see [`SessionKey`].
     pub interface: u16,
 
     /// The peer IP address address from which the BGP message has been received.
     pub peer_address: IpAddr,
 
     /// The IP address of the AS that received this BGP message.
+    /// BIRD sets the addresses that are not known yet to `0.0.0.0` while a session is being
+    /// established.
     pub local_address: IpAddr,
 
     /// The old state of the BGP collector.
@@ -82,36 +93,36 @@ pub struct STATE_CHANGE {
 }
 
 impl STATE_CHANGE {
-    fn parse(mut stream: impl Read) -> Result<STATE_CHANGE, Error> {
-        let peer_as = stream.read_u16::<BigEndian>()?;
-        let local_as = stream.read_u16::<BigEndian>()?;
-        let interface = stream.read_u16::<BigEndian>()?;
-        let afi = stream.read_u16::<BigEndian>()?;
-        let peer_address = match AFI::from(afi)? {
-            AFI::IPV4 => IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-            AFI::IPV6 => IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
-        };
-        let local_address = match AFI::from(afi)? {
-            AFI::IPV4 => IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-            AFI::IPV6 => IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
-        };
+    fn parse(mut stream: impl Read, asn_size: AsnSize) -> Result<STATE_CHANGE, Error> {
+        let peer = Peer::parse(&mut stream, asn_size, false)?;
         let old_state = stream.read_u16::<BigEndian>()?;
         let new_state = stream.read_u16::<BigEndian>()?;
 
         Ok(STATE_CHANGE {
-            peer_as,
-            local_as,
-            interface,
-            peer_address,
-            local_address,
+            peer_as: peer.peer_as as u16,
+            local_as: peer.local_as as u16,
+            interface: peer.interface,
+            peer_address: peer.peer_address,
+            local_address: peer.local_address,
             old_state,
             new_state,
         })
     }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(self.peer_as)?;
+        stream.write_u16::<BigEndian>(self.local_as)?;
+        stream.write_u16::<BigEndian>(self.interface)?;
+        stream.write_u16::<BigEndian>(afi_of(&self.peer_address, &self.local_address)?)?;
+        write_address(&mut stream, &self.peer_address)?;
+        write_address(&mut stream, &self.local_address)?;
+        stream.write_u16::<BigEndian>(self.old_state)?;
+        stream.write_u16::<BigEndian>(self.new_state)
+    }
 }
 
 /// Represents a BGP message (UPDATE, OPEN, NOTIFICATION and KEEPALIVE) using 16bit ASN.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct MESSAGE {
     /// The peer ASN from which the BGP message has been received.
@@ -120,7 +131,7 @@ pub struct MESSAGE {
     /// The ASN of the AS that received this BGP message.
     pub local_as: u16,
 
-    /// The interface identifier to which this message applies.
+    /// The interface identifier to which this message applies, see [`SessionKey`].
     pub interface: u16,
 
     /// The peer IP address address from which the BGP message has been received.
@@ -133,38 +144,102 @@ pub struct MESSAGE {
     pub message: Vec<u8>,
 }
 
-impl MESSAGE {
-    fn parse(header: &Header, mut stream: impl Read) -> Result<MESSAGE, Error> {
-        let peer_as = stream.read_u16::<BigEndian>()?;
-        let local_as = stream.read_u16::<BigEndian>()?;
+/// Reads an IPv4 or IPv6 address depending on the given address family.
+fn read_address(mut stream: impl Read, afi: u16) -> Result<IpAddr, Error> {
+    AFI::from(afi)?.read_address(&mut stream)
+}
+
+/// The fields with which every BGP4MP subtype except SNAPSHOT starts. The ASNs of subtypes with
+/// 2-byte ASNs always fit in a `u16`.
+struct Peer {
+    peer_as: u32,
+    local_as: u32,
+    interface: u16,
+    peer_address: IpAddr,
+    local_address: IpAddr,
+    /// The number of bytes that were read.
+    length: u32,
+}
+
+impl Peer {
+    /// Reads the ASNs of the given size, the interface and the addresses. If `allow_missing_afi`
+    /// is set a zero AFI is accepted, in which case no addresses are read and both are unspecified.
+    fn parse(
+        mut stream: impl Read,
+        asn_size: AsnSize,
+        allow_missing_afi: bool,
+    ) -> Result<Peer, Error> {
+        let peer_as = asn_size.read(&mut stream)?;
+        let local_as = asn_size.read(&mut stream)?;
         let interface = stream.read_u16::<BigEndian>()?;
         let afi = stream.read_u16::<BigEndian>()?;
-        let peer_address = match AFI::from(afi)? {
-            AFI::IPV4 => IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-            AFI::IPV6 => IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
-        };
-        let local_address = match AFI::from(afi)? {
-            AFI::IPV4 => IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-            AFI::IPV6 => IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
+        let (peer_address, local_address, address_size) = if afi == 0 && allow_missing_afi {
+            let unspecified = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+            (unspecified, unspecified, 0)
+        } else {
+            (
+                read_address(&mut stream, afi)?,
+                read_address(&mut stream, afi)?,
+                2 * AFI::from(afi)?.size(),
+            )
         };
 
-        let length = header.length - (8 + 2 * AFI::from(afi)?.size());
-        let mut message = vec![0; length as usize];
-        stream.read_exact(&mut message)?;
-
-        Ok(MESSAGE {
+        Ok(Peer {
             peer_as,
             local_as,
             interface,
             peer_address,
             local_address,
+            length: 2 * asn_size.size() + 4 + address_size,
+        })
+    }
+}
+
+impl MESSAGE {
+    fn parse(
+        header: &Header,
+        mut stream: impl Read,
+        allow_missing_afi: bool,
+    ) -> Result<MESSAGE, Error> {
+        let asn_size = AsnSize::for_bgp4mp_subtype(header.sub_type);
+        let peer = Peer::parse(&mut stream, asn_size, allow_missing_afi)?;
+        let length = crate::message_length(header, peer.length)?;
+        let mut message = vec![0; length];
+        stream.read_exact(&mut message)?;
+
+        Ok(MESSAGE {
+            peer_as: peer.peer_as as u16,
+            local_as: peer.local_as as u16,
+            interface: peer.interface,
+            peer_address: peer.peer_address,
+            local_address: peer.local_address,
             message,
         })
     }
+
+    /// Returns the key identifying the BGP session over which this message was exchanged.
+    pub fn session_key(&self) -> SessionKey {
+        SessionKey {
+            peer_ip: self.peer_address,
+            peer_as: u32::from(self.peer_as),
+            local_ip: self.local_address,
+            local_as: u32::from(self.local_as),
+        }
+    }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(self.peer_as)?;
+        stream.write_u16::<BigEndian>(self.local_as)?;
+        stream.write_u16::<BigEndian>(self.interface)?;
+        stream.write_u16::<BigEndian>(afi_of(&self.peer_address, &self.local_address)?)?;
+        write_address(&mut stream, &self.peer_address)?;
+        write_address(&mut stream, &self.local_address)?;
+        stream.write_all(&self.message)
+    }
 }
 
 /// Represents a BGP message (UPDATE, OPEN, NOTIFICATION and KEEPALIVE) using 32bit ASN.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct MESSAGE_AS4 {
     /// The peer ASN from which the BGP message has been received.
@@ -173,7 +248,7 @@ pub struct MESSAGE_AS4 {
     /// The ASN of the AS that received this BGP message.
     pub local_as: u32,
 
-    /// The interface identifier to which this message applies.
+    /// The interface identifier to which this message applies, see [`SessionKey`].
     pub interface: u16,
 
     /// The peer IP address address from which the BGP message has been received.
@@ -187,33 +262,166 @@ pub struct MESSAGE_AS4 {
 }
 
 impl MESSAGE_AS4 {
-    fn parse(header: &Header, mut stream: impl Read) -> Result<MESSAGE_AS4, Error> {
-        let peer_as = stream.read_u32::<BigEndian>()?;
-        let local_as = stream.read_u32::<BigEndian>()?;
-        let interface = stream.read_u16::<BigEndian>()?;
-        let afi = stream.read_u16::<BigEndian>()?;
-        let peer_address = match AFI::from(afi)? {
-            AFI::IPV4 => IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-            AFI::IPV6 => IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
-        };
-        let local_address = match AFI::from(afi)? {
-            AFI::IPV4 => IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-            AFI::IPV6 => IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
-        };
-
-        let length = header.length - (12 + 2 * AFI::from(afi)?.size());
-        let mut message = vec![0; length as usize];
+    fn parse(
+        header: &Header,
+        mut stream: impl Read,
+        allow_missing_afi: bool,
+    ) -> Result<MESSAGE_AS4, Error> {
+        let asn_size = AsnSize::for_bgp4mp_subtype(header.sub_type);
+        let peer = Peer::parse(&mut stream, asn_size, allow_missing_afi)?;
+        let length = crate::message_length(header, peer.length)?;
+        let mut message = vec![0; length];
         stream.read_exact(&mut message)?;
 
         Ok(MESSAGE_AS4 {
-            peer_as,
-            local_as,
-            interface,
-            peer_address,
-            local_address,
+            peer_as: peer.peer_as,
+            local_as: peer.local_as,
+            interface: peer.interface,
+            peer_address: peer.peer_address,
+            local_address: peer.local_address,
             message,
         })
     }
+
+    ///
+    /// # Summary
+    /// Parses a MESSAGE_AS4 from the body of a BGP4MP record, excluding the MRT header and the
+    /// extended timestamp.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// An error is returned if the bytes do not contain a well-formed MESSAGE_AS4.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<MESSAGE_AS4, Error> {
+        let header = Header {
+            timestamp: 0,
+            extended: 0,
+            record_type: 16,
+            sub_type: 4,
+            length: bytes.len() as u32,
+        };
+        MESSAGE_AS4::parse(&header, bytes, false)
+    }
+
+    /// Returns the key identifying the BGP session over which this message was exchanged.
+    pub fn session_key(&self) -> SessionKey {
+        SessionKey {
+            peer_ip: self.peer_address,
+            peer_as: self.peer_as,
+            local_ip: self.local_address,
+            local_as: self.local_as,
+        }
+    }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u32::<BigEndian>(self.peer_as)?;
+        stream.write_u32::<BigEndian>(self.local_as)?;
+        stream.write_u16::<BigEndian>(self.interface)?;
+        stream.write_u16::<BigEndian>(afi_of(&self.peer_address, &self.local_address)?)?;
+        write_address(&mut stream, &self.peer_address)?;
+        write_address(&mut stream, &self.local_address)?;
+        stream.write_all(&self.message)
+    }
+}
+
+impl TryFrom<&[u8]> for MESSAGE_AS4 {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<MESSAGE_AS4, Error> {
+        MESSAGE_AS4::from_bytes(bytes)
+    }
+}
+
+///
+/// Identifies a BGP session by the addresses and ASNs of both of its endpoints. ASNs are stored
+/// as 32 bit numbers, such that messages with 16 and 32 bit ASNs of the same session are equal.
+///
+/// The `interface` field of the BGP4MP subtypes is the index of the interface of the collector
+/// over which the session is established, which distinguishes the sessions of collectors that
+/// are connected over multiple links. 0 means that the interface is unspecified, which is what
+/// most collectors, including BIRD, write. It is therefore not part of the key.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SessionKey {
+    /// The IP address of the peer.
+    pub peer_ip: IpAddr,
+
+    /// The ASN of the peer.
+    pub peer_as: u32,
+
+    /// The IP address of the local endpoint.
+    pub local_ip: IpAddr,
+
+    /// The ASN of the local endpoint.
+    pub local_as: u32,
+}
+
+///
+/// A BGP4MP message or state change of which the ASNs are widened to 32 bits, as returned by
+/// [`BGP4MP::normalized`]. This allows handling all subtypes carrying a message or state change
+/// alike.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedMessage<'a> {
+    /// The ASN of the peer.
+    pub peer_as: u32,
+
+    /// The ASN of the local endpoint.
+    pub local_as: u32,
+
+    /// The index of the interface of the collector, or 0 if unspecified.
+    pub interface: u16,
+
+    /// The IP address of the peer.
+    pub peer_address: IpAddr,
+
+    /// The IP address of the local endpoint.
+    pub local_address: IpAddr,
+
+    /// The size of the ASNs of the subtype, which is also the size of the ASNs in the path
+    /// attributes of the message.
+    pub asn_size: AsnSize,
+
+    /// Whether the subtype indicates that the prefixes of the message carry path identifiers.
+    pub add_path: bool,
+
+    /// Whether the message was generated by the collector instead of received from the peer.
+    pub local: bool,
+
+    /// The message or state change.
+    pub content: NormalizedContent<'a>,
+}
+
+impl NormalizedMessage<'_> {
+    /// Returns the key identifying the BGP session of this message or state change.
+    pub fn session_key(&self) -> SessionKey {
+        SessionKey {
+            peer_ip: self.peer_address,
+            peer_as: self.peer_as,
+            local_ip: self.local_address,
+            local_as: self.local_as,
+        }
+    }
+}
+
+/// The content of a [`NormalizedMessage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NormalizedContent<'a> {
+    /// A BGP message, starting with its 19-byte header.
+    Message(&'a [u8]),
+
+    /// A transition of the BGP Finite State Machine of the session.
+    StateChange {
+        /// The state before the transition.
+        old_state: u16,
+        /// The state after the transition.
+        new_state: u16,
+    },
 }
 
 ///
@@ -227,7 +435,7 @@ impl MESSAGE_AS4 {
 /// 6 Established
 /// More information can found in [RFC4271](https://tools.ietf.org/html/rfc4271#section-8).
 ///
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct STATE_CHANGE_AS4 {
     /// The peer ASN from which the BGP message has been received.
@@ -236,13 +444,15 @@ pub struct STATE_CHANGE_AS4 {
     /// The ASN of the AS that received this BGP message.
     pub local_as: u32,
 
-    /// The interface identifier to which this message applies.
+    /// The interface identifier to which this message applies, see [`SessionKey`].
     pub interface: u16,
 
     /// The peer IP address address from which the BGP message has been received.
     pub peer_address: IpAddr,
 
     /// The IP address of the AS that received this BGP message.
+    /// BIRD sets the addresses that are not known yet to `0.0.0.0` while a session is being
+    /// established.
     pub local_address: IpAddr,
 
     /// The old state of the BGP collector.
@@ -253,66 +463,90 @@ pub struct STATE_CHANGE_AS4 {
 }
 
 impl STATE_CHANGE_AS4 {
-    fn parse(mut stream: impl Read) -> Result<STATE_CHANGE_AS4, Error> {
-        let peer_as = stream.read_u32::<BigEndian>()?;
-        let local_as = stream.read_u32::<BigEndian>()?;
-        let interface = stream.read_u16::<BigEndian>()?;
-        let afi = stream.read_u16::<BigEndian>()?;
-        let peer_address = match AFI::from(afi)? {
-            AFI::IPV4 => IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-            AFI::IPV6 => IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
-        };
-        let local_address = match AFI::from(afi)? {
-            AFI::IPV4 => IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-            AFI::IPV6 => IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
-        };
+    fn parse(mut stream: impl Read, asn_size: AsnSize) -> Result<STATE_CHANGE_AS4, Error> {
+        let peer = Peer::parse(&mut stream, asn_size, false)?;
         let old_state = stream.read_u16::<BigEndian>()?;
         let new_state = stream.read_u16::<BigEndian>()?;
 
         Ok(STATE_CHANGE_AS4 {
-            peer_as,
-            local_as,
-            interface,
-            peer_address,
-            local_address,
+            peer_as: peer.peer_as,
+            local_as: peer.local_as,
+            interface: peer.interface,
+            peer_address: peer.peer_address,
+            local_address: peer.local_address,
             old_state,
             new_state,
         })
     }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u32::<BigEndian>(self.peer_as)?;
+        stream.write_u32::<BigEndian>(self.local_as)?;
+        stream.write_u16::<BigEndian>(self.interface)?;
+        stream.write_u16::<BigEndian>(afi_of(&self.peer_address, &self.local_address)?)?;
+        write_address(&mut stream, &self.peer_address)?;
+        write_address(&mut stream, &self.local_address)?;
+        stream.write_u16::<BigEndian>(self.old_state)?;
+        stream.write_u16::<BigEndian>(self.new_state)
+    }
 }
 
 /// Deprecated: Used to record BGP4MP messages in a file.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct SNAPSHOT {
     /// The associated view number.
     pub view_number: u16,
 
-    /// The NULL-terminated filename of the file where ENTRY records are recorded.
+    /// The filename of the file where ENTRY records are recorded. Stored without the
+    /// terminating NUL, which is written again if `nul_terminated` is set.
     pub filename: Vec<u8>,
+
+    /// Whether the filename was terminated by a NUL within the record. Some encoders omit it,
+    /// in which case the filename ends with the record.
+    pub nul_terminated: bool,
 }
 
 impl SNAPSHOT {
-    fn parse(mut stream: impl Read) -> Result<SNAPSHOT, Error> {
+    fn parse(header: &Header, mut stream: impl Read) -> Result<SNAPSHOT, Error> {
         let view_number = stream.read_u16::<BigEndian>()?;
-        let mut filename = Vec::new();
-
-        let mut buffer = stream.read_u8()?;
-        while buffer != b'\0' {
-            filename.push(buffer);
-            buffer = stream.read_u8()?;
-        }
+        let (filename, nul_terminated) =
+            crate::read_nul_terminated(&mut stream, header.length.saturating_sub(2))?;
 
         Ok(SNAPSHOT {
             view_number,
             filename,
+            nul_terminated,
         })
     }
+
+    /// Returns the filename of the file where ENTRY records are recorded as raw bytes, excluding the NUL terminator.
+    pub fn filename_bytes(&self) -> &[u8] {
+        &self.filename
+    }
+
+    /// Returns the filename of the file where ENTRY records are recorded, replacing invalid UTF-8 sequences by U+FFFD.
+    pub fn filename_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.filename)
+    }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(self.view_number)?;
+        stream.write_all(&self.filename)?;
+        if self.nul_terminated {
+            stream.write_u8(b'\0')?;
+        }
+        Ok(())
+    }
 }
 
 /// Used to record RIB table entries but has not seen wide support.
 /// More information can found in [RFC6396](https://tools.ietf.org/html/rfc6396#appendix-B.2.6).
-#[derive(Debug)]
+///
+/// The record contains two address families: the first one determines the size of the peer and
+/// local addresses, the second one (stored in `afi`) belongs to the next hop and the prefix.
+/// Only 2-byte ASNs can be represented, as no AS4 variant of this record has been defined.
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct ENTRY {
     /// The peer ASN from which the BGP message has been received.
@@ -321,7 +555,8 @@ pub struct ENTRY {
     /// The ASN of the AS that received this BGP message.
     pub local_as: u16,
 
-    /// The interface identifier to which this message applies.
+    /// The index of the interface of the collector over which the route was received, or 0 if
+    /// unspecified.
     pub interface: u16,
 
     /// The peer IP address address from which the BGP message has been received.
@@ -342,7 +577,11 @@ pub struct ENTRY {
     /// Represents the address of the next hop of this route.
     pub next_hop: IpAddr,
 
-    /// The Address Family Identifier (AFI) of the NLRI.
+    /// The link-local address of the next hop, present if the next hop contained both a global
+    /// and a link-local IPv6 address as described in [RFC2545](https://tools.ietf.org/html/rfc2545#section-3).
+    pub next_hop_link_local: Option<Ipv6Addr>,
+
+    /// The Address Family Identifier (AFI) of the next hop and the NLRI.
     pub afi: u16,
 
     /// The Subsequent Address Family Identifier (SAFI) of the NLRI.
@@ -359,34 +598,51 @@ pub struct ENTRY {
 }
 
 impl ENTRY {
-    fn parse(mut stream: impl Read) -> Result<ENTRY, Error> {
-        let peer_as = stream.read_u16::<BigEndian>()?;
-        let local_as = stream.read_u16::<BigEndian>()?;
-        let interface = stream.read_u16::<BigEndian>()?;
+    ///
+    /// # Summary
+    /// Parses the path attributes of this route. An ENTRY record only supports 2-byte ASNs, so
+    /// the AS_PATH and AGGREGATOR attributes are decoded as such.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// An error is returned if any of the path attributes is ill-formatted.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn parsed_attributes(&self) -> Result<Vec<PathAttribute>, MrtError> {
+        Ok(attributes::parse(
+            &self.attributes,
+            AsnSize::Two,
+            &ParseOptions::default(),
+        )?)
+    }
 
-        let afi = stream.read_u16::<BigEndian>()?;
-        let peer_address = match AFI::from(afi)? {
-            AFI::IPV4 => IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-            AFI::IPV6 => IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
-        };
-        let local_address = match AFI::from(afi)? {
-            AFI::IPV4 => IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-            AFI::IPV6 => IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
-        };
+    fn parse(mut stream: impl Read, asn_size: AsnSize) -> Result<ENTRY, Error> {
+        let peer = Peer::parse(&mut stream, asn_size, false)?;
 
         let view_number = stream.read_u16::<BigEndian>()?;
         let status = stream.read_u16::<BigEndian>()?;
         let time_last_change = stream.read_u32::<BigEndian>()?;
 
-        // Read the AFI and SAFI belonging to the prefix.
+        // Read the AFI and SAFI belonging to the next hop and prefix. This AFI may differ from the
+        // AFI of the peer and local addresses read above.
         let afi = stream.read_u16::<BigEndian>()?;
         let safi = stream.read_u8()?;
 
         // Read the next hop.
         let next_hop_length = stream.read_u8()?;
+        let mut next_hop_link_local = None;
         let next_hop = match next_hop_length {
             4 => IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
             16 => IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
+            32 => {
+                let global = Ipv6Addr::from(stream.read_u128::<BigEndian>()?);
+                next_hop_link_local = Some(Ipv6Addr::from(stream.read_u128::<BigEndian>()?));
+                IpAddr::V6(global)
+            }
             x => {
                 return Err(Error::new(
                     ErrorKind::InvalidData,
@@ -396,10 +652,7 @@ impl ENTRY {
         };
 
         // Read the prefix.
-        let prefix_length: u8 = stream.read_u8()?;
-        let length: u8 = (prefix_length + 7) / 8;
-        let mut prefix: Vec<u8> = vec![0; length as usize];
-        stream.read_exact(&mut prefix)?;
+        let (prefix_length, prefix) = nlri::read_prefix_unchecked(&mut stream)?;
 
         // Read the attributes
         let attribute_length = stream.read_u16::<BigEndian>()?;
@@ -407,15 +660,16 @@ impl ENTRY {
         stream.read_exact(&mut attributes)?;
 
         Ok(ENTRY {
-            peer_as,
-            local_as,
-            interface,
-            peer_address,
-            local_address,
+            peer_as: peer.peer_as as u16,
+            local_as: peer.local_as as u16,
+            interface: peer.interface,
+            peer_address: peer.peer_address,
+            local_address: peer.local_address,
             view_number,
             status,
             time_last_change,
             next_hop,
+            next_hop_link_local,
             afi,
             safi,
             prefix_length,
@@ -423,9 +677,60 @@ impl ENTRY {
             attributes,
         })
     }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(self.peer_as)?;
+        stream.write_u16::<BigEndian>(self.local_as)?;
+        stream.write_u16::<BigEndian>(self.interface)?;
+        stream.write_u16::<BigEndian>(afi_of(&self.peer_address, &self.local_address)?)?;
+        write_address(&mut stream, &self.peer_address)?;
+        write_address(&mut stream, &self.local_address)?;
+        stream.write_u16::<BigEndian>(self.view_number)?;
+        stream.write_u16::<BigEndian>(self.status)?;
+        stream.write_u32::<BigEndian>(self.time_last_change)?;
+        stream.write_u16::<BigEndian>(self.afi)?;
+        stream.write_u8(self.safi)?;
+        match (self.next_hop, self.next_hop_link_local) {
+            (IpAddr::V4(_), None) => stream.write_u8(4)?,
+            (IpAddr::V6(_), None) => stream.write_u8(16)?,
+            (IpAddr::V6(_), Some(_)) => stream.write_u8(32)?,
+            (IpAddr::V4(_), Some(_)) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "A link-local next hop requires an IPv6 next hop.",
+                ));
+            }
+        }
+        write_address(&mut stream, &self.next_hop)?;
+        if let Some(link_local) = self.next_hop_link_local {
+            stream.write_u128::<BigEndian>(u128::from(link_local))?;
+        }
+        stream.write_u8(self.prefix_length)?;
+        stream.write_all(&self.prefix)?;
+        write_u16_prefixed(&mut stream, &self.attributes)
+    }
 }
 
 impl BGP4MP {
+    /// Returns the subtype of the MRT record from which this message is parsed, which is the same
+    /// for the BGP4MP and BGP4MP_ET record types.
+    pub fn sub_type(&self) -> u16 {
+        match self {
+            BGP4MP::STATE_CHANGE(_) => codes::bgp4mp::STATE_CHANGE,
+            BGP4MP::MESSAGE(_) => codes::bgp4mp::MESSAGE,
+            BGP4MP::ENTRY(_) => codes::bgp4mp::ENTRY,
+            BGP4MP::SNAPSHOT(_) => codes::bgp4mp::SNAPSHOT,
+            BGP4MP::MESSAGE_AS4(_) => codes::bgp4mp::MESSAGE_AS4,
+            BGP4MP::STATE_CHANGE_AS4(_) => codes::bgp4mp::STATE_CHANGE_AS4,
+            BGP4MP::MESSAGE_LOCAL(_) => codes::bgp4mp::MESSAGE_LOCAL,
+            BGP4MP::MESSAGE_AS4_LOCAL(_) => codes::bgp4mp::MESSAGE_AS4_LOCAL,
+            BGP4MP::MESSAGE_ADDPATH(_) => codes::bgp4mp::MESSAGE_ADDPATH,
+            BGP4MP::MESSAGE_AS4_ADDPATH(_) => codes::bgp4mp::MESSAGE_AS4_ADDPATH,
+            BGP4MP::MESSAGE_LOCAL_ADDPATH(_) => codes::bgp4mp::MESSAGE_LOCAL_ADDPATH,
+            BGP4MP::MESSAGE_AS4_LOCAL_ADDPATH(_) => codes::bgp4mp::MESSAGE_AS4_LOCAL_ADDPATH,
+        }
+    }
+
     ///
     /// # Summary
     /// Used to parse sub-types of the BGP4MP MRT record type.
@@ -440,37 +745,189 @@ impl BGP4MP {
     /// # Safety
     /// This function does not make use of unsafe code.
     ///
-    pub(crate) fn parse(header: &Header, stream: impl Read) -> Result<BGP4MP, Error> {
+    pub(crate) fn parse(
+        header: &Header,
+        stream: impl Read,
+        options: &ParseOptions,
+    ) -> Result<BGP4MP, Error> {
         debug_assert!(
-            header.record_type == 16 || header.record_type == 17,
+            header.record_type == codes::BGP4MP || header.record_type == codes::BGP4MP_ET,
             "Invalid record type in MRTHeader, expected BGP4MP record type."
         );
 
+        // Only the locally generated messages are known to be written without addresses.
+        let allow_missing_afi = options.allow_missing_afi
+            && matches!(
+                header.sub_type,
+                codes::bgp4mp::MESSAGE_LOCAL
+                    | codes::bgp4mp::MESSAGE_AS4_LOCAL
+                    | codes::bgp4mp::MESSAGE_LOCAL_ADDPATH
+                    | codes::bgp4mp::MESSAGE_AS4_LOCAL_ADDPATH
+            );
+
+        let asn_size = AsnSize::for_bgp4mp_subtype(header.sub_type);
         match header.sub_type {
-            0 => Ok(BGP4MP::STATE_CHANGE(STATE_CHANGE::parse(stream)?)),
-            1 => Ok(BGP4MP::MESSAGE(MESSAGE::parse(header, stream)?)),
-            2 => Ok(BGP4MP::ENTRY(ENTRY::parse(stream)?)),
-            3 => Ok(BGP4MP::SNAPSHOT(SNAPSHOT::parse(stream)?)),
-            4 => Ok(BGP4MP::MESSAGE_AS4(MESSAGE_AS4::parse(header, stream)?)),
-            5 => Ok(BGP4MP::STATE_CHANGE_AS4(STATE_CHANGE_AS4::parse(stream)?)),
-            6 => Ok(BGP4MP::MESSAGE_LOCAL(MESSAGE::parse(header, stream)?)),
-            7 => Ok(BGP4MP::MESSAGE_AS4_LOCAL(MESSAGE_AS4::parse(
-                header, stream,
+            codes::bgp4mp::STATE_CHANGE => {
+                Ok(BGP4MP::STATE_CHANGE(STATE_CHANGE::parse(stream, asn_size)?))
+            }
+            codes::bgp4mp::MESSAGE => Ok(BGP4MP::MESSAGE(MESSAGE::parse(
+                header,
+                stream,
+                allow_missing_afi,
             )?)),
-            8 => Ok(BGP4MP::MESSAGE_ADDPATH(MESSAGE::parse(header, stream)?)),
-            9 => Ok(BGP4MP::MESSAGE_AS4_ADDPATH(MESSAGE_AS4::parse(
-                header, stream,
+            codes::bgp4mp::ENTRY => Ok(BGP4MP::ENTRY(ENTRY::parse(stream, asn_size)?)),
+            codes::bgp4mp::SNAPSHOT => Ok(BGP4MP::SNAPSHOT(SNAPSHOT::parse(header, stream)?)),
+            codes::bgp4mp::MESSAGE_AS4 => Ok(BGP4MP::MESSAGE_AS4(MESSAGE_AS4::parse(
+                header,
+                stream,
+                allow_missing_afi,
+            )?)),
+            codes::bgp4mp::STATE_CHANGE_AS4 => Ok(BGP4MP::STATE_CHANGE_AS4(
+                STATE_CHANGE_AS4::parse(stream, asn_size)?,
+            )),
+            codes::bgp4mp::MESSAGE_LOCAL => Ok(BGP4MP::MESSAGE_LOCAL(MESSAGE::parse(
+                header,
+                stream,
+                allow_missing_afi,
             )?)),
-            10 => Ok(BGP4MP::MESSAGE_LOCAL_ADDPATH(MESSAGE::parse(
-                header, stream,
+            codes::bgp4mp::MESSAGE_AS4_LOCAL => Ok(BGP4MP::MESSAGE_AS4_LOCAL(MESSAGE_AS4::parse(
+                header,
+                stream,
+                allow_missing_afi,
             )?)),
-            11 => Ok(BGP4MP::MESSAGE_AS4_LOCAL_ADDPATH(MESSAGE_AS4::parse(
-                header, stream,
+            codes::bgp4mp::MESSAGE_ADDPATH => Ok(BGP4MP::MESSAGE_ADDPATH(MESSAGE::parse(
+                header,
+                stream,
+                allow_missing_afi,
             )?)),
+            codes::bgp4mp::MESSAGE_AS4_ADDPATH => Ok(BGP4MP::MESSAGE_AS4_ADDPATH(
+                MESSAGE_AS4::parse(header, stream, allow_missing_afi)?,
+            )),
+            codes::bgp4mp::MESSAGE_LOCAL_ADDPATH => Ok(BGP4MP::MESSAGE_LOCAL_ADDPATH(
+                MESSAGE::parse(header, stream, allow_missing_afi)?,
+            )),
+            codes::bgp4mp::MESSAGE_AS4_LOCAL_ADDPATH => Ok(BGP4MP::MESSAGE_AS4_LOCAL_ADDPATH(
+                MESSAGE_AS4::parse(header, stream, allow_missing_afi)?,
+            )),
             _ => Err(Error::new(
                 ErrorKind::InvalidData,
                 "Unknown MRT record subtype found in MRTHeader",
             )),
         }
     }
+
+    ///
+    /// # Summary
+    /// Returns the message or state change of this record with 4-byte ASNs, regardless of the
+    /// ASN size of its subtype, and with the ADD-PATH and locally generated variants of the
+    /// subtype turned into flags. Returns `None` for ENTRY and SNAPSHOT records.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::fs::File;
+    /// use mrt_rs::bgp4mp::NormalizedContent;
+    /// use mrt_rs::{Reader, Record};
+    ///
+    /// let mut reader = Reader::new(File::open("res/bird-mrtdump_bgp").unwrap());
+    /// while let Some((_, Record::BGP4MP(record))) = reader.read().unwrap() {
+    ///     let normalized = record.normalized().unwrap();
+    ///     if let NormalizedContent::Message(message) = normalized.content {
+    ///         println!("AS{}: {} bytes", normalized.peer_as, message.len());
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn normalized(&self) -> Option<NormalizedMessage<'_>> {
+        let (add_path, local) = match self {
+            BGP4MP::MESSAGE_ADDPATH(_) | BGP4MP::MESSAGE_AS4_ADDPATH(_) => (true, false),
+            BGP4MP::MESSAGE_LOCAL(_) | BGP4MP::MESSAGE_AS4_LOCAL(_) => (false, true),
+            BGP4MP::MESSAGE_LOCAL_ADDPATH(_) | BGP4MP::MESSAGE_AS4_LOCAL_ADDPATH(_) => (true, true),
+            _ => (false, false),
+        };
+
+        let asn_size = AsnSize::for_bgp4mp_subtype(self.sub_type());
+        let normalized = match self {
+            BGP4MP::STATE_CHANGE(x) => NormalizedMessage {
+                peer_as: u32::from(x.peer_as),
+                local_as: u32::from(x.local_as),
+                interface: x.interface,
+                peer_address: x.peer_address,
+                local_address: x.local_address,
+                asn_size,
+                add_path,
+                local,
+                content: NormalizedContent::StateChange {
+                    old_state: x.old_state,
+                    new_state: x.new_state,
+                },
+            },
+            BGP4MP::STATE_CHANGE_AS4(x) => NormalizedMessage {
+                peer_as: x.peer_as,
+                local_as: x.local_as,
+                interface: x.interface,
+                peer_address: x.peer_address,
+                local_address: x.local_address,
+                asn_size,
+                add_path,
+                local,
+                content: NormalizedContent::StateChange {
+                    old_state: x.old_state,
+                    new_state: x.new_state,
+                },
+            },
+            BGP4MP::MESSAGE(x)
+            | BGP4MP::MESSAGE_LOCAL(x)
+            | BGP4MP::MESSAGE_ADDPATH(x)
+            | BGP4MP::MESSAGE_LOCAL_ADDPATH(x) => NormalizedMessage {
+                peer_as: u32::from(x.peer_as),
+                local_as: u32::from(x.local_as),
+                interface: x.interface,
+                peer_address: x.peer_address,
+                local_address: x.local_address,
+                asn_size,
+                add_path,
+                local,
+                content: NormalizedContent::Message(&x.message),
+            },
+            BGP4MP::MESSAGE_AS4(x)
+            | BGP4MP::MESSAGE_AS4_LOCAL(x)
+            | BGP4MP::MESSAGE_AS4_ADDPATH(x)
+            | BGP4MP::MESSAGE_AS4_LOCAL_ADDPATH(x) => NormalizedMessage {
+                peer_as: x.peer_as,
+                local_as: x.local_as,
+                interface: x.interface,
+                peer_address: x.peer_address,
+                local_address: x.local_address,
+                asn_size,
+                add_path,
+                local,
+                content: NormalizedContent::Message(&x.message),
+            },
+            BGP4MP::ENTRY(_) | BGP4MP::SNAPSHOT(_) => return None,
+        };
+        Some(normalized)
+    }
+
+    pub(crate) fn write(&self, stream: impl Write) -> Result<(), Error> {
+        match self {
+            BGP4MP::STATE_CHANGE(x) => x.write(stream),
+            BGP4MP::MESSAGE(x)
+            | BGP4MP::MESSAGE_LOCAL(x)
+            | BGP4MP::MESSAGE_ADDPATH(x)
+            | BGP4MP::MESSAGE_LOCAL_ADDPATH(x) => x.write(stream),
+            BGP4MP::ENTRY(x) => x.write(stream),
+            BGP4MP::SNAPSHOT(x) => x.write(stream),
+            BGP4MP::MESSAGE_AS4(x)
+            | BGP4MP::MESSAGE_AS4_LOCAL(x)
+            | BGP4MP::MESSAGE_AS4_ADDPATH(x)
+            | BGP4MP::MESSAGE_AS4_LOCAL_ADDPATH(x) => x.write(stream),
+            BGP4MP::STATE_CHANGE_AS4(x) => x.write(stream),
+        }
+    }
 }
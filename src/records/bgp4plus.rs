@@ -1,11 +1,22 @@
-use byteorder::{BigEndian, ReadBytesExt};
-use std::io::{Error, ErrorKind, Read};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::borrow::Cow;
+use std::io::{Error, Read, Write};
 use std::net::Ipv6Addr;
 
+use crate::codes;
 use crate::Header;
 
-/// The BGPPLUS enum represents all possible subtypes of the BGPPLUS record type.
-#[derive(Debug)]
+///
+/// The BGPPLUS enum represents all possible subtypes of the BGPPLUS and BGP4PLUS_01 record types.
+///
+/// Both record types were defined to carry the BGP messages of IPv6 peers. BGP4PLUS followed the
+/// initial Internet-Draft of the Multiprotocol Extensions to BGP-4, while BGP4PLUS_01 followed
+/// its -01 revision, which changed the encoding of the multiprotocol attributes inside the BGP
+/// UPDATE messages. The MRT encoding of both record types is identical, as noted in
+/// [RFC6396](https://tools.ietf.org/html/rfc6396#appendix-B.2.5), such that both are parsed by
+/// this enum and only the record type tells them apart. The BGP messages are kept as raw bytes.
+///
+#[derive(Debug, PartialEq)]
 #[allow(missing_docs)]
 #[allow(non_camel_case_types)]
 pub enum BGP4PLUS {
@@ -21,26 +32,52 @@ pub enum BGP4PLUS {
 
 /// Used for the deprecated BGP message type.
 impl BGP4PLUS {
+    /// Returns the subtype of the MRT record from which this message is parsed, which is the same
+    /// for the BGP4PLUS and BGP4PLUS_01 record types.
+    pub fn sub_type(&self) -> u16 {
+        match self {
+            BGP4PLUS::NULL => codes::bgp::NULL,
+            BGP4PLUS::UPDATE(_) => codes::bgp::UPDATE,
+            BGP4PLUS::PREF_UPDATE => codes::bgp::PREF_UPDATE,
+            BGP4PLUS::STATE_CHANGE(_) => codes::bgp::STATE_CHANGE,
+            BGP4PLUS::SYNC(_) => codes::bgp::SYNC,
+            BGP4PLUS::OPEN(_) => codes::bgp::OPEN,
+            BGP4PLUS::NOTIFY(_) => codes::bgp::NOTIFY,
+            BGP4PLUS::KEEPALIVE(_) => codes::bgp::KEEPALIVE,
+        }
+    }
+
     pub(crate) fn parse(header: &Header, stream: impl Read) -> Result<BGP4PLUS, Error> {
         match header.sub_type {
-            0 => Ok(BGP4PLUS::NULL),
-            1 => Ok(BGP4PLUS::UPDATE(MESSAGE::parse(header, stream)?)),
-            2 => Ok(BGP4PLUS::PREF_UPDATE),
-            3 => Ok(BGP4PLUS::STATE_CHANGE(STATE_CHANGE::parse(stream)?)),
-            4 => Ok(BGP4PLUS::SYNC(SYNC::parse(stream)?)),
-            5 => Ok(BGP4PLUS::OPEN(MESSAGE::parse(header, stream)?)),
-            6 => Ok(BGP4PLUS::NOTIFY(MESSAGE::parse(header, stream)?)),
-            7 => Ok(BGP4PLUS::KEEPALIVE(MESSAGE::parse(header, stream)?)),
-            _ => Err(Error::new(
-                ErrorKind::Other,
+            codes::bgp::NULL => Ok(BGP4PLUS::NULL),
+            codes::bgp::UPDATE => Ok(BGP4PLUS::UPDATE(MESSAGE::parse(header, stream)?)),
+            codes::bgp::PREF_UPDATE => Ok(BGP4PLUS::PREF_UPDATE),
+            codes::bgp::STATE_CHANGE => Ok(BGP4PLUS::STATE_CHANGE(STATE_CHANGE::parse(stream)?)),
+            codes::bgp::SYNC => Ok(BGP4PLUS::SYNC(SYNC::parse(header, stream)?)),
+            codes::bgp::OPEN => Ok(BGP4PLUS::OPEN(MESSAGE::parse(header, stream)?)),
+            codes::bgp::NOTIFY => Ok(BGP4PLUS::NOTIFY(MESSAGE::parse(header, stream)?)),
+            codes::bgp::KEEPALIVE => Ok(BGP4PLUS::KEEPALIVE(MESSAGE::parse(header, stream)?)),
+            _ => Err(Error::other(
                 "Unknown MRT record subtype found in MRTHeader",
             )),
         }
     }
+
+    pub(crate) fn write(&self, stream: impl Write) -> Result<(), Error> {
+        match self {
+            BGP4PLUS::NULL | BGP4PLUS::PREF_UPDATE => Ok(()),
+            BGP4PLUS::UPDATE(x)
+            | BGP4PLUS::OPEN(x)
+            | BGP4PLUS::NOTIFY(x)
+            | BGP4PLUS::KEEPALIVE(x) => x.write(stream),
+            BGP4PLUS::STATE_CHANGE(x) => x.write(stream),
+            BGP4PLUS::SYNC(x) => x.write(stream),
+        }
+    }
 }
 
 /// Represents the BGP_UPDATE, BGP_OPEN, BGP_NOTIFY and BGP_KEEPALIVE subtypes of IPv6 peers.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct MESSAGE {
     /// The peer ASN from which the BGP message has been received.
@@ -66,8 +103,9 @@ impl MESSAGE {
         let local_as = stream.read_u16::<BigEndian>()?;
         let local_ip = Ipv6Addr::from(stream.read_u128::<BigEndian>()?);
 
-        let length = header.length - 12;
-        let mut message = vec![0; length as usize];
+        // Both the ASNs and the IPv6 addresses of the peer and the local endpoint.
+        let length = crate::message_length(header, 36)?;
+        let mut message = vec![0; length];
         stream.read_exact(&mut message)?;
 
         Ok(MESSAGE {
@@ -78,13 +116,21 @@ impl MESSAGE {
             message,
         })
     }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(self.peer_as)?;
+        stream.write_u128::<BigEndian>(u128::from(self.peer_ip))?;
+        stream.write_u16::<BigEndian>(self.local_as)?;
+        stream.write_u128::<BigEndian>(u128::from(self.local_ip))?;
+        stream.write_all(&self.message)
+    }
 }
 
 ///
 /// Represents a state change in the BGP Finite State Machine (FSM).
 /// More information can found in [RFC4271](https://tools.ietf.org/html/rfc4271#section-8).
 ///
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct STATE_CHANGE {
     /// The peer ASN from which the BGP message has been received.
@@ -109,33 +155,60 @@ impl STATE_CHANGE {
             new_state: stream.read_u16::<BigEndian>()?,
         })
     }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(self.peer_as)?;
+        stream.write_u128::<BigEndian>(u128::from(self.peer_ip))?;
+        stream.write_u16::<BigEndian>(self.old_state)?;
+        stream.write_u16::<BigEndian>(self.new_state)
+    }
 }
 
 /// Deprecated: Used to record RIB entries in a file.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct SYNC {
     /// The view number of this Routing Information Base.
     pub view_number: u16,
 
-    /// The filename of the BGP RIB entries. NULL-terminated.
+    /// The filename of the BGP RIB entries. Stored without the terminating NUL, which is written
+    /// again if `nul_terminated` is set.
     pub filename: Vec<u8>,
+
+    /// Whether the filename was terminated by a NUL within the record. Some encoders omit it,
+    /// in which case the filename ends with the record.
+    pub nul_terminated: bool,
 }
 
 impl SYNC {
-    fn parse(mut stream: impl Read) -> Result<SYNC, Error> {
+    fn parse(header: &Header, mut stream: impl Read) -> Result<SYNC, Error> {
         let view_number = stream.read_u16::<BigEndian>()?;
-        let mut filename = Vec::new();
-
-        let mut buffer = stream.read_u8()?;
-        while buffer != b'\0' {
-            filename.push(buffer);
-            buffer = stream.read_u8()?;
-        }
+        let (filename, nul_terminated) =
+            crate::read_nul_terminated(&mut stream, header.length.saturating_sub(2))?;
 
         Ok(SYNC {
             view_number,
             filename,
+            nul_terminated,
         })
     }
+
+    /// Returns the filename of the BGP RIB entries as raw bytes, excluding the NUL terminator.
+    pub fn filename_bytes(&self) -> &[u8] {
+        &self.filename
+    }
+
+    /// Returns the filename of the BGP RIB entries, replacing invalid UTF-8 sequences by U+FFFD.
+    pub fn filename_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.filename)
+    }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(self.view_number)?;
+        stream.write_all(&self.filename)?;
+        if self.nul_terminated {
+            stream.write_u8(b'\0')?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,63 @@
+use byteorder::{BigEndian, ByteOrder};
+use std::io::{Error, ErrorKind};
+
+use crate::MrtError;
+
+/// The length of the BMP common header.
+const COMMON_HEADER_LENGTH: usize = 6;
+
+/// The length of the BMP per-peer header.
+const PER_PEER_HEADER_LENGTH: usize = 42;
+
+/// The BMP message type of Route Monitoring messages.
+const ROUTE_MONITORING: u8 = 0;
+
+///
+/// # Summary
+/// Extracts the BGP PDU wrapped by a BMP Route Monitoring message by stripping its common
+/// header and per-peer header. The resulting UPDATE can be decoded using `bgp::parse_update`.
+/// Note that the peer uses 2-byte ASNs in its AS_PATH if the A flag (0x20) of the per-peer
+/// header is set. Other BMP message types are not supported.
+/// More information can found in [RFC7854](https://tools.ietf.org/html/rfc7854#section-4).
+///
+/// # Panics
+/// This function does not panic.
+///
+/// # Errors
+/// An error of kind `UnexpectedEof` is returned if the message is shorter than its declared
+/// length. An error of kind `InvalidData` is returned if the message is not a BMP version 3
+/// Route Monitoring message or its length is ill-formatted.
+///
+/// # Safety
+/// This function does not make use of unsafe code.
+///
+pub fn extract_bgp(bytes: &[u8]) -> Result<Vec<u8>, MrtError> {
+    if bytes.len() < COMMON_HEADER_LENGTH {
+        return Err(Error::from(ErrorKind::UnexpectedEof).into());
+    }
+
+    let version = bytes[0];
+    let length = BigEndian::read_u32(&bytes[1..5]) as usize;
+    let message_type = bytes[5];
+
+    if version != 3 {
+        let msg = format!("Unsupported BMP version: {}", version);
+        return Err(Error::new(ErrorKind::InvalidData, msg).into());
+    }
+    if message_type != ROUTE_MONITORING {
+        let msg = format!("BMP message type {} is not Route Monitoring.", message_type);
+        return Err(Error::new(ErrorKind::InvalidData, msg).into());
+    }
+    if length < COMMON_HEADER_LENGTH + PER_PEER_HEADER_LENGTH {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "BMP message is too short to contain a per-peer header.",
+        )
+        .into());
+    }
+    if bytes.len() < length {
+        return Err(Error::from(ErrorKind::UnexpectedEof).into());
+    }
+
+    Ok(bytes[COMMON_HEADER_LENGTH + PER_PEER_HEADER_LENGTH..length].to_vec())
+}
@@ -0,0 +1,100 @@
+use byteorder::{BigEndian, ReadBytesExt};
+use std::io::{Error, ErrorKind, Read};
+
+use crate::{MrtError, ParseOptions, AFI};
+
+/// A prefix consisting of its ADD-PATH path identifier, its length in bits and its bytes.
+pub type Prefix = (Option<u32>, u8, Vec<u8>);
+
+///
+/// # Summary
+/// Parses a sequence of prefixes as encoded in the NLRI and withdrawn routes fields of a BGP
+/// UPDATE message and in the MP_REACH_NLRI and MP_UNREACH_NLRI attributes. Every prefix consists
+/// of its length in bits followed by the minimal number of bytes holding the prefix, optionally
+/// preceded by a 4-byte path identifier if ADD-PATH is used.
+///
+/// Returns the path identifier, the prefix length and the prefix bytes of every prefix.
+///
+/// # Panics
+/// This function does not panic.
+///
+/// # Errors
+/// An `MrtError::Io` of kind `InvalidData` is returned if `options.strict` is set and a prefix
+/// length exceeds the number of bits of the address family, and of kind `UnexpectedEof` if a
+/// prefix is truncated.
+///
+/// # Safety
+/// This function does not make use of unsafe code.
+///
+pub fn parse_prefixes(
+    bytes: &[u8],
+    afi: AFI,
+    add_path: bool,
+    options: &ParseOptions,
+) -> Result<Vec<Prefix>, MrtError> {
+    Ok(read_prefixes(bytes, afi, add_path, options)?)
+}
+
+/// Reads prefixes like `parse_prefixes`, returning the IO error for use by the record parsers.
+pub(crate) fn read_prefixes(
+    mut bytes: &[u8],
+    afi: AFI,
+    add_path: bool,
+    options: &ParseOptions,
+) -> Result<Vec<Prefix>, Error> {
+    let mut prefixes = Vec::new();
+    while !bytes.is_empty() {
+        let path_id = match add_path {
+            true => Some(bytes.read_u32::<BigEndian>()?),
+            false => None,
+        };
+        let (prefix_length, prefix) = read_prefix(&mut bytes, &afi, options)?;
+        prefixes.push((path_id, prefix_length, prefix));
+    }
+    Ok(prefixes)
+}
+
+/// Reads the length of a prefix in bits followed by the bytes of the prefix.
+pub(crate) fn read_prefix(
+    mut stream: impl Read,
+    afi: &AFI,
+    options: &ParseOptions,
+) -> Result<(u8, Vec<u8>), Error> {
+    let prefix_length = stream.read_u8()?;
+    validate_prefix_length(prefix_length, afi, options)?;
+    Ok((prefix_length, read_prefix_bytes(stream, prefix_length)?))
+}
+
+/// Reads a prefix like `read_prefix`, without validating the prefix length.
+pub(crate) fn read_prefix_unchecked(mut stream: impl Read) -> Result<(u8, Vec<u8>), Error> {
+    let prefix_length = stream.read_u8()?;
+    Ok((prefix_length, read_prefix_bytes(stream, prefix_length)?))
+}
+
+/// Reads the minimal number of bytes holding a prefix of the given length.
+fn read_prefix_bytes(mut stream: impl Read, prefix_length: u8) -> Result<Vec<u8>, Error> {
+    let mut prefix = vec![0; usize::from(prefix_length.div_ceil(8))];
+    stream.read_exact(&mut prefix)?;
+    Ok(prefix)
+}
+
+/// Validates that a prefix length does not exceed the number of bits of the address family.
+fn validate_prefix_length(
+    prefix_length: u8,
+    afi: &AFI,
+    options: &ParseOptions,
+) -> Result<(), Error> {
+    if options.strict && u32::from(prefix_length) > 8 * afi.size() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Prefix length {} exceeds the maximum of {} for {:?}",
+                prefix_length,
+                8 * afi.size(),
+                afi
+            ),
+        ));
+    }
+
+    Ok(())
+}
@@ -1,12 +1,13 @@
-use byteorder::{BigEndian, ReadBytesExt};
-use std::io::{Error, Read};
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{Error, Read, Write};
+use std::net::{IpAddr, Ipv4Addr};
 
+use crate::writer::{afi_of, write_address};
 use crate::Header;
 use crate::AFI;
 
 /// The OSPFv2 struct represents the data contained in an MRT record type of OSPFv2.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct OSPFv2 {
     /// The IPv4 address from which this message was received.
     pub remote: Ipv4Addr,
@@ -35,21 +36,27 @@ impl OSPFv2 {
     ///
     pub fn parse(header: &Header, mut stream: impl Read) -> Result<OSPFv2, Error> {
         // The fixed size of the header consisting of two IPv4 addresses.
-        let length = (header.length - 2 * AFI::IPV4.size()) as usize;
+        let length = crate::message_length(header, 2 * AFI::IPV4.size())?;
         let mut record = OSPFv2 {
             remote: Ipv4Addr::from(stream.read_u32::<BigEndian>()?),
             local: Ipv4Addr::from(stream.read_u32::<BigEndian>()?),
-            message: vec![0; length as usize],
+            message: vec![0; length],
         };
 
         // Fill the entire buffer.
         stream.read_exact(&mut record.message)?;
         Ok(record)
     }
+
+    pub(crate) fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u32::<BigEndian>(u32::from(self.remote))?;
+        stream.write_u32::<BigEndian>(u32::from(self.local))?;
+        stream.write_all(&self.message)
+    }
 }
 
 /// The OSPFv3 struct represents the data contained in an MRT record type of OSPFv3 and OSPFv3_ET.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct OSPFv3 {
     /// The IP address of the router from which this message was received.
     pub remote: IpAddr,
@@ -77,27 +84,23 @@ impl OSPFv3 {
     /// This function does not make use of unsafe code.
     ///
     pub fn parse(header: &Header, mut stream: impl Read) -> Result<OSPFv3, Error> {
-        let mut record = match AFI::from(stream.read_u16::<BigEndian>()?)? {
-            AFI::IPV4 => {
-                let length = (header.length - 2 * AFI::IPV4.size()) as usize;
-                OSPFv3 {
-                    remote: IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-                    local: IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-                    message: vec![0; length as usize],
-                }
-            }
-            AFI::IPV6 => {
-                let length: usize = (header.length - 2 * AFI::IPV6.size()) as usize;
-                OSPFv3 {
-                    remote: IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
-                    local: IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
-                    message: vec![0; length as usize],
-                }
-            }
+        let afi = AFI::from(stream.read_u16::<BigEndian>()?)?;
+        let length = crate::message_length(header, 2 + 2 * afi.size())?;
+        let mut record = OSPFv3 {
+            remote: afi.read_address(&mut stream)?,
+            local: afi.read_address(&mut stream)?,
+            message: vec![0; length],
         };
 
         // Fill the entire buffer.
         stream.read_exact(&mut record.message)?;
         Ok(record)
     }
+
+    pub(crate) fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(afi_of(&self.remote, &self.local)?)?;
+        write_address(&mut stream, &self.remote)?;
+        write_address(&mut stream, &self.local)?;
+        stream.write_all(&self.message)
+    }
 }
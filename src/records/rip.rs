@@ -1,12 +1,12 @@
-use byteorder::{BigEndian, ReadBytesExt};
-use std::io::{Error, Read};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{Error, Read, Write};
 use std::net::{Ipv4Addr, Ipv6Addr};
 
 use crate::Header;
 use crate::AFI;
 
 /// The RIP struct represents the data contained in an MRT record type of RIP.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct RIP {
     /// The IPv4 address of the router from which this message was received.
     pub remote: Ipv4Addr,
@@ -35,21 +35,27 @@ impl RIP {
     ///
     pub fn parse(header: &Header, mut stream: impl Read) -> Result<RIP, Error> {
         // The fixed size of the header consisting of two IPv4 addresses.
-        let length = (header.length - 2 * AFI::IPV4.size()) as usize;
+        let length = crate::message_length(header, 2 * AFI::IPV4.size())?;
         let mut record = RIP {
             remote: Ipv4Addr::from(stream.read_u32::<BigEndian>()?),
             local: Ipv4Addr::from(stream.read_u32::<BigEndian>()?),
-            message: vec![0; length as usize],
+            message: vec![0; length],
         };
 
         // Fill the entire buffer.
         stream.read_exact(&mut record.message)?;
         Ok(record)
     }
+
+    pub(crate) fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u32::<BigEndian>(u32::from(self.remote))?;
+        stream.write_u32::<BigEndian>(u32::from(self.local))?;
+        stream.write_all(&self.message)
+    }
 }
 
 /// The RIP struct represents the data contained in an MRT record type of RIP.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct RIPNG {
     /// The IPv6 address of the router from which this message was received.
     pub remote: Ipv6Addr,
@@ -77,16 +83,22 @@ impl RIPNG {
     /// This function does not make use of unsafe code.
     ///
     pub fn parse(header: &Header, mut stream: impl Read) -> Result<RIPNG, Error> {
-        // The fixed size of the header consisting of two IPv4 addresses.
-        let length = (header.length - 2 * AFI::IPV6.size()) as usize;
+        // The fixed size of the header consisting of two IPv6 addresses.
+        let length = crate::message_length(header, 2 * AFI::IPV6.size())?;
         let mut record = RIPNG {
             remote: Ipv6Addr::from(stream.read_u128::<BigEndian>()?),
             local: Ipv6Addr::from(stream.read_u128::<BigEndian>()?),
-            message: vec![0; length as usize],
+            message: vec![0; length],
         };
 
         // Fill the entire buffer.
         stream.read_exact(&mut record.message)?;
         Ok(record)
     }
+
+    pub(crate) fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u128::<BigEndian>(u128::from(self.remote))?;
+        stream.write_u128::<BigEndian>(u128::from(self.local))?;
+        stream.write_all(&self.message)
+    }
 }
@@ -1,12 +1,19 @@
-use byteorder::{BigEndian, ReadBytesExt};
-use std::io::{Error, Read};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::convert::TryFrom;
+use std::io::{Error, ErrorKind, Read, Take, Write};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
+use crate::attributes::{self, MpReachNlri};
+use crate::codes;
+use crate::records::nlri;
+use crate::writer::{write_address, write_count, write_u16_prefixed};
+use crate::AsnSize;
 use crate::Header;
-use crate::AFI;
+use crate::ParseOptions;
+use crate::{AFI, SAFI};
 
 /// Represents a RIB entry of a Routing Information Base.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct TABLE_DUMP {
     /// Identifies the RIB view. Normally set to 0.
@@ -57,19 +64,14 @@ impl TABLE_DUMP {
         let view_number = stream.read_u16::<BigEndian>()?;
         let sequence_number = stream.read_u16::<BigEndian>()?;
 
-        let prefix = match AFI::from(header.sub_type)? {
-            AFI::IPV4 => IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-            AFI::IPV6 => IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
-        };
+        let afi = AFI::from(header.sub_type)?;
+        let prefix = afi.read_address(&mut stream)?;
 
         let prefix_length = stream.read_u8()?;
         let status = stream.read_u8()?;
         let originated_time = stream.read_u32::<BigEndian>()?;
 
-        let peer_address = match AFI::from(header.sub_type)? {
-            AFI::IPV4 => IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?)),
-            AFI::IPV6 => IpAddr::V6(Ipv6Addr::from(stream.read_u128::<BigEndian>()?)),
-        };
+        let peer_address = afi.read_address(&mut stream)?;
 
         let peer_as = stream.read_u16::<BigEndian>()?;
         let attribute_length = stream.read_u16::<BigEndian>()?;
@@ -88,10 +90,54 @@ impl TABLE_DUMP {
             attributes,
         })
     }
+
+    /// Returns the prefix if this is an AFI_IPv4 record, which is known from its subtype.
+    pub fn prefix_ipv4(&self) -> Option<Ipv4Addr> {
+        match self.prefix {
+            IpAddr::V4(x) => Some(x),
+            IpAddr::V6(_) => None,
+        }
+    }
+
+    /// Returns the prefix if this is an AFI_IPv6 record, which is known from its subtype.
+    pub fn prefix_ipv6(&self) -> Option<Ipv6Addr> {
+        match self.prefix {
+            IpAddr::V4(_) => None,
+            IpAddr::V6(x) => Some(x),
+        }
+    }
+
+    /// Returns the address of the peer if this is an AFI_IPv4 record.
+    pub fn peer_address_ipv4(&self) -> Option<Ipv4Addr> {
+        match self.peer_address {
+            IpAddr::V4(x) => Some(x),
+            IpAddr::V6(_) => None,
+        }
+    }
+
+    /// Returns the address of the peer if this is an AFI_IPv6 record.
+    pub fn peer_address_ipv6(&self) -> Option<Ipv6Addr> {
+        match self.peer_address {
+            IpAddr::V4(_) => None,
+            IpAddr::V6(x) => Some(x),
+        }
+    }
+
+    pub(crate) fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(self.view_number)?;
+        stream.write_u16::<BigEndian>(self.sequence_number)?;
+        write_address(&mut stream, &self.prefix)?;
+        stream.write_u8(self.prefix_length)?;
+        stream.write_u8(self.status)?;
+        stream.write_u32::<BigEndian>(self.originated_time)?;
+        write_address(&mut stream, &self.peer_address)?;
+        stream.write_u16::<BigEndian>(self.peer_as)?;
+        write_u16_prefixed(&mut stream, &self.attributes)
+    }
 }
 
 /// Used to store Routing Information Base (RIB) entries.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(missing_docs)]
 #[allow(non_camel_case_types)]
 pub enum TABLE_DUMP_V2 {
@@ -110,27 +156,65 @@ pub enum TABLE_DUMP_V2 {
 
 /// This record provides the BGP ID of the collector, an optional view name,
 /// and a list of indexed peers.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct PEER_INDEX_TABLE {
     /// The identifier of the collector often set to its IPv4 address.
     pub collector_id: u32,
 
-    /// Optional associated view name. Set to the empty string if empty.
+    /// Optional associated view name. Set to the empty string if empty. Invalid UTF-8 is replaced
+    /// by U+FFFD unless `ParseOptions::strict_utf8` is set, in which case an error is returned.
     pub view_name: String,
 
-    /// An array of peers from which messages were received.
+    /// The view name as it was encoded in the record. When written, these bytes are used instead
+    /// of `view_name` as long as they still decode to it, such that a view name with invalid
+    /// UTF-8 is written back unchanged.
+    pub view_name_bytes: Vec<u8>,
+
+    /// An array of peers from which messages were received. An empty peer table is legal, in
+    /// which case the RIB entries that follow cannot be attributed to a peer.
     pub peer_entries: Vec<PeerEntry>,
+
+    /// Any bytes following the peer entries within the record, such as fields added by future
+    /// extensions of the format. Only kept if `ParseOptions::keep_extra` is set, otherwise empty.
+    pub extra: Vec<u8>,
 }
 
 impl PEER_INDEX_TABLE {
-    fn parse(mut stream: impl Read) -> Result<PEER_INDEX_TABLE, Error> {
+    fn parse(
+        header: &Header,
+        stream: impl Read,
+        options: &ParseOptions,
+    ) -> Result<PEER_INDEX_TABLE, Error> {
+        let mut stream = stream.take(u64::from(header.length));
+        let mut table = PEER_INDEX_TABLE::parse_fields(&mut stream, options)?;
+        if options.keep_extra {
+            stream.read_to_end(&mut table.extra)?;
+        } else {
+            std::io::copy(&mut stream, &mut std::io::sink())?;
+        }
+        Ok(table)
+    }
+
+    fn parse_fields(
+        mut stream: impl Read,
+        options: &ParseOptions,
+    ) -> Result<PEER_INDEX_TABLE, Error> {
         let collector_id = stream.read_u32::<BigEndian>()?;
         let view_name_length = stream.read_u16::<BigEndian>()?;
 
         let mut buffer: Vec<u8> = vec![0; view_name_length as usize];
         stream.read_exact(&mut buffer)?;
-        let view_name = String::from_utf8_lossy(&buffer).to_string();
+        let view_name = match std::str::from_utf8(&buffer) {
+            Ok(x) => x.to_string(),
+            Err(_) if options.strict_utf8 => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "View name of PEER_INDEX_TABLE is not valid UTF-8.",
+                ));
+            }
+            Err(_) => String::from_utf8_lossy(&buffer).to_string(),
+        };
 
         let peer_count = stream.read_u16::<BigEndian>()?;
         let mut peer_entries: Vec<PeerEntry> = Vec::with_capacity(peer_count as usize);
@@ -141,13 +225,78 @@ impl PEER_INDEX_TABLE {
         Ok(PEER_INDEX_TABLE {
             collector_id,
             view_name,
+            view_name_bytes: buffer,
             peer_entries,
+            extra: Vec::new(),
         })
     }
+
+    ///
+    /// # Summary
+    /// Parses a PEER_INDEX_TABLE from the body of a TABLE_DUMP_V2 record, excluding the MRT header.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// An error is returned if the bytes do not contain exactly one well-formed PEER_INDEX_TABLE.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<PEER_INDEX_TABLE, Error> {
+        let table = PEER_INDEX_TABLE::parse_fields(&mut bytes, &ParseOptions::default())?;
+        crate::ensure_consumed(bytes, "PEER_INDEX_TABLE")?;
+        Ok(table)
+    }
+
+    ///
+    /// Returns an iterator over all peers together with their index in this table. The index
+    /// is 0-based and is the value referenced by the `peer_index` of a `RIBEntry`.
+    ///
+    pub fn iter_indexed(&self) -> impl Iterator<Item = (u16, &PeerEntry)> {
+        (0..=u16::MAX).zip(self.peer_entries.iter())
+    }
+
+    ///
+    /// Returns the peer at the given 0-based index, as referenced by a `RIBEntry`, or `None` if
+    /// the index lies outside of the table.
+    ///
+    pub fn get(&self, index: u16) -> Option<&PeerEntry> {
+        self.peer_entries.get(index as usize)
+    }
+
+    /// Returns the identifier of the collector as the IPv4 address it conventionally represents.
+    pub fn collector_ip(&self) -> Ipv4Addr {
+        Ipv4Addr::from(self.collector_id)
+    }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u32::<BigEndian>(self.collector_id)?;
+        let view_name = if String::from_utf8_lossy(&self.view_name_bytes) == self.view_name {
+            &self.view_name_bytes
+        } else {
+            self.view_name.as_bytes()
+        };
+        write_u16_prefixed(&mut stream, view_name)?;
+        write_count(&mut stream, self.peer_entries.len())?;
+        for entry in &self.peer_entries {
+            entry.write(&mut stream)?;
+        }
+        stream.write_all(&self.extra)
+    }
+}
+
+impl TryFrom<&[u8]> for PEER_INDEX_TABLE {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<PEER_INDEX_TABLE, Error> {
+        PEER_INDEX_TABLE::from_bytes(bytes)
+    }
 }
 
 /// Describes a peer from which BGP messages were received.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PeerEntry {
     /// Special flags in bit 0 and bit 1. Specifying the ASN and IP type.
     pub peer_type: u8,
@@ -166,7 +315,11 @@ impl PeerEntry {
     fn parse(mut stream: impl Read) -> Result<PeerEntry, Error> {
         let peer_type = stream.read_u8()?;
         let ipv6 = (peer_type & 1) != 0;
-        let as_size = (peer_type & 2) != 0;
+        let asn_size = if (peer_type & 2) != 0 {
+            AsnSize::Four
+        } else {
+            AsnSize::Two
+        };
 
         let peer_bgp_id = stream.read_u32::<BigEndian>()?;
         let peer_ip_address = if ipv6 {
@@ -175,11 +328,7 @@ impl PeerEntry {
             IpAddr::V4(Ipv4Addr::from(stream.read_u32::<BigEndian>()?))
         };
 
-        let peer_as = if as_size {
-            stream.read_u32::<BigEndian>()?
-        } else {
-            u32::from(stream.read_u16::<BigEndian>()?)
-        };
+        let peer_as = asn_size.read(&mut stream)?;
 
         Ok(PeerEntry {
             peer_type,
@@ -188,10 +337,42 @@ impl PeerEntry {
             peer_as,
         })
     }
+
+    /// Returns the BGP identifier of the peer as the IPv4 address it conventionally represents.
+    pub fn bgp_id_ip(&self) -> Ipv4Addr {
+        Ipv4Addr::from(self.peer_bgp_id)
+    }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        let ipv6 = (self.peer_type & 1) != 0;
+        let as_size = (self.peer_type & 2) != 0;
+
+        stream.write_u8(self.peer_type)?;
+        stream.write_u32::<BigEndian>(self.peer_bgp_id)?;
+        if ipv6 != self.peer_ip_address.is_ipv6() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The peer type does not match the address family of the peer.",
+            ));
+        }
+        write_address(&mut stream, &self.peer_ip_address)?;
+
+        if as_size {
+            stream.write_u32::<BigEndian>(self.peer_as)
+        } else {
+            let peer_as = u16::try_from(self.peer_as).map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    "The peer type does not allow a 4-byte peer ASN.",
+                )
+            })?;
+            stream.write_u16::<BigEndian>(peer_as)
+        }
+    }
 }
 
 /// Represents a route in the Routing Information Base (RIB)
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct RIBEntry {
     /// The index of the peer inside the PEER_INDEX_TABLE.
     pub peer_index: u16,
@@ -218,10 +399,176 @@ impl RIBEntry {
             attributes,
         })
     }
+
+    ///
+    /// Parses the MP_REACH_NLRI attribute of this route, if present. The NLRI are decoded
+    /// without path identifiers.
+    ///
+    pub fn mp_reach_nlri(&self) -> Result<Option<MpReachNlri>, Error> {
+        attributes::find_mp_reach_nlri(&self.attributes, false)
+    }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(self.peer_index)?;
+        stream.write_u32::<BigEndian>(self.originated_time)?;
+        write_u16_prefixed(&mut stream, &self.attributes)
+    }
+}
+
+///
+/// Reads a single NLRI of a RIB_GENERIC record as it would appear in an MP_REACH_NLRI attribute.
+/// The returned bytes include the length field(s) of the NLRI, whose encoding depends on the
+/// address family.
+///
+fn read_generic_nlri(
+    mut stream: impl Read,
+    afi: AFI,
+    safi: u8,
+    options: &ParseOptions,
+) -> Result<Vec<u8>, Error> {
+    let mut nlri = Vec::new();
+    let length = match (afi, SAFI::try_from(safi).ok()) {
+        // Flow specifications use a 1-byte length, or a 12-bit length if it exceeds 239 bytes.
+        (AFI::IPV4 | AFI::IPV6, Some(SAFI::FLOWSPEC | SAFI::FLOWSPEC_VPN)) => {
+            let first = stream.read_u8()?;
+            nlri.push(first);
+            if first >= 0xF0 {
+                let second = stream.read_u8()?;
+                nlri.push(second);
+                (usize::from(first & 0x0F) << 8) | usize::from(second)
+            } else {
+                usize::from(first)
+            }
+        }
+
+        // IP prefixes, including labeled and VPN prefixes, start with their length in bits.
+        // The length of labeled and VPN prefixes includes the label and route distinguisher,
+        // so only unicast and multicast prefixes are validated against the address family.
+        (AFI::IPV4 | AFI::IPV6, safi) => {
+            let (prefix_length, prefix) = match safi {
+                Some(SAFI::UNICAST | SAFI::MULTICAST) => {
+                    nlri::read_prefix(&mut stream, &afi, options)?
+                }
+                _ => nlri::read_prefix_unchecked(&mut stream)?,
+            };
+            nlri.push(prefix_length);
+            nlri.extend(prefix);
+            return Ok(nlri);
+        }
+
+        // VPLS NLRI start with a 2-byte length.
+        (AFI::L2VPN, Some(SAFI::VPLS)) => {
+            let length = stream.read_u16::<BigEndian>()?;
+            nlri.extend(&length.to_be_bytes());
+            usize::from(length)
+        }
+
+        // EVPN NLRI start with a route type followed by a 1-byte length.
+        (AFI::L2VPN, Some(SAFI::EVPN)) => {
+            let route_type = stream.read_u8()?;
+            let length = stream.read_u8()?;
+            nlri.extend(&[route_type, length]);
+            usize::from(length)
+        }
+
+        // BGP-LS NLRI start with a 2-byte NLRI type followed by a 2-byte length.
+        (AFI::BGP_LS, _) => {
+            let nlri_type = stream.read_u16::<BigEndian>()?;
+            let length = stream.read_u16::<BigEndian>()?;
+            nlri.extend(&nlri_type.to_be_bytes());
+            nlri.extend(&length.to_be_bytes());
+            usize::from(length)
+        }
+
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Unsupported AFI {} and SAFI {} in RIB_GENERIC record.",
+                    afi.code(),
+                    safi
+                ),
+            ))
+        }
+    };
+
+    let header_length = nlri.len();
+    nlri.resize(header_length + length, 0);
+    stream.read_exact(&mut nlri[header_length..])?;
+    Ok(nlri)
+}
+
+/// The size of a RIB entry without any path attributes.
+const RIB_ENTRY_MIN_SIZE: u32 = 8;
+
+/// The size of a RIB entry with a path identifier without any path attributes.
+const RIB_ENTRY_ADDPATH_MIN_SIZE: u32 = 12;
+
+/// Validates that the bytes remaining after the first `consumed` bytes of the record can
+/// contain the given number of entries, before any memory is allocated for them.
+fn validate_entry_count(
+    header: &Header,
+    consumed: u32,
+    entry_count: u16,
+    entry_size: u32,
+) -> Result<(), Error> {
+    let remaining = header.length.saturating_sub(consumed);
+    if u32::from(entry_count) * entry_size > remaining {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Entry count {} requires at least {} bytes but only {} bytes remain",
+                entry_count,
+                u32::from(entry_count) * entry_size,
+                remaining
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+///
+/// Parses a RIB record from at most the `header.length` bytes of its body. If
+/// `ParseOptions::exact_rib_length` is set, reading beyond the body or leaving bytes of the body
+/// unread is reported as an error of kind `InvalidData`. Without it, bytes left unread are
+/// handled by `parse_record` according to `ParseOptions::reject_trailing_bytes`.
+///
+fn parse_bounded<R: Read, T>(
+    header: &Header,
+    stream: R,
+    options: &ParseOptions,
+    parse: impl FnOnce(&mut Take<R>) -> Result<T, Error>,
+) -> Result<T, Error> {
+    let mut stream = stream.take(u64::from(header.length));
+    let result = parse(&mut stream);
+    if !options.exact_rib_length {
+        return result;
+    }
+
+    match result {
+        Err(ref e) if e.kind() == ErrorKind::UnexpectedEof && stream.limit() == 0 => {
+            Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "RIB entries exceed the record length of {} bytes",
+                    header.length
+                ),
+            ))
+        }
+        Ok(_) if stream.limit() != 0 => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "{} bytes of the record remain after the RIB entries",
+                stream.limit()
+            ),
+        )),
+        x => x,
+    }
 }
 
 /// Represents a collection of routes for a specific IP prefix.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct RIB_AFI {
     /// A sequence number that identifies the route collection. Wraps back to zero on overflow.
@@ -238,15 +585,30 @@ pub struct RIB_AFI {
 }
 
 impl RIB_AFI {
-    fn parse(mut stream: impl Read) -> Result<RIB_AFI, Error> {
+    fn parse(
+        header: &Header,
+        stream: impl Read,
+        afi: AFI,
+        options: &ParseOptions,
+    ) -> Result<RIB_AFI, Error> {
+        parse_bounded(header, stream, options, |stream| {
+            RIB_AFI::parse_body(header, stream, afi, options)
+        })
+    }
+
+    fn parse_body(
+        header: &Header,
+        mut stream: impl Read,
+        afi: AFI,
+        options: &ParseOptions,
+    ) -> Result<RIB_AFI, Error> {
         let sequence_number = stream.read_u32::<BigEndian>()?;
 
-        let prefix_length: u8 = stream.read_u8()?;
-        let length: u8 = (prefix_length + 7) / 8;
-        let mut prefix: Vec<u8> = vec![0; length as usize];
-        stream.read_exact(&mut prefix)?;
+        let (prefix_length, prefix) = nlri::read_prefix(&mut stream, &afi, options)?;
 
         let entry_count = stream.read_u16::<BigEndian>()?;
+        let consumed = 7 + prefix.len() as u32;
+        validate_entry_count(header, consumed, entry_count, RIB_ENTRY_MIN_SIZE)?;
         let mut entries: Vec<RIBEntry> = Vec::with_capacity(entry_count as usize);
         for _ in 0..entry_count {
             entries.push(RIBEntry::parse(&mut stream)?);
@@ -259,10 +621,21 @@ impl RIB_AFI {
             entries,
         })
     }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u32::<BigEndian>(self.sequence_number)?;
+        stream.write_u8(self.prefix_length)?;
+        stream.write_all(&self.prefix)?;
+        write_count(&mut stream, self.entries.len())?;
+        for entry in &self.entries {
+            entry.write(&mut stream)?;
+        }
+        Ok(())
+    }
 }
 
-/// Represents a collection of routes for a specific IP prefix.
-#[derive(Debug)]
+/// Represents a collection of routes for a single NLRI of any address family.
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct RIB_GENERIC {
     /// A sequence number that identifies the route collection. Wraps back to zero on overflow.
@@ -274,7 +647,8 @@ pub struct RIB_GENERIC {
     /// The Subsequent Address Family Identifier (SAFI) of this RIB entry.
     pub safi: u8,
 
-    /// The NLRI in bytes.
+    /// A single NLRI encoded as in an MP_REACH_NLRI attribute, including its length field.
+    /// For IP address families this is the prefix length in bits followed by the prefix.
     pub nlri: Vec<u8>,
 
     /// A collection of routes to this prefix.
@@ -282,28 +656,19 @@ pub struct RIB_GENERIC {
 }
 
 impl RIB_GENERIC {
-    fn parse(mut stream: impl Read) -> Result<RIB_GENERIC, Error> {
+    fn parse(
+        header: &Header,
+        mut stream: impl Read,
+        options: &ParseOptions,
+    ) -> Result<RIB_GENERIC, Error> {
         let sequence_number = stream.read_u32::<BigEndian>()?;
-        let afi = AFI::from(stream.read_u16::<BigEndian>()?)?;
+        let afi = AFI::try_from(stream.read_u16::<BigEndian>()?)?;
         let safi = stream.read_u8()?;
-
-        let length = match afi {
-            AFI::IPV4 => {
-                match safi {
-                    // MPLS-labeled VPN address
-                    128 => (stream.read_u8()? + 7) / 8,
-
-                    // Default to 4.
-                    _ => 4,
-                }
-            }
-            AFI::IPV6 => 16,
-        };
-
-        let mut nlri: Vec<u8> = vec![0; length as usize];
-        stream.read_exact(&mut nlri)?;
+        let nlri = read_generic_nlri(&mut stream, afi, safi, options)?;
 
         let entry_count = stream.read_u16::<BigEndian>()?;
+        let consumed = 9 + nlri.len() as u32;
+        validate_entry_count(header, consumed, entry_count, RIB_ENTRY_MIN_SIZE)?;
         let mut entries: Vec<RIBEntry> = Vec::with_capacity(entry_count as usize);
         for _ in 0..entry_count {
             entries.push(RIBEntry::parse(&mut stream)?);
@@ -317,10 +682,22 @@ impl RIB_GENERIC {
             entries,
         })
     }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u32::<BigEndian>(self.sequence_number)?;
+        stream.write_u16::<BigEndian>(self.afi.code())?;
+        stream.write_u8(self.safi)?;
+        stream.write_all(&self.nlri)?;
+        write_count(&mut stream, self.entries.len())?;
+        for entry in &self.entries {
+            entry.write(&mut stream)?;
+        }
+        Ok(())
+    }
 }
 
 /// Represents a route in the Routing Information Base (RIB) allowing multiple paths.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct RIBEntryAddPath {
     /// The index of the peer inside the PEER_INDEX_TABLE.
     pub peer_index: u16,
@@ -351,10 +728,25 @@ impl RIBEntryAddPath {
             attributes,
         })
     }
+
+    ///
+    /// Parses the MP_REACH_NLRI attribute of this route, if present. As this route is part of
+    /// an ADD-PATH RIB, the NLRI are decoded including their path identifiers.
+    ///
+    pub fn mp_reach_nlri(&self) -> Result<Option<MpReachNlri>, Error> {
+        attributes::find_mp_reach_nlri(&self.attributes, true)
+    }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u16::<BigEndian>(self.peer_index)?;
+        stream.write_u32::<BigEndian>(self.originated_time)?;
+        stream.write_u32::<BigEndian>(self.path_identifier)?;
+        write_u16_prefixed(&mut stream, &self.attributes)
+    }
 }
 
 /// Represents a collection of routes for a specific IP prefix.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct RIB_AFI_ADDPATH {
     /// A sequence number that identifies the route collection. Wraps back to zero on overflow.
@@ -371,14 +763,29 @@ pub struct RIB_AFI_ADDPATH {
 }
 
 impl RIB_AFI_ADDPATH {
-    fn parse(mut stream: impl Read) -> Result<RIB_AFI_ADDPATH, Error> {
+    fn parse(
+        header: &Header,
+        stream: impl Read,
+        afi: AFI,
+        options: &ParseOptions,
+    ) -> Result<RIB_AFI_ADDPATH, Error> {
+        parse_bounded(header, stream, options, |stream| {
+            RIB_AFI_ADDPATH::parse_body(header, stream, afi, options)
+        })
+    }
+
+    fn parse_body(
+        header: &Header,
+        mut stream: impl Read,
+        afi: AFI,
+        options: &ParseOptions,
+    ) -> Result<RIB_AFI_ADDPATH, Error> {
         let sequence_number = stream.read_u32::<BigEndian>()?;
-        let prefix_length: u8 = stream.read_u8()?;
-        let length: u8 = (prefix_length + 7) / 8;
-        let mut prefix: Vec<u8> = vec![0; length as usize];
-        stream.read_exact(&mut prefix)?;
+        let (prefix_length, prefix) = nlri::read_prefix(&mut stream, &afi, options)?;
 
         let entry_count = stream.read_u16::<BigEndian>()?;
+        let consumed = 7 + prefix.len() as u32;
+        validate_entry_count(header, consumed, entry_count, RIB_ENTRY_ADDPATH_MIN_SIZE)?;
         let mut entries: Vec<RIBEntryAddPath> = Vec::with_capacity(entry_count as usize);
         for _ in 0..entry_count {
             entries.push(RIBEntryAddPath::parse(&mut stream)?);
@@ -391,10 +798,21 @@ impl RIB_AFI_ADDPATH {
             entries,
         })
     }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u32::<BigEndian>(self.sequence_number)?;
+        stream.write_u8(self.prefix_length)?;
+        stream.write_all(&self.prefix)?;
+        write_count(&mut stream, self.entries.len())?;
+        for entry in &self.entries {
+            entry.write(&mut stream)?;
+        }
+        Ok(())
+    }
 }
 
-/// Represents a collection of routes for a specific IP prefix.
-#[derive(Debug)]
+/// Represents a collection of routes for a single NLRI of any address family allowing multiple paths.
+#[derive(Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub struct RIB_GENERIC_ADDPATH {
     /// A sequence number that identifies the route collection. Wraps back to zero on overflow.
@@ -406,7 +824,8 @@ pub struct RIB_GENERIC_ADDPATH {
     /// The Subsequent Address Family Identifier (SAFI) of this RIB entry.
     pub safi: u8,
 
-    /// The NLRI in bytes.
+    /// A single NLRI encoded as in an MP_REACH_NLRI attribute, including its length field.
+    /// For IP address families this is the prefix length in bits followed by the prefix.
     pub nlri: Vec<u8>,
 
     /// A collection of routes to this prefix.
@@ -414,28 +833,19 @@ pub struct RIB_GENERIC_ADDPATH {
 }
 
 impl RIB_GENERIC_ADDPATH {
-    fn parse(mut stream: impl Read) -> Result<RIB_GENERIC_ADDPATH, Error> {
+    fn parse(
+        header: &Header,
+        mut stream: impl Read,
+        options: &ParseOptions,
+    ) -> Result<RIB_GENERIC_ADDPATH, Error> {
         let sequence_number = stream.read_u32::<BigEndian>()?;
-        let afi = AFI::from(stream.read_u16::<BigEndian>()?)?;
+        let afi = AFI::try_from(stream.read_u16::<BigEndian>()?)?;
         let safi = stream.read_u8()?;
-
-        let length = match afi {
-            AFI::IPV4 => {
-                match safi {
-                    // MPLS-labeled VPN address
-                    128 => (stream.read_u8()? + 7) / 8,
-
-                    // Default to 4.
-                    _ => 4,
-                }
-            }
-            AFI::IPV6 => 16,
-        };
-
-        let mut nlri: Vec<u8> = vec![0; length as usize];
-        stream.read_exact(&mut nlri)?;
+        let nlri = read_generic_nlri(&mut stream, afi, safi, options)?;
 
         let entry_count = stream.read_u16::<BigEndian>()?;
+        let consumed = 9 + nlri.len() as u32;
+        validate_entry_count(header, consumed, entry_count, RIB_ENTRY_ADDPATH_MIN_SIZE)?;
         let mut entries: Vec<RIBEntryAddPath> = Vec::with_capacity(entry_count as usize);
         for _ in 0..entry_count {
             entries.push(RIBEntryAddPath::parse(&mut stream)?);
@@ -449,10 +859,41 @@ impl RIB_GENERIC_ADDPATH {
             entries,
         })
     }
+
+    fn write(&self, mut stream: impl Write) -> Result<(), Error> {
+        stream.write_u32::<BigEndian>(self.sequence_number)?;
+        stream.write_u16::<BigEndian>(self.afi.code())?;
+        stream.write_u8(self.safi)?;
+        stream.write_all(&self.nlri)?;
+        write_count(&mut stream, self.entries.len())?;
+        for entry in &self.entries {
+            entry.write(&mut stream)?;
+        }
+        Ok(())
+    }
 }
 
 #[allow(non_camel_case_types)]
 impl TABLE_DUMP_V2 {
+    /// Returns the subtype of the MRT record from which this value is parsed.
+    pub fn sub_type(&self) -> u16 {
+        use codes::table_dump_v2 as sub_type;
+
+        match self {
+            TABLE_DUMP_V2::PEER_INDEX_TABLE(_) => sub_type::PEER_INDEX_TABLE,
+            TABLE_DUMP_V2::RIB_IPV4_UNICAST(_) => sub_type::RIB_IPV4_UNICAST,
+            TABLE_DUMP_V2::RIB_IPV4_MULTICAST(_) => sub_type::RIB_IPV4_MULTICAST,
+            TABLE_DUMP_V2::RIB_IPV6_UNICAST(_) => sub_type::RIB_IPV6_UNICAST,
+            TABLE_DUMP_V2::RIB_IPV6_MULTICAST(_) => sub_type::RIB_IPV6_MULTICAST,
+            TABLE_DUMP_V2::RIB_GENERIC(_) => sub_type::RIB_GENERIC,
+            TABLE_DUMP_V2::RIB_IPV4_UNICAST_ADDPATH(_) => sub_type::RIB_IPV4_UNICAST_ADDPATH,
+            TABLE_DUMP_V2::RIB_IPV4_MULTICAST_ADDPATH(_) => sub_type::RIB_IPV4_MULTICAST_ADDPATH,
+            TABLE_DUMP_V2::RIB_IPV6_UNICAST_ADDPATH(_) => sub_type::RIB_IPV6_UNICAST_ADDPATH,
+            TABLE_DUMP_V2::RIB_IPV6_MULTICAST_ADDPATH(_) => sub_type::RIB_IPV6_MULTICAST_ADDPATH,
+            TABLE_DUMP_V2::RIB_GENERIC_ADDPATH(_) => sub_type::RIB_GENERIC_ADDPATH,
+        }
+    }
+
     ///
     /// # Summary
     /// Used to parse TABLE_DUMP_V2 MRT records.
@@ -468,37 +909,79 @@ impl TABLE_DUMP_V2 {
     /// This function does not make use of unsafe code.
     ///
     pub fn parse(header: &Header, stream: impl Read) -> Result<TABLE_DUMP_V2, Error> {
+        TABLE_DUMP_V2::parse_with_options(header, stream, &ParseOptions::default())
+    }
+
+    pub(crate) fn parse_with_options(
+        header: &Header,
+        stream: impl Read,
+        options: &ParseOptions,
+    ) -> Result<TABLE_DUMP_V2, Error> {
         match header.sub_type {
-            1 => Ok(TABLE_DUMP_V2::PEER_INDEX_TABLE(PEER_INDEX_TABLE::parse(
-                stream,
-            )?)),
-            2 => Ok(TABLE_DUMP_V2::RIB_IPV4_UNICAST(RIB_AFI::parse(stream)?)),
-            3 => Ok(TABLE_DUMP_V2::RIB_IPV4_MULTICAST(RIB_AFI::parse(stream)?)),
-            4 => Ok(TABLE_DUMP_V2::RIB_IPV6_UNICAST(RIB_AFI::parse(stream)?)),
-            5 => Ok(TABLE_DUMP_V2::RIB_IPV6_MULTICAST(RIB_AFI::parse(stream)?)),
-            6 => Ok(TABLE_DUMP_V2::RIB_GENERIC(RIB_GENERIC::parse(stream)?)),
-            8 => Ok(TABLE_DUMP_V2::RIB_IPV4_UNICAST_ADDPATH(
-                RIB_AFI_ADDPATH::parse(stream)?,
+            codes::table_dump_v2::PEER_INDEX_TABLE => Ok(TABLE_DUMP_V2::PEER_INDEX_TABLE(
+                PEER_INDEX_TABLE::parse(header, stream, options)?,
             )),
-            9 => Ok(TABLE_DUMP_V2::RIB_IPV4_MULTICAST_ADDPATH(
-                RIB_AFI_ADDPATH::parse(stream)?,
+            codes::table_dump_v2::RIB_IPV4_UNICAST => Ok(TABLE_DUMP_V2::RIB_IPV4_UNICAST(
+                RIB_AFI::parse(header, stream, AFI::IPV4, options)?,
             )),
-            10 => Ok(TABLE_DUMP_V2::RIB_IPV6_UNICAST_ADDPATH(
-                RIB_AFI_ADDPATH::parse(stream)?,
+            codes::table_dump_v2::RIB_IPV4_MULTICAST => Ok(TABLE_DUMP_V2::RIB_IPV4_MULTICAST(
+                RIB_AFI::parse(header, stream, AFI::IPV4, options)?,
             )),
-            11 => Ok(TABLE_DUMP_V2::RIB_IPV6_MULTICAST_ADDPATH(
-                RIB_AFI_ADDPATH::parse(stream)?,
+            codes::table_dump_v2::RIB_IPV6_UNICAST => Ok(TABLE_DUMP_V2::RIB_IPV6_UNICAST(
+                RIB_AFI::parse(header, stream, AFI::IPV6, options)?,
             )),
-            12 => Ok(TABLE_DUMP_V2::RIB_GENERIC_ADDPATH(
-                RIB_GENERIC_ADDPATH::parse(stream)?,
+            codes::table_dump_v2::RIB_IPV6_MULTICAST => Ok(TABLE_DUMP_V2::RIB_IPV6_MULTICAST(
+                RIB_AFI::parse(header, stream, AFI::IPV6, options)?,
+            )),
+            codes::table_dump_v2::RIB_GENERIC => Ok(TABLE_DUMP_V2::RIB_GENERIC(
+                RIB_GENERIC::parse(header, stream, options)?,
+            )),
+            codes::table_dump_v2::RIB_IPV4_UNICAST_ADDPATH => {
+                Ok(TABLE_DUMP_V2::RIB_IPV4_UNICAST_ADDPATH(
+                    RIB_AFI_ADDPATH::parse(header, stream, AFI::IPV4, options)?,
+                ))
+            }
+            codes::table_dump_v2::RIB_IPV4_MULTICAST_ADDPATH => {
+                Ok(TABLE_DUMP_V2::RIB_IPV4_MULTICAST_ADDPATH(
+                    RIB_AFI_ADDPATH::parse(header, stream, AFI::IPV4, options)?,
+                ))
+            }
+            codes::table_dump_v2::RIB_IPV6_UNICAST_ADDPATH => {
+                Ok(TABLE_DUMP_V2::RIB_IPV6_UNICAST_ADDPATH(
+                    RIB_AFI_ADDPATH::parse(header, stream, AFI::IPV6, options)?,
+                ))
+            }
+            codes::table_dump_v2::RIB_IPV6_MULTICAST_ADDPATH => {
+                Ok(TABLE_DUMP_V2::RIB_IPV6_MULTICAST_ADDPATH(
+                    RIB_AFI_ADDPATH::parse(header, stream, AFI::IPV6, options)?,
+                ))
+            }
+            codes::table_dump_v2::RIB_GENERIC_ADDPATH => Ok(TABLE_DUMP_V2::RIB_GENERIC_ADDPATH(
+                RIB_GENERIC_ADDPATH::parse(header, stream, options)?,
             )),
             _ => {
                 let msg = format!(
                     "{} is not a valid sub-type of Tabledump v2",
                     header.sub_type
                 );
-                Err(std::io::Error::new(std::io::ErrorKind::Other, msg))
+                Err(std::io::Error::other(msg))
             }
         }
     }
+
+    pub(crate) fn write(&self, stream: impl Write) -> Result<(), Error> {
+        match self {
+            TABLE_DUMP_V2::PEER_INDEX_TABLE(x) => x.write(stream),
+            TABLE_DUMP_V2::RIB_IPV4_UNICAST(x)
+            | TABLE_DUMP_V2::RIB_IPV4_MULTICAST(x)
+            | TABLE_DUMP_V2::RIB_IPV6_UNICAST(x)
+            | TABLE_DUMP_V2::RIB_IPV6_MULTICAST(x) => x.write(stream),
+            TABLE_DUMP_V2::RIB_GENERIC(x) => x.write(stream),
+            TABLE_DUMP_V2::RIB_IPV4_UNICAST_ADDPATH(x)
+            | TABLE_DUMP_V2::RIB_IPV4_MULTICAST_ADDPATH(x)
+            | TABLE_DUMP_V2::RIB_IPV6_UNICAST_ADDPATH(x)
+            | TABLE_DUMP_V2::RIB_IPV6_MULTICAST_ADDPATH(x) => x.write(stream),
+            TABLE_DUMP_V2::RIB_GENERIC_ADDPATH(x) => x.write(stream),
+        }
+    }
 }
@@ -0,0 +1,339 @@
+use std::cmp::Ordering;
+use std::io::{Error, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::attributes::{self, MpReachNlri, PathAttribute};
+use crate::tabledump::{PeerEntry, TABLE_DUMP_V2};
+use crate::{AsnSize, MrtError, ParseOptions, Reader, Record, AFI, SAFI};
+
+///
+/// A single route of a TABLE_DUMP_V2 RIB dump, combining a RIB entry with the prefix of the
+/// record it belongs to and the peer from the last PEER_INDEX_TABLE.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// The timestamp of the record containing this route, which is the time of the dump.
+    pub timestamp: u32,
+
+    /// The index of the peer inside the PEER_INDEX_TABLE.
+    pub peer_index: u16,
+
+    /// The peer from which this route was received, if it is present in the PEER_INDEX_TABLE.
+    pub peer: Option<PeerEntry>,
+
+    /// The address of the prefix, with all bits beyond the prefix length set to zero.
+    pub prefix: IpAddr,
+
+    /// The length of the prefix in bits.
+    pub prefix_length: u8,
+
+    /// Whether this route is part of the unicast or the multicast RIB, as given by the subtype of
+    /// the record.
+    pub safi: SAFI,
+
+    /// The moment that this route was received.
+    pub originated_time: u32,
+
+    /// The path identifier of this route if the RIB dump uses ADD-PATH.
+    pub path_id: Option<u32>,
+
+    /// The BGP path attributes associated with this route.
+    pub attributes: Vec<u8>,
+}
+
+impl Route {
+    ///
+    /// # Summary
+    /// Parses the path attributes of this route. RIB dumps always use 4-byte ASNs.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// An error is returned if any of the path attributes is ill-formatted.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn parse_attributes(&self) -> Result<Vec<PathAttribute>, Error> {
+        attributes::parse(&self.attributes, AsnSize::Four, &ParseOptions::default())
+    }
+
+    ///
+    /// Parses the MP_REACH_NLRI attribute of this route, if present. The NLRI are decoded
+    /// including their path identifiers if the route is part of an ADD-PATH RIB.
+    ///
+    pub fn mp_reach_nlri(&self) -> Result<Option<MpReachNlri>, Error> {
+        attributes::find_mp_reach_nlri(&self.attributes, self.path_id.is_some())
+    }
+
+    /// Returns true if this route is part of a multicast RIB.
+    pub fn is_multicast(&self) -> bool {
+        self.safi == SAFI::MULTICAST
+    }
+}
+
+///
+/// An iterator over the routes of the TABLE_DUMP_V2 records read by a [`Reader`], as returned by
+/// [`Reader::routes`]. Records of other types and RIB_GENERIC records are skipped. A RIB dump
+/// consisting of only a PEER_INDEX_TABLE yields no routes. Peers are resolved using the most
+/// recent PEER_INDEX_TABLE, even if records of other types, such as the BGP4MP records of merged
+/// archives, are interleaved with the RIB records.
+///
+/// # Examples
+/// ```
+/// use std::fs::File;
+/// use mrt_rs::Reader;
+///
+/// let mut reader = Reader::new(File::open("res/quagga_rib").unwrap());
+/// for route in reader.routes() {
+///     let route = route.unwrap();
+///     println!("{}/{}", route.prefix, route.prefix_length);
+/// }
+/// ```
+///
+#[derive(Debug)]
+pub struct Routes<'a, T: Read> {
+    reader: &'a mut Reader<T>,
+    peers: Vec<PeerEntry>,
+    pending: std::vec::IntoIter<Route>,
+    done: bool,
+}
+
+impl<'a, T: Read> Routes<'a, T> {
+    pub(crate) fn new(reader: &'a mut Reader<T>) -> Routes<'a, T> {
+        Routes {
+            reader,
+            peers: Vec::new(),
+            pending: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+
+    ///
+    /// Yields only the first route of every prefix. Since a TABLE_DUMP_V2 RIB dump contains all
+    /// routes of a prefix in a single record, only consecutive routes are compared, which keeps
+    /// memory usage constant.
+    ///
+    pub fn dedup_by_prefix(self) -> DedupByPrefix<Self, fn(&Route, &Route) -> Ordering> {
+        DedupByPrefix::new(self, |_, _| Ordering::Equal)
+    }
+
+    ///
+    /// Yields only the best route of every prefix, being the greatest route according to the
+    /// given comparator. Of multiple equally good routes the first one is yielded.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::fs::File;
+    /// use mrt_rs::attributes::PathAttribute;
+    /// use mrt_rs::{Reader, Route};
+    ///
+    /// fn local_pref(route: &Route) -> u32 {
+    ///     let attributes = route.parse_attributes().unwrap_or_default();
+    ///     attributes
+    ///         .iter()
+    ///         .find_map(|x| match x {
+    ///             PathAttribute::LOCAL_PREF(x) => Some(*x),
+    ///             _ => None,
+    ///         })
+    ///         .unwrap_or(100)
+    /// }
+    ///
+    /// let mut reader = Reader::new(File::open("res/quagga_rib").unwrap());
+    /// let routes = reader
+    ///     .routes()
+    ///     .dedup_by_prefix_with(|a, b| local_pref(a).cmp(&local_pref(b)));
+    /// assert_eq!(routes.count(), 6);
+    /// ```
+    ///
+    pub fn dedup_by_prefix_with<F>(self, compare: F) -> DedupByPrefix<Self, F>
+    where
+        F: FnMut(&Route, &Route) -> Ordering,
+    {
+        DedupByPrefix::new(self, compare)
+    }
+
+    /// Converts the routes of a single record, updating the peers for a PEER_INDEX_TABLE.
+    fn expand(&mut self, timestamp: u32, record: TABLE_DUMP_V2) -> Vec<Route> {
+        // The peer index, originated time, path identifier and attributes of every entry.
+        let safi = match record {
+            TABLE_DUMP_V2::RIB_IPV4_MULTICAST(_)
+            | TABLE_DUMP_V2::RIB_IPV6_MULTICAST(_)
+            | TABLE_DUMP_V2::RIB_IPV4_MULTICAST_ADDPATH(_)
+            | TABLE_DUMP_V2::RIB_IPV6_MULTICAST_ADDPATH(_) => SAFI::MULTICAST,
+            _ => SAFI::UNICAST,
+        };
+        let (afi, prefix_length, prefix, entries): (_, _, _, Vec<_>) = match record {
+            TABLE_DUMP_V2::PEER_INDEX_TABLE(x) => {
+                self.peers = x.peer_entries;
+                return Vec::new();
+            }
+            TABLE_DUMP_V2::RIB_IPV4_UNICAST(x) | TABLE_DUMP_V2::RIB_IPV4_MULTICAST(x) => {
+                let entries = x.entries.into_iter();
+                let entries =
+                    entries.map(|e| (e.peer_index, e.originated_time, None, e.attributes));
+                (AFI::IPV4, x.prefix_length, x.prefix, entries.collect())
+            }
+            TABLE_DUMP_V2::RIB_IPV6_UNICAST(x) | TABLE_DUMP_V2::RIB_IPV6_MULTICAST(x) => {
+                let entries = x.entries.into_iter();
+                let entries =
+                    entries.map(|e| (e.peer_index, e.originated_time, None, e.attributes));
+                (AFI::IPV6, x.prefix_length, x.prefix, entries.collect())
+            }
+            TABLE_DUMP_V2::RIB_IPV4_UNICAST_ADDPATH(x)
+            | TABLE_DUMP_V2::RIB_IPV4_MULTICAST_ADDPATH(x) => {
+                let entries = x.entries.into_iter().map(|e| {
+                    let path_id = Some(e.path_identifier);
+                    (e.peer_index, e.originated_time, path_id, e.attributes)
+                });
+                (AFI::IPV4, x.prefix_length, x.prefix, entries.collect())
+            }
+            TABLE_DUMP_V2::RIB_IPV6_UNICAST_ADDPATH(x)
+            | TABLE_DUMP_V2::RIB_IPV6_MULTICAST_ADDPATH(x) => {
+                let entries = x.entries.into_iter().map(|e| {
+                    let path_id = Some(e.path_identifier);
+                    (e.peer_index, e.originated_time, path_id, e.attributes)
+                });
+                (AFI::IPV6, x.prefix_length, x.prefix, entries.collect())
+            }
+            TABLE_DUMP_V2::RIB_GENERIC(_) | TABLE_DUMP_V2::RIB_GENERIC_ADDPATH(_) => {
+                return Vec::new()
+            }
+        };
+
+        let prefix = match prefix_address(afi, &prefix, prefix_length) {
+            Some(x) => x,
+            None => return Vec::new(),
+        };
+        entries
+            .into_iter()
+            .map(|(peer_index, originated_time, path_id, attributes)| Route {
+                timestamp,
+                peer_index,
+                peer: self.peers.get(peer_index as usize).cloned(),
+                prefix,
+                prefix_length,
+                safi,
+                originated_time,
+                path_id,
+                attributes,
+            })
+            .collect()
+    }
+}
+
+impl<T: Read> Iterator for Routes<'_, T> {
+    type Item = Result<Route, MrtError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(route) = self.pending.next() {
+                return Some(Ok(route));
+            }
+            if self.done {
+                return None;
+            }
+
+            match self.reader.read() {
+                Ok(Some((header, Record::TABLE_DUMP_V2(record)))) => {
+                    self.pending = self.expand(header.timestamp, record).into_iter();
+                }
+                Ok(Some(_)) => (),
+                Ok(None) => self.done = true,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+///
+/// An iterator adapter that yields a single route per prefix out of consecutive routes for the
+/// same prefix, as returned by [`Routes::dedup_by_prefix`] and [`Routes::dedup_by_prefix_with`].
+/// IPv4 and IPv6 prefixes may be mixed in the same stream. Unicast and multicast routes for the
+/// same prefix are considered different prefixes.
+///
+#[derive(Debug)]
+pub struct DedupByPrefix<I, F> {
+    inner: I,
+    compare: F,
+
+    /// The item following the routes of the previously yielded prefix.
+    next: Option<Result<Route, MrtError>>,
+}
+
+impl<I, F> DedupByPrefix<I, F> {
+    fn new(inner: I, compare: F) -> DedupByPrefix<I, F> {
+        DedupByPrefix {
+            inner,
+            compare,
+            next: None,
+        }
+    }
+}
+
+impl<I, F> Iterator for DedupByPrefix<I, F>
+where
+    I: Iterator<Item = Result<Route, MrtError>>,
+    F: FnMut(&Route, &Route) -> Ordering,
+{
+    type Item = Result<Route, MrtError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut best = match self.next.take().or_else(|| self.inner.next())? {
+            Ok(route) => route,
+            Err(e) => return Some(Err(e)),
+        };
+
+        loop {
+            match self.inner.next() {
+                Some(Ok(route))
+                    if route.prefix == best.prefix
+                        && route.prefix_length == best.prefix_length
+                        && route.safi == best.safi =>
+                {
+                    if (self.compare)(&route, &best) == Ordering::Greater {
+                        best = route;
+                    }
+                }
+                // Errors are returned after the route of the preceding prefix.
+                next => {
+                    self.next = next;
+                    return Some(Ok(best));
+                }
+            }
+        }
+    }
+}
+
+/// Converts a prefix truncated to whole bytes into an address of the given family, setting all
+/// bits beyond the prefix length to zero. Returns `None` if the prefixes of the family are not
+/// IP prefixes.
+pub(crate) fn prefix_address(afi: AFI, prefix: &[u8], prefix_length: u8) -> Option<IpAddr> {
+    match afi {
+        AFI::IPV4 => {
+            let mut bytes = [0; 4];
+            let length = prefix.len().min(4);
+            bytes[..length].copy_from_slice(&prefix[..length]);
+            let mask = u32::MAX
+                .checked_shl(32_u32.saturating_sub(u32::from(prefix_length)))
+                .unwrap_or(0);
+            Some(IpAddr::V4(Ipv4Addr::from(u32::from_be_bytes(bytes) & mask)))
+        }
+        AFI::IPV6 => {
+            let mut bytes = [0; 16];
+            let length = prefix.len().min(16);
+            bytes[..length].copy_from_slice(&prefix[..length]);
+            let mask = u128::MAX
+                .checked_shl(128_u32.saturating_sub(u32::from(prefix_length)))
+                .unwrap_or(0);
+            Some(IpAddr::V6(Ipv6Addr::from(
+                u128::from_be_bytes(bytes) & mask,
+            )))
+        }
+        AFI::L2VPN | AFI::BGP_LS => None,
+    }
+}
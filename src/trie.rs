@@ -0,0 +1,148 @@
+use std::io::Read;
+use std::net::IpAddr;
+
+use crate::{MrtError, Reader, Route};
+
+///
+/// A binary prefix trie holding the routes of a TABLE_DUMP_V2 RIB dump, which allows
+/// longest-prefix-match lookups against the routing table at the time of the dump.
+///
+/// # Examples
+/// ```
+/// use std::fs::File;
+/// use mrt_rs::{Reader, RibTrie};
+///
+/// let mut reader = Reader::new(File::open("res/openbgpd_rib_table-v2").unwrap());
+/// let trie = RibTrie::from_reader(&mut reader).unwrap();
+/// let route = trie.longest_match("192.168.1.77".parse().unwrap()).unwrap();
+/// assert_eq!(route.prefix_length, 24);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct RibTrie {
+    /// All nodes of the trie. The first node is the root of the IPv4 trie and the second node
+    /// is the root of the IPv6 trie.
+    nodes: Vec<Node>,
+
+    /// The number of distinct prefixes for which routes are present.
+    prefixes: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// The indices of the nodes extending the prefix of this node with a zero and a one bit.
+    children: [Option<usize>; 2],
+
+    /// The routes of the prefix ending at this node.
+    routes: Vec<Route>,
+}
+
+impl RibTrie {
+    /// Creates an empty trie.
+    pub fn new() -> RibTrie {
+        RibTrie {
+            nodes: vec![Node::default(), Node::default()],
+            prefixes: 0,
+        }
+    }
+
+    ///
+    /// # Summary
+    /// Builds a trie from the routes of all remaining TABLE_DUMP_V2 records of the Reader.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any error encountered while reading the records is returned.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn from_reader<T: Read>(reader: &mut Reader<T>) -> Result<RibTrie, MrtError> {
+        let mut trie = RibTrie::new();
+        for route in reader.routes() {
+            trie.insert(route?);
+        }
+        Ok(trie)
+    }
+
+    /// Adds a route to the trie. Prefix lengths exceeding the address family are truncated.
+    pub fn insert(&mut self, route: Route) {
+        let (mut index, bits, max_length) = RibTrie::root(route.prefix);
+        for depth in 0..route.prefix_length.min(max_length) {
+            let bit = bit_at(bits, depth);
+            index = match self.nodes[index].children[bit] {
+                Some(child) => child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[index].children[bit] = Some(child);
+                    child
+                }
+            };
+        }
+
+        let routes = &mut self.nodes[index].routes;
+        if routes.is_empty() {
+            self.prefixes += 1;
+        }
+        routes.push(route);
+    }
+
+    ///
+    /// Returns the first route of the most specific prefix containing the given address, or
+    /// `None` if no prefix contains it.
+    ///
+    pub fn longest_match(&self, address: IpAddr) -> Option<&Route> {
+        self.longest_match_all(address).first()
+    }
+
+    ///
+    /// Returns all routes of the most specific prefix containing the given address, typically
+    /// one for every peer. The slice is empty if no prefix contains the address.
+    ///
+    pub fn longest_match_all(&self, address: IpAddr) -> &[Route] {
+        let (mut index, bits, max_length) = RibTrie::root(address);
+        let mut best = &self.nodes[index].routes;
+        for depth in 0..max_length {
+            match self.nodes[index].children[bit_at(bits, depth)] {
+                Some(child) => index = child,
+                None => break,
+            }
+            if !self.nodes[index].routes.is_empty() {
+                best = &self.nodes[index].routes;
+            }
+        }
+        best
+    }
+
+    /// Returns the number of distinct prefixes in the trie.
+    pub fn len(&self) -> usize {
+        self.prefixes
+    }
+
+    /// Returns true if the trie does not contain any routes.
+    pub fn is_empty(&self) -> bool {
+        self.prefixes == 0
+    }
+
+    /// Returns the index of the root node, the bits and the maximum prefix length of an address.
+    fn root(address: IpAddr) -> (usize, u128, u8) {
+        match address {
+            IpAddr::V4(x) => (0, u128::from(u32::from(x)) << 96, 32),
+            IpAddr::V6(x) => (1, u128::from(x), 128),
+        }
+    }
+}
+
+impl Default for RibTrie {
+    fn default() -> RibTrie {
+        RibTrie::new()
+    }
+}
+
+/// Returns the bit at the given depth, counting from the most significant bit.
+fn bit_at(bits: u128, depth: u8) -> usize {
+    ((bits >> (127 - u32::from(depth))) & 1) as usize
+}
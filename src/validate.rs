@@ -0,0 +1,248 @@
+use crate::attributes::{self, PathAttribute};
+use crate::bgp::{self, MessageHeader, UPDATE_TYPE};
+use crate::bgp4mp::BGP4MP;
+use crate::tabledump::{PeerEntry, PEER_INDEX_TABLE, TABLE_DUMP_V2};
+use crate::{AsnSize, ParseOptions, Record};
+
+///
+/// The state against which records are validated by `Record::validate`. The context is usually
+/// kept up to date while reading a stream by passing every record to `update`.
+///
+#[derive(Debug, Clone, Default)]
+pub struct ValidationContext {
+    /// The peers of the most recent PEER_INDEX_TABLE, or `None` if no such table has been seen.
+    pub peers: Option<Vec<PeerEntry>>,
+
+    /// The ASN of the collector, which is required to tell whether the routes of a RIB dump
+    /// were received over an eBGP session. RIB entries are not checked for it if unknown.
+    pub local_as: Option<u32>,
+}
+
+impl ValidationContext {
+    /// Creates a context without any peers and without the ASN of the collector.
+    pub fn new() -> ValidationContext {
+        ValidationContext::default()
+    }
+
+    /// Creates a context containing the peers of the given PEER_INDEX_TABLE.
+    pub fn with_peer_index_table(table: &PEER_INDEX_TABLE) -> ValidationContext {
+        ValidationContext {
+            peers: Some(table.peer_entries.clone()),
+            local_as: None,
+        }
+    }
+
+    /// Sets `ValidationContext::local_as`.
+    pub fn local_as(mut self, local_as: u32) -> ValidationContext {
+        self.local_as = Some(local_as);
+        self
+    }
+
+    /// Keeps the peers of the record if it is a PEER_INDEX_TABLE, such that the RIB entries
+    /// following it can be validated.
+    pub fn update(&mut self, record: &Record) {
+        if let Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(x)) = record {
+            self.peers = Some(x.peer_entries.clone());
+        }
+    }
+}
+
+/// A data-quality issue found by `Record::validate` in a record that was parsed successfully.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationWarning {
+    /// The record contains RIB entries but no PEER_INDEX_TABLE is known.
+    MissingPeerIndexTable,
+
+    /// A RIB entry references a peer that is not present in the PEER_INDEX_TABLE.
+    PeerIndexOutOfRange {
+        /// The peer index of the RIB entry.
+        peer_index: u16,
+        /// The number of peers in the PEER_INDEX_TABLE.
+        peer_count: usize,
+    },
+
+    /// A route received over an eBGP session has a missing or empty AS_PATH.
+    EmptyAsPath {
+        /// The ASN of the peer from which the route was received.
+        peer_as: u32,
+    },
+
+    /// The path attributes or BGP message of the record could not be parsed.
+    Malformed(String),
+}
+
+impl Record {
+    ///
+    /// # Summary
+    /// Checks the semantics of a parsed record and returns the issues that were found, without
+    /// rejecting the record. The following is checked:
+    ///
+    /// * Every RIB entry of a TABLE_DUMP_V2 record references a peer of the PEER_INDEX_TABLE.
+    /// * Routes received over eBGP sessions have a non-empty AS_PATH. For RIB entries this
+    ///   requires `ValidationContext::local_as`, for BGP4MP messages the ASNs of the session
+    ///   are part of the record. Messages using ADD-PATH are not checked.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::fs::File;
+    /// use mrt_rs::{Reader, ValidationContext};
+    ///
+    /// let mut reader = Reader::new(File::open("res/quagga_rib").unwrap());
+    /// let mut context = ValidationContext::new();
+    /// while let Some((_, record)) = reader.read().unwrap() {
+    ///     context.update(&record);
+    ///     assert!(record.validate(&context).is_empty());
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn validate(&self, context: &ValidationContext) -> Vec<ValidationWarning> {
+        let mut warnings = Vec::new();
+        match self {
+            Record::TABLE_DUMP_V2(x) => validate_rib(x, context, &mut warnings),
+            Record::BGP4MP(x) | Record::BGP4MP_ET(x) => match x {
+                BGP4MP::MESSAGE(y) | BGP4MP::MESSAGE_LOCAL(y) => validate_message(
+                    u32::from(y.peer_as),
+                    u32::from(y.local_as),
+                    &y.message,
+                    AsnSize::Two,
+                    &mut warnings,
+                ),
+                BGP4MP::MESSAGE_AS4(y) | BGP4MP::MESSAGE_AS4_LOCAL(y) => validate_message(
+                    y.peer_as,
+                    y.local_as,
+                    &y.message,
+                    AsnSize::Four,
+                    &mut warnings,
+                ),
+                _ => (),
+            },
+            _ => (),
+        }
+        warnings
+    }
+}
+
+/// Validates the peer indices and AS_PATHs of the RIB entries of a TABLE_DUMP_V2 record.
+fn validate_rib(
+    record: &TABLE_DUMP_V2,
+    context: &ValidationContext,
+    warnings: &mut Vec<ValidationWarning>,
+) {
+    let entries: Vec<(u16, &[u8])> = match record {
+        TABLE_DUMP_V2::PEER_INDEX_TABLE(_) => return,
+        TABLE_DUMP_V2::RIB_IPV4_UNICAST(x)
+        | TABLE_DUMP_V2::RIB_IPV4_MULTICAST(x)
+        | TABLE_DUMP_V2::RIB_IPV6_UNICAST(x)
+        | TABLE_DUMP_V2::RIB_IPV6_MULTICAST(x) => x
+            .entries
+            .iter()
+            .map(|e| (e.peer_index, &e.attributes[..]))
+            .collect(),
+        TABLE_DUMP_V2::RIB_IPV4_UNICAST_ADDPATH(x)
+        | TABLE_DUMP_V2::RIB_IPV4_MULTICAST_ADDPATH(x)
+        | TABLE_DUMP_V2::RIB_IPV6_UNICAST_ADDPATH(x)
+        | TABLE_DUMP_V2::RIB_IPV6_MULTICAST_ADDPATH(x) => x
+            .entries
+            .iter()
+            .map(|e| (e.peer_index, &e.attributes[..]))
+            .collect(),
+        TABLE_DUMP_V2::RIB_GENERIC(x) => x
+            .entries
+            .iter()
+            .map(|e| (e.peer_index, &e.attributes[..]))
+            .collect(),
+        TABLE_DUMP_V2::RIB_GENERIC_ADDPATH(x) => x
+            .entries
+            .iter()
+            .map(|e| (e.peer_index, &e.attributes[..]))
+            .collect(),
+    };
+
+    let peers = match &context.peers {
+        Some(x) => x,
+        None => {
+            if !entries.is_empty() {
+                warnings.push(ValidationWarning::MissingPeerIndexTable);
+            }
+            return;
+        }
+    };
+
+    for (peer_index, attributes) in entries {
+        let peer = match peers.get(usize::from(peer_index)) {
+            Some(x) => x,
+            None => {
+                warnings.push(ValidationWarning::PeerIndexOutOfRange {
+                    peer_index,
+                    peer_count: peers.len(),
+                });
+                continue;
+            }
+        };
+
+        match context.local_as {
+            Some(local_as) if local_as != peer.peer_as => {
+                match attributes::parse(attributes, AsnSize::Four, &ParseOptions::default()) {
+                    Ok(x) => validate_as_path(peer.peer_as, &x, warnings),
+                    Err(e) => warnings.push(ValidationWarning::Malformed(e.to_string())),
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Validates the AS_PATH of an UPDATE message announcing routes over an eBGP session.
+fn validate_message(
+    peer_as: u32,
+    local_as: u32,
+    message: &[u8],
+    asn_size: AsnSize,
+    warnings: &mut Vec<ValidationWarning>,
+) {
+    if peer_as == local_as {
+        return;
+    }
+    match MessageHeader::parse(message) {
+        Ok(x) if x.message_type == UPDATE_TYPE => (),
+        _ => return,
+    }
+
+    match bgp::parse_update(message, asn_size, &ParseOptions::default()) {
+        Ok(update) => {
+            let announces = !update.nlri.is_empty()
+                || update
+                    .attributes
+                    .iter()
+                    .any(|x| matches!(x, PathAttribute::MP_REACH_NLRI(_)));
+            if announces {
+                validate_as_path(peer_as, &update.attributes, warnings);
+            }
+        }
+        Err(e) => warnings.push(ValidationWarning::Malformed(e.to_string())),
+    }
+}
+
+/// Reports a missing or empty AS_PATH among the attributes of a route received from a peer.
+fn validate_as_path(
+    peer_as: u32,
+    attributes: &[PathAttribute],
+    warnings: &mut Vec<ValidationWarning>,
+) {
+    let empty = attributes
+        .iter()
+        .find_map(|x| match x {
+            PathAttribute::AS_PATH(path) => Some(path.is_empty()),
+            _ => None,
+        })
+        .unwrap_or(true);
+    if empty {
+        warnings.push(ValidationWarning::EmptyAsPath { peer_as });
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::{btree_map, BTreeMap};
+use std::io::Read;
+
+use crate::tabledump::TABLE_DUMP;
+use crate::{Header, MrtError, Reader, Record};
+
+///
+/// Groups the records of a legacy TABLE_DUMP RIB dump by their view number and returns the
+/// views in ascending order of their view number. Within a view the records are returned in the
+/// order of their sequence numbers, taking into account that sequence numbers wrap back to 0
+/// after 65535. All other record types are skipped.
+///
+/// Since the records of different views may be interleaved, the whole stream is read into memory
+/// before the first view is returned.
+///
+/// # Examples
+/// ```
+/// use std::fs::File;
+/// use mrt_rs::{Reader, TableDumpReader};
+///
+/// let reader = Reader::new(File::open("res/openbgpd_rib_table").unwrap());
+/// for view in TableDumpReader::new(reader) {
+///     let (view_number, records) = view.unwrap();
+///     println!("View {} contains {} prefixes", view_number, records.len());
+/// }
+/// ```
+///
+#[derive(Debug)]
+pub struct TableDumpReader<T: Read> {
+    reader: Reader<T>,
+
+    /// The grouped views, or `None` if the stream has not been read yet.
+    views: Option<btree_map::IntoIter<u16, Vec<(Header, TABLE_DUMP)>>>,
+}
+
+/// The records of a single view together with the position of the last record of that view.
+#[derive(Default)]
+struct View {
+    /// The sequence number of the last record of this view.
+    last: u16,
+
+    /// The position of the last record of this view, which keeps increasing past 65535.
+    position: i64,
+
+    /// The records of this view together with their position.
+    records: Vec<(i64, Header, TABLE_DUMP)>,
+}
+
+impl<T: Read> TableDumpReader<T> {
+    /// Creates a TableDumpReader that reads the TABLE_DUMP records of the given Reader.
+    pub fn new(reader: Reader<T>) -> TableDumpReader<T> {
+        TableDumpReader {
+            reader,
+            views: None,
+        }
+    }
+
+    /// Reads all remaining records and groups the TABLE_DUMP records by their view number.
+    fn group(&mut self) -> Result<BTreeMap<u16, Vec<(Header, TABLE_DUMP)>>, MrtError> {
+        let mut views: BTreeMap<u16, View> = BTreeMap::new();
+        while let Some((header, record)) = self.reader.read()? {
+            let record = match record {
+                Record::TABLE_DUMP(x) => x,
+                _ => continue,
+            };
+
+            let view = views.entry(record.view_number).or_default();
+
+            // The sequence number is interpreted relative to the previous record of the view,
+            // such that a small step backwards is a reordering instead of a wraparound.
+            if !view.records.is_empty() {
+                let step = record.sequence_number.wrapping_sub(view.last) as i16;
+                view.position += i64::from(step);
+            } else {
+                view.position = i64::from(record.sequence_number);
+            }
+            view.last = record.sequence_number;
+            view.records.push((view.position, header, record));
+        }
+
+        Ok(views
+            .into_iter()
+            .map(|(view_number, mut view)| {
+                view.records.sort_by_key(|(position, _, _)| *position);
+                let records = view.records.into_iter().map(|(_, h, r)| (h, r));
+                (view_number, records.collect())
+            })
+            .collect())
+    }
+}
+
+impl<T: Read> Iterator for TableDumpReader<T> {
+    type Item = Result<(u16, Vec<(Header, TABLE_DUMP)>), MrtError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.views.is_none() {
+            match self.group() {
+                Ok(x) => self.views = Some(x.into_iter()),
+                Err(e) => {
+                    self.views = Some(BTreeMap::new().into_iter());
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        self.views.as_mut()?.next().map(Ok)
+    }
+}
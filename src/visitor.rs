@@ -0,0 +1,136 @@
+use crate::bgp4mp::{BGP4MP, MESSAGE, MESSAGE_AS4, STATE_CHANGE, STATE_CHANGE_AS4};
+use crate::tabledump::{
+    RIBEntry, RIBEntryAddPath, PEER_INDEX_TABLE, RIB_AFI, RIB_AFI_ADDPATH, RIB_GENERIC,
+    RIB_GENERIC_ADDPATH, TABLE_DUMP_V2,
+};
+use crate::{Header, Record};
+
+///
+/// Receives the records read by [`Reader::drive`](crate::Reader::drive). All methods have a
+/// default implementation that does nothing, so a visitor only implements the methods of the
+/// records it is interested in.
+///
+/// # Examples
+/// ```
+/// use mrt_rs::{Header, Reader, RecordVisitor};
+/// use mrt_rs::tabledump::RIBEntry;
+///
+/// struct EntryCounter(usize);
+///
+/// impl RecordVisitor for EntryCounter {
+///     fn visit_header(&mut self, header: &Header) -> bool {
+///         header.record_type == 13
+///     }
+///
+///     fn visit_rib_entry(&mut self, _: &Header, _: &RIBEntry) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// let mut counter = EntryCounter(0);
+/// let mut reader = Reader::new(std::fs::File::open("res/quagga_rib").unwrap());
+/// reader.drive(&mut counter).unwrap();
+/// ```
+///
+pub trait RecordVisitor {
+    ///
+    /// Called for every record before its body is parsed. If false is returned the body is
+    /// skipped without being parsed and none of the other methods are called for this record.
+    ///
+    fn visit_header(&mut self, _header: &Header) -> bool {
+        true
+    }
+
+    /// Called for BGP4MP(_ET) messages using 2-byte ASNs, including the LOCAL and ADDPATH variants.
+    fn visit_bgp4mp_message(&mut self, _header: &Header, _message: &MESSAGE) {}
+
+    /// Called for BGP4MP(_ET) messages using 4-byte ASNs, including the LOCAL and ADDPATH variants.
+    fn visit_bgp4mp_message_as4(&mut self, _header: &Header, _message: &MESSAGE_AS4) {}
+
+    /// Called for BGP4MP(_ET) state changes using 2-byte ASNs.
+    fn visit_bgp4mp_state_change(&mut self, _header: &Header, _state_change: &STATE_CHANGE) {}
+
+    /// Called for BGP4MP(_ET) state changes using 4-byte ASNs.
+    fn visit_bgp4mp_state_change_as4(
+        &mut self,
+        _header: &Header,
+        _state_change: &STATE_CHANGE_AS4,
+    ) {
+    }
+
+    /// Called for every PEER_INDEX_TABLE of a TABLE_DUMP_V2 stream.
+    fn visit_peer_index_table(&mut self, _header: &Header, _table: &PEER_INDEX_TABLE) {}
+
+    /// Called for the AFI/SAFI-specific RIB records. Calls `visit_rib_entry` for every entry by default.
+    fn visit_rib_afi(&mut self, header: &Header, rib: &RIB_AFI) {
+        for entry in &rib.entries {
+            self.visit_rib_entry(header, entry);
+        }
+    }
+
+    /// Called for RIB_GENERIC records. Calls `visit_rib_entry` for every entry by default.
+    fn visit_rib_generic(&mut self, header: &Header, rib: &RIB_GENERIC) {
+        for entry in &rib.entries {
+            self.visit_rib_entry(header, entry);
+        }
+    }
+
+    /// Called for the ADD-PATH variants of the AFI/SAFI-specific RIB records.
+    /// Calls `visit_rib_entry_addpath` for every entry by default.
+    fn visit_rib_afi_addpath(&mut self, header: &Header, rib: &RIB_AFI_ADDPATH) {
+        for entry in &rib.entries {
+            self.visit_rib_entry_addpath(header, entry);
+        }
+    }
+
+    /// Called for RIB_GENERIC_ADDPATH records. Calls `visit_rib_entry_addpath` for every entry by default.
+    fn visit_rib_generic_addpath(&mut self, header: &Header, rib: &RIB_GENERIC_ADDPATH) {
+        for entry in &rib.entries {
+            self.visit_rib_entry_addpath(header, entry);
+        }
+    }
+
+    /// Called for every entry of a RIB record without ADD-PATH support.
+    fn visit_rib_entry(&mut self, _header: &Header, _entry: &RIBEntry) {}
+
+    /// Called for every entry of a RIB record with ADD-PATH support.
+    fn visit_rib_entry_addpath(&mut self, _header: &Header, _entry: &RIBEntryAddPath) {}
+
+    /// Called for all records that are not handled by any of the other methods.
+    fn visit_record(&mut self, _header: &Header, _record: &Record) {}
+}
+
+/// Dispatches a record to the matching method of the visitor.
+pub(crate) fn dispatch<V: RecordVisitor>(visitor: &mut V, header: &Header, record: &Record) {
+    match record {
+        Record::BGP4MP(x) | Record::BGP4MP_ET(x) => match x {
+            BGP4MP::MESSAGE(m)
+            | BGP4MP::MESSAGE_LOCAL(m)
+            | BGP4MP::MESSAGE_ADDPATH(m)
+            | BGP4MP::MESSAGE_LOCAL_ADDPATH(m) => visitor.visit_bgp4mp_message(header, m),
+            BGP4MP::MESSAGE_AS4(m)
+            | BGP4MP::MESSAGE_AS4_LOCAL(m)
+            | BGP4MP::MESSAGE_AS4_ADDPATH(m)
+            | BGP4MP::MESSAGE_AS4_LOCAL_ADDPATH(m) => visitor.visit_bgp4mp_message_as4(header, m),
+            BGP4MP::STATE_CHANGE(s) => visitor.visit_bgp4mp_state_change(header, s),
+            BGP4MP::STATE_CHANGE_AS4(s) => visitor.visit_bgp4mp_state_change_as4(header, s),
+            BGP4MP::ENTRY(_) | BGP4MP::SNAPSHOT(_) => visitor.visit_record(header, record),
+        },
+        Record::TABLE_DUMP_V2(x) => match x {
+            TABLE_DUMP_V2::PEER_INDEX_TABLE(t) => visitor.visit_peer_index_table(header, t),
+            TABLE_DUMP_V2::RIB_IPV4_UNICAST(r)
+            | TABLE_DUMP_V2::RIB_IPV4_MULTICAST(r)
+            | TABLE_DUMP_V2::RIB_IPV6_UNICAST(r)
+            | TABLE_DUMP_V2::RIB_IPV6_MULTICAST(r) => visitor.visit_rib_afi(header, r),
+            TABLE_DUMP_V2::RIB_GENERIC(r) => visitor.visit_rib_generic(header, r),
+            TABLE_DUMP_V2::RIB_IPV4_UNICAST_ADDPATH(r)
+            | TABLE_DUMP_V2::RIB_IPV4_MULTICAST_ADDPATH(r)
+            | TABLE_DUMP_V2::RIB_IPV6_UNICAST_ADDPATH(r)
+            | TABLE_DUMP_V2::RIB_IPV6_MULTICAST_ADDPATH(r) => {
+                visitor.visit_rib_afi_addpath(header, r)
+            }
+            TABLE_DUMP_V2::RIB_GENERIC_ADDPATH(r) => visitor.visit_rib_generic_addpath(header, r),
+        },
+        _ => visitor.visit_record(header, record),
+    }
+}
@@ -0,0 +1,209 @@
+use byteorder::{BigEndian, WriteBytesExt};
+use std::convert::TryFrom;
+use std::io::{Error, ErrorKind, Write};
+use std::net::IpAddr;
+
+use crate::{codes, Header, MrtError, Record, AFI};
+
+///
+/// Writes MRT records to an underlying stream.
+///
+/// # Examples
+/// ```
+/// use mrt_rs::{HeaderBuilder, Reader, Writer};
+///
+/// let mut reader = Reader::new(std::fs::File::open("res/bird-mrtdump_bgp").unwrap());
+/// let mut writer = Writer::new(Vec::new());
+///
+/// while let Some((header, record)) = reader.read().unwrap() {
+///     let builder = HeaderBuilder::new(header.record_type, header.sub_type)
+///         .timestamp(header.timestamp);
+///     writer.write(&builder.build(), &record).unwrap();
+/// }
+/// ```
+///
+#[derive(Debug)]
+pub struct Writer<T: Write> {
+    /// The stream to which MRT records are written.
+    pub stream: T,
+}
+
+impl<T: Write> Writer<T> {
+    /// Creates a new Writer that writes MRT records to the given stream.
+    pub fn new(stream: T) -> Writer<T> {
+        Writer { stream }
+    }
+
+    ///
+    /// Writes a single MRT record to the stream. The length of the given header is ignored and
+    /// is instead computed from the serialized record. For record types with an extended timestamp
+    /// the `extended` field of the header is written as well and included in the length.
+    ///
+    /// Returns the header as it was written to the stream.
+    ///
+    /// # Panics
+    /// This function does not panic.
+    ///
+    /// # Errors
+    /// Any IO error will be returned while writing to the stream.
+    /// An error of kind `InvalidInput` is returned if the record cannot be serialized, or if the
+    /// type or subtype of the header does not match the record. The subtype is only compared for
+    /// record types that define subtypes.
+    ///
+    /// # Safety
+    /// This function does not make use of unsafe code.
+    ///
+    pub fn write(&mut self, header: &Header, record: &Record) -> Result<Header, MrtError> {
+        if let Some((record_type, sub_type)) = record.wire_type() {
+            if header.record_type != record_type
+                || (has_sub_types(record_type) && header.sub_type != sub_type)
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Type of the header does not match the record.",
+                )
+                .into());
+            }
+        }
+
+        let mut body = Vec::new();
+        crate::write_record(header, record, &mut body)?;
+
+        let header = Header {
+            timestamp: header.timestamp,
+            extended: header.extended,
+            record_type: header.record_type,
+            sub_type: header.sub_type,
+            length: u32::try_from(body.len())
+                .map_err(|_| Error::new(ErrorKind::InvalidInput, "MRT record is too large."))?,
+        };
+
+        self.stream.write_u32::<BigEndian>(header.timestamp)?;
+        self.stream.write_u16::<BigEndian>(header.record_type)?;
+        self.stream.write_u16::<BigEndian>(header.sub_type)?;
+        self.stream.write_u32::<BigEndian>(header.length)?;
+        self.stream.write_all(&body)?;
+        Ok(header)
+    }
+}
+
+///
+/// Used to construct the `Header` of an MRT record that is about to be written.
+/// The length of the record is computed by the `Writer` once the record has been serialized.
+///
+/// # Examples
+/// ```
+/// use mrt_rs::HeaderBuilder;
+///
+/// let header = HeaderBuilder::new(17, 4).timestamp(1_500_000_000).extended(250_000).build();
+/// assert_eq!(header.extended, 250_000);
+/// ```
+///
+#[derive(Debug)]
+pub struct HeaderBuilder {
+    timestamp: u32,
+    extended: u32,
+    record_type: u16,
+    sub_type: u16,
+}
+
+impl HeaderBuilder {
+    /// Creates a new HeaderBuilder for the given record type and sub-type.
+    pub fn new(record_type: u16, sub_type: u16) -> HeaderBuilder {
+        HeaderBuilder {
+            timestamp: 0,
+            extended: 0,
+            record_type,
+            sub_type,
+        }
+    }
+
+    /// Sets the time at which the record was generated. Represented in UNIX time.
+    pub fn timestamp(mut self, timestamp: u32) -> HeaderBuilder {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Sets the microsecond resolution of the timestamp. Only written for extended timestamp types.
+    pub fn extended(mut self, extended: u32) -> HeaderBuilder {
+        self.extended = extended;
+        self
+    }
+
+    /// Creates the Header. Its length is zero until it has been written by a `Writer`.
+    pub fn build(self) -> Header {
+        Header {
+            timestamp: self.timestamp,
+            extended: self.extended,
+            record_type: self.record_type,
+            sub_type: self.sub_type,
+            length: 0,
+        }
+    }
+}
+
+/// Returns true if the record type distinguishes its records by their subtype.
+fn has_sub_types(record_type: u16) -> bool {
+    matches!(
+        record_type,
+        codes::BGP
+            | codes::BGP4PLUS
+            | codes::BGP4PLUS_01
+            | codes::TABLE_DUMP
+            | codes::TABLE_DUMP_V2
+            | codes::BGP4MP
+            | codes::BGP4MP_ET
+    )
+}
+
+/// Writes an IPv4 or IPv6 address.
+pub(crate) fn write_address(mut stream: impl Write, address: &IpAddr) -> Result<(), Error> {
+    match address {
+        IpAddr::V4(x) => stream.write_u32::<BigEndian>(u32::from(*x)),
+        IpAddr::V6(x) => stream.write_u128::<BigEndian>(u128::from(*x)),
+    }
+}
+
+/// Returns the AFI of two addresses that must belong to the same address family.
+pub(crate) fn afi_of(first: &IpAddr, second: &IpAddr) -> Result<u16, Error> {
+    match (first, second) {
+        (IpAddr::V4(_), IpAddr::V4(_)) => Ok(AFI::IPV4.code()),
+        (IpAddr::V6(_), IpAddr::V6(_)) => Ok(AFI::IPV6.code()),
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Addresses of a record must belong to the same address family.",
+        )),
+    }
+}
+
+/// Writes a buffer preceded by its length as a 2-byte value.
+pub(crate) fn write_u16_prefixed(mut stream: impl Write, bytes: &[u8]) -> Result<(), Error> {
+    let length = u16::try_from(bytes.len())
+        .map_err(|_| Error::new(ErrorKind::InvalidInput, "Field exceeds 65535 bytes."))?;
+    stream.write_u16::<BigEndian>(length)?;
+    stream.write_all(bytes)
+}
+
+/// Writes a 2-byte count of elements.
+pub(crate) fn write_count(mut stream: impl Write, count: usize) -> Result<(), Error> {
+    let count = u16::try_from(count)
+        .map_err(|_| Error::new(ErrorKind::InvalidInput, "Field exceeds 65535 elements."))?;
+    stream.write_u16::<BigEndian>(count)
+}
+
+/// A stream that discards all bytes written to it and only counts them.
+#[derive(Debug, Default)]
+pub(crate) struct ByteCounter {
+    pub(crate) count: u64,
+}
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.count += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
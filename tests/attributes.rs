@@ -0,0 +1,587 @@
+use mrt_rs::attributes::{
+    self, reconstruct_as_path, Administrator, Aggregator, AsPath, ExtendedCommunity,
+    LazyAttributes, PathAttribute, SegmentType, AS_TRANS,
+};
+use mrt_rs::tabledump::TABLE_DUMP_V2;
+use mrt_rs::{AsnSize, ParseOptions, Record};
+use std::fs::File;
+use std::net::Ipv4Addr;
+
+// Tests if all four AS_PATH segment types are parsed.
+#[test]
+fn test_as_path_segment_types() {
+    for (code, segment_type) in [
+        (1, SegmentType::AS_SET),
+        (2, SegmentType::AS_SEQUENCE),
+        (3, SegmentType::AS_CONFED_SEQUENCE),
+        (4, SegmentType::AS_CONFED_SET),
+    ]
+    .iter()
+    {
+        let bytes = [*code, 2, 0, 0, 0xFD, 0xE8, 0, 0, 0x1B, 0x58];
+        let path = AsPath::parse(
+            &bytes[..],
+            bytes.len() as u16,
+            AsnSize::Four,
+            &ParseOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(path.segments.len(), 1);
+        assert_eq!(path.segments[0].segment_type, *segment_type);
+        assert_eq!(path.segments[0].asns, vec![65000, 7000]);
+    }
+}
+
+// Tests if an AS_PATH consisting of multiple 2-byte segments is parsed.
+#[test]
+fn test_as_path_confederation() {
+    let bytes = [3, 1, 0xFD, 0xE8, 2, 2, 0x1B, 0x58, 0, 174];
+    let path = AsPath::parse(
+        &bytes[..],
+        bytes.len() as u16,
+        AsnSize::Two,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(path.segments.len(), 2);
+    assert_eq!(
+        path.segments[0].segment_type,
+        SegmentType::AS_CONFED_SEQUENCE
+    );
+    assert_eq!(path.segments[0].asns, vec![65000]);
+    assert_eq!(path.segments[1].segment_type, SegmentType::AS_SEQUENCE);
+    assert_eq!(path.segments[1].asns, vec![7000, 174]);
+}
+
+// Tests if an unknown segment type results in an error instead of a panic.
+#[test]
+fn test_as_path_unknown_segment_type() {
+    let bytes = [5, 1, 0, 1];
+    assert!(AsPath::parse(
+        &bytes[..],
+        bytes.len() as u16,
+        AsnSize::Two,
+        &ParseOptions::default()
+    )
+    .is_err());
+}
+
+// Tests if a Route Target with a 2-octet ASN administrator is classified.
+#[test]
+fn test_extended_community_two_octet_route_target() {
+    let bytes = [0x00, 0x02, 0xFD, 0xE8, 0x00, 0x00, 0x00, 0x64];
+    let community = ExtendedCommunity::parse(&bytes[..]).unwrap();
+    assert_eq!(
+        community,
+        ExtendedCommunity::RouteTarget {
+            administrator: Administrator::TwoOctetAs(65000),
+            assigned_number: 100,
+        }
+    );
+}
+
+// Tests if a Route Target with an IPv4 address administrator is classified.
+#[test]
+fn test_extended_community_ipv4_route_target() {
+    let bytes = [0x01, 0x02, 192, 0, 2, 1, 0x00, 0x07];
+    let community = ExtendedCommunity::from(bytes);
+    assert_eq!(
+        community,
+        ExtendedCommunity::RouteTarget {
+            administrator: Administrator::Ipv4(Ipv4Addr::new(192, 0, 2, 1)),
+            assigned_number: 7,
+        }
+    );
+}
+
+// Tests if unclassified extended communities retain their raw bytes.
+#[test]
+fn test_extended_community_unknown() {
+    let bytes = [0x03, 0x0C, 0, 0, 0, 0, 0, 8];
+    assert_eq!(
+        ExtendedCommunity::from(bytes),
+        ExtendedCommunity::Unknown(bytes)
+    );
+}
+
+// Tests if the AS_TRANS placeholders in an AS_PATH are substituted by the AS4_PATH.
+#[test]
+fn test_reconstruct_as_path() {
+    // AS_PATH: 100 23456 23456 using 2-byte ASNs.
+    let as_path = [2, 3, 0, 100, 0x5B, 0xA0, 0x5B, 0xA0];
+    let as_path = AsPath::parse(
+        &as_path[..],
+        as_path.len() as u16,
+        AsnSize::Two,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(as_path.asns(), vec![100, AS_TRANS, AS_TRANS]);
+
+    // AS4_PATH: 196608 200000 using 4-byte ASNs.
+    let as4_path = [2, 2, 0, 3, 0, 0, 0, 3, 0x0D, 0x40];
+    let as4_path = AsPath::parse(
+        &as4_path[..],
+        as4_path.len() as u16,
+        AsnSize::Four,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        reconstruct_as_path(&as_path, &as4_path).asns(),
+        vec![100, 196608, 200000]
+    );
+}
+
+// Tests if an AS4_PATH containing more ASNs than the AS_PATH is ignored.
+#[test]
+fn test_reconstruct_as_path_longer_as4_path() {
+    // AS_PATH: 100 23456 using 2-byte ASNs.
+    let as_path = [2, 2, 0, 100, 0x5B, 0xA0];
+    let as_path = AsPath::parse(
+        &as_path[..],
+        as_path.len() as u16,
+        AsnSize::Two,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+
+    // AS4_PATH: 196608 200000 300000 using 4-byte ASNs.
+    let as4_path = [2, 3, 0, 3, 0, 0, 0, 3, 0x0D, 0x40, 0, 4, 0x93, 0xE0];
+    let as4_path = AsPath::parse(
+        &as4_path[..],
+        as4_path.len() as u16,
+        AsnSize::Four,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(reconstruct_as_path(&as_path, &as4_path), as_path);
+
+    // An AS_SET only counts as a single ASN, so {100 200} 23456 is shorter than 196608 200000 300000.
+    let as_path = [1, 2, 0, 100, 0, 200, 2, 1, 0x5B, 0xA0];
+    let as_path = AsPath::parse(
+        &as_path[..],
+        as_path.len() as u16,
+        AsnSize::Two,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(reconstruct_as_path(&as_path, &as4_path), as_path);
+}
+
+// Tests if confederation segments are kept from the AS_PATH, but discarded from the AS4_PATH.
+#[test]
+fn test_reconstruct_as_path_confederation() {
+    // AS_PATH: (65001 65002) 100 23456 using 2-byte ASNs.
+    let as_path = [3, 2, 0xFD, 0xE9, 0xFD, 0xEA, 2, 2, 0, 100, 0x5B, 0xA0];
+    let as_path = AsPath::parse(
+        &as_path[..],
+        as_path.len() as u16,
+        AsnSize::Two,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+
+    // AS4_PATH: (65001) 196608 using 4-byte ASNs. The confederation segment is not counted,
+    // so the AS4_PATH is shorter than the AS_PATH and replaces its last ASN.
+    let as4_path = [3, 1, 0, 0, 0xFD, 0xE9, 2, 1, 0, 3, 0, 0];
+    let as4_path = AsPath::parse(
+        &as4_path[..],
+        as4_path.len() as u16,
+        AsnSize::Four,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+
+    let path = reconstruct_as_path(&as_path, &as4_path);
+    assert_eq!(path.segments.len(), 2);
+    assert_eq!(
+        path.segments[0].segment_type,
+        SegmentType::AS_CONFED_SEQUENCE
+    );
+    assert_eq!(path.segments[0].asns, vec![65001, 65002]);
+    assert_eq!(path.segments[1].segment_type, SegmentType::AS_SEQUENCE);
+    assert_eq!(path.segments[1].asns, vec![100, 196608]);
+}
+
+// Tests if the AGGREGATOR and AS4_AGGREGATOR attributes are parsed using their respective ASN widths.
+#[test]
+fn test_aggregators() {
+    let bytes = [
+        0xC0, 7, 6, 0x5B, 0xA0, 10, 0, 0, 1, // AGGREGATOR 23456 10.0.0.1
+        0xC0, 18, 8, 0, 3, 0, 0, 10, 0, 0, 1, // AS4_AGGREGATOR 196608 10.0.0.1
+    ];
+    let attributes = attributes::parse(&bytes, AsnSize::Two, &ParseOptions::default()).unwrap();
+    assert_eq!(
+        attributes,
+        vec![
+            PathAttribute::AGGREGATOR(Aggregator {
+                asn: AS_TRANS,
+                ip: Ipv4Addr::new(10, 0, 0, 1),
+            }),
+            PathAttribute::AS4_AGGREGATOR(Aggregator {
+                asn: 196608,
+                ip: Ipv4Addr::new(10, 0, 0, 1),
+            }),
+        ]
+    );
+}
+
+// Tests if the path attributes of all RIB entries in the samples can be parsed.
+#[test]
+fn test_rib_attributes() {
+    for name in &["res/quagga_rib", "res/openbgpd_rib_table-v2"] {
+        let mut file = File::open(name).unwrap();
+        while let Some((_, record)) = mrt_rs::read(&mut file).unwrap() {
+            if let Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_IPV4_UNICAST(rib)) = record {
+                for entry in rib.entries {
+                    let attributes = attributes::parse(
+                        &entry.attributes,
+                        AsnSize::Four,
+                        &ParseOptions::default(),
+                    )
+                    .unwrap();
+                    assert!(attributes
+                        .iter()
+                        .any(|x| matches!(x, PathAttribute::AS_PATH(_))));
+                }
+            }
+        }
+    }
+}
+
+// Tests if ATOMIC_AGGREGATE is only accepted with a length of zero.
+#[test]
+fn test_atomic_aggregate_length() {
+    // ATOMIC_AGGREGATE followed by LOCAL_PREF, which must still be parsed.
+    let bytes = [0x40, 6, 0, 0x40, 5, 4, 0, 0, 0, 100];
+    assert_eq!(
+        attributes::parse(&bytes, AsnSize::Four, &ParseOptions::default()).unwrap(),
+        vec![
+            PathAttribute::ATOMIC_AGGREGATE,
+            PathAttribute::LOCAL_PREF(100)
+        ]
+    );
+
+    let bytes = [0x40, 6, 2, 0, 0];
+    let error = attributes::parse(&bytes, AsnSize::Four, &ParseOptions::default()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+// Tests if an attribute using the extended length bit with a length above 255 is parsed.
+#[test]
+fn test_extended_length_attribute() {
+    let communities: Vec<u32> = (0..70).map(|i| (65000 << 16) | i).collect();
+    let mut bytes = vec![0xD0, 8, 1, 24];
+    for community in &communities {
+        bytes.extend(&community.to_be_bytes());
+    }
+    bytes.extend(&[0x40, 5, 4, 0, 0, 0, 100]);
+
+    assert_eq!(
+        attributes::parse(&bytes, AsnSize::Four, &ParseOptions::default()).unwrap(),
+        vec![
+            PathAttribute::COMMUNITY(communities),
+            PathAttribute::LOCAL_PREF(100)
+        ]
+    );
+}
+
+// Tests if an attribute length exceeding the length of all path attributes is rejected.
+#[test]
+fn test_attribute_length_exceeds_total() {
+    // A LOCAL_PREF declaring 8 bytes of which only 4 are present.
+    let error = attributes::parse(
+        &[0x40, 5, 8, 0, 0, 0, 100],
+        AsnSize::Four,
+        &ParseOptions::default(),
+    )
+    .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    // An extended length attribute of which the length itself is truncated.
+    let error =
+        attributes::parse(&[0x50, 8, 1], AsnSize::Four, &ParseOptions::default()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+// Tests if an AS_PATH is formatted using the notation of bgpdump for all segment types.
+#[test]
+fn test_as_path_display() {
+    let bytes = [
+        3, 2, 0xFD, 0xE9, 0xFD, 0xEA, // AS_CONFED_SEQUENCE (65001 65002)
+        4, 1, 0xFD, 0xEB, // AS_CONFED_SET [65003]
+        2, 2, 0, 100, 0, 200, // AS_SEQUENCE 100 200
+        1, 2, 0, 1, 0, 2, // AS_SET {1,2}
+    ];
+    let path = AsPath::parse(
+        &bytes[..],
+        bytes.len() as u16,
+        AsnSize::Two,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(path.to_string(), "(65001 65002) [65003] 100 200 {1,2}");
+}
+
+// Tests if the origin and length of an AS_PATH skip confederation segments and count an AS_SET once.
+#[test]
+fn test_as_path_origin_and_length() {
+    let bytes = [
+        3, 2, 0xFD, 0xE9, 0xFD, 0xEA, // AS_CONFED_SEQUENCE (65001 65002)
+        2, 3, 0, 100, 0, 200, 1, 44, // AS_SEQUENCE 100 200 300
+        1, 2, 0, 1, 0, 2, // AS_SET {1,2}
+    ];
+    let path = AsPath::parse(
+        &bytes[..],
+        bytes.len() as u16,
+        AsnSize::Two,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(path.origin(), Some(300));
+    assert_eq!(path.len(), 4);
+    assert!(!path.is_empty());
+
+    // A path consisting of only an AS_SET has no origin.
+    let bytes = [1, 2, 0, 1, 0, 2];
+    let path = AsPath::parse(
+        &bytes[..],
+        bytes.len() as u16,
+        AsnSize::Two,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(path.origin(), None);
+    assert_eq!(path.len(), 1);
+
+    // A path consisting of only confederation segments is empty.
+    let bytes = [3, 1, 0xFD, 0xE9];
+    let path = AsPath::parse(
+        &bytes[..],
+        bytes.len() as u16,
+        AsnSize::Two,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(path.origin(), None);
+    assert!(path.is_empty());
+}
+
+// Tests if MULTI_EXIT_DISC and LOCAL_PREF must be 4 bytes in strict mode and are zero-extended otherwise.
+#[test]
+fn test_u32_attribute_length() {
+    let strict = mrt_rs::ParseOptions::default();
+    let lenient = mrt_rs::ParseOptions {
+        strict: false,
+        ..Default::default()
+    };
+
+    let bytes = [0x80, 4, 4, 0, 0, 0, 10, 0x40, 5, 4, 0, 0, 0, 100];
+    for options in [&strict, &lenient].iter() {
+        assert_eq!(
+            attributes::parse(&bytes, AsnSize::Four, options).unwrap(),
+            vec![
+                PathAttribute::MULTI_EXIT_DISC(10),
+                PathAttribute::LOCAL_PREF(100)
+            ]
+        );
+    }
+
+    // A 2-byte MULTI_EXIT_DISC followed by a 2-byte LOCAL_PREF.
+    let bytes = [0x80, 4, 2, 0, 10, 0x40, 5, 2, 0, 100];
+    let error = attributes::parse(&bytes, AsnSize::Four, &ParseOptions::default()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    let error = attributes::parse(&bytes, AsnSize::Four, &strict).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(
+        attributes::parse(&bytes, AsnSize::Four, &lenient).unwrap(),
+        vec![
+            PathAttribute::MULTI_EXIT_DISC(10),
+            PathAttribute::LOCAL_PREF(100)
+        ]
+    );
+}
+
+// Tests if a single path attribute is parsed from a buffer and random bytes never cause a panic.
+#[test]
+fn test_path_attribute_from_bytes() {
+    let bytes = [0x40, 5, 4, 0, 0, 0, 100];
+    assert_eq!(
+        PathAttribute::from_bytes(&bytes, AsnSize::Four).unwrap(),
+        PathAttribute::LOCAL_PREF(100)
+    );
+
+    let bytes = [0x40, 5, 4, 0, 0, 0, 100, 0];
+    let error = PathAttribute::from_bytes(&bytes, AsnSize::Four).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    // A xorshift generator keeps the inputs reproducible without additional dependencies.
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for _ in 0..20_000 {
+        let length = (next() % 64) as usize;
+        let mut bytes: Vec<u8> = (0..length).map(|_| next() as u8).collect();
+
+        // Make the attribute length consistent with the buffer for a part of the inputs, such
+        // that the values of the attributes are parsed as well.
+        if length >= 3 && next() % 2 == 0 {
+            bytes[0] &= !0x10;
+            bytes[2] = (length - 3) as u8;
+        }
+
+        let _ = PathAttribute::from_bytes(&bytes, AsnSize::Two);
+        let _ = PathAttribute::from_bytes(&bytes, AsnSize::Four);
+    }
+}
+
+// Tests if a NEXT_HOP of 4 bytes is parsed as IPv4, one of 16 bytes as IPv6 and others are rejected.
+#[test]
+fn test_next_hop_length() {
+    let bytes = [0x40, 3, 4, 192, 0, 2, 1];
+    assert_eq!(
+        PathAttribute::from_bytes(&bytes, AsnSize::Four).unwrap(),
+        PathAttribute::NEXT_HOP("192.0.2.1".parse().unwrap())
+    );
+
+    let mut bytes = vec![0x40, 3, 16, 0x20, 0x01, 0x0D, 0xB8];
+    bytes.extend(&[0; 11]);
+    bytes.push(1);
+    assert_eq!(
+        PathAttribute::from_bytes(&bytes, AsnSize::Four).unwrap(),
+        PathAttribute::NEXT_HOP("2001:db8::1".parse().unwrap())
+    );
+
+    let bytes = [0x40, 3, 6, 192, 0, 2, 1, 0, 0];
+    let error = PathAttribute::from_bytes(&bytes, AsnSize::Four).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+// Tests if AS_PATHs claiming more ASNs than they contain or than the configured limit are rejected.
+#[test]
+fn test_as_path_length_limit() {
+    // 40 segments of 255 ASNs each claim 10200 ASNs in an attribute of only 80 bytes.
+    let bytes: Vec<u8> = (0..40).flat_map(|_| vec![2, 255]).collect();
+    let error = AsPath::parse(
+        &bytes[..],
+        bytes.len() as u16,
+        AsnSize::Four,
+        &ParseOptions::default(),
+    )
+    .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    // 10 full segments of 255 ASNs contain 2550 ASNs, which exceeds the default limit.
+    let mut bytes = Vec::new();
+    for _ in 0..10 {
+        bytes.extend(&[2, 255]);
+        bytes.extend((0..255u32).flat_map(|x| x.to_be_bytes().to_vec()));
+    }
+    let error = AsPath::parse(
+        &bytes[..],
+        bytes.len() as u16,
+        AsnSize::Four,
+        &ParseOptions::default(),
+    )
+    .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    let options = mrt_rs::ParseOptions {
+        max_as_path_length: 4096,
+        ..Default::default()
+    };
+    let path = AsPath::parse(&bytes[..], bytes.len() as u16, AsnSize::Four, &options).unwrap();
+    assert_eq!(path.len(), 2550);
+}
+
+// Tests if consecutive duplicate ASNs are counted and collapsed across segment boundaries.
+#[test]
+fn test_as_path_prepends() {
+    use mrt_rs::attributes::Segment;
+
+    let path = AsPath {
+        segments: vec![
+            Segment {
+                segment_type: SegmentType::AS_SEQUENCE,
+                asns: vec![7018, 7018, 7018, 174],
+            },
+            Segment {
+                segment_type: SegmentType::AS_SEQUENCE,
+                asns: vec![174, 3356],
+            },
+        ],
+    };
+    assert_eq!(path.prepend_counts(), vec![(7018, 3), (174, 2), (3356, 1)]);
+    assert_eq!(path.dedup_prepends(), vec![7018, 174, 3356]);
+
+    // A path without prepends is returned unmodified.
+    let path = AsPath {
+        segments: vec![Segment {
+            segment_type: SegmentType::AS_SEQUENCE,
+            asns: vec![3356, 174, 7018],
+        }],
+    };
+    assert_eq!(path.prepend_counts(), vec![(3356, 1), (174, 1), (7018, 1)]);
+    assert_eq!(path.dedup_prepends(), path.asns());
+
+    let path = AsPath { segments: vec![] };
+    assert!(path.prepend_counts().is_empty());
+    assert!(path.dedup_prepends().is_empty());
+}
+
+// Tests if lazily decoded path attributes agree with eagerly decoded path attributes.
+#[test]
+fn test_lazy_attributes() {
+    for fixture in &[
+        "res/quagga_rib",
+        "res/openbgpd_rib_table-v2",
+        "res/bird-mrtdump_rib",
+    ] {
+        let mut reader = mrt_rs::Reader::new(File::open(fixture).unwrap());
+        for route in reader.routes() {
+            let bytes = route.unwrap().attributes;
+            let eager = attributes::parse(&bytes, AsnSize::Four, &ParseOptions::default()).unwrap();
+            let lazy = LazyAttributes::new(bytes, AsnSize::Four);
+            for type_code in 0..=255 {
+                let expected = eager.iter().find(|x| type_code_of(x) == type_code);
+                assert_eq!(lazy.get(type_code).unwrap().as_ref(), expected);
+            }
+        }
+    }
+
+    // Only the attributes preceding the requested attribute are scanned.
+    let lazy = LazyAttributes::new(vec![0x40, 1, 1, 0, 0x40, 5, 2, 0], AsnSize::Four);
+    assert!(matches!(
+        lazy.get(1).unwrap(),
+        Some(PathAttribute::ORIGIN(_))
+    ));
+    assert!(lazy.get(5).is_err());
+}
+
+/// Returns the type code of a decoded path attribute.
+fn type_code_of(attribute: &PathAttribute) -> u8 {
+    match attribute {
+        PathAttribute::ORIGIN(_) => 1,
+        PathAttribute::AS_PATH(_) => 2,
+        PathAttribute::NEXT_HOP(_) => 3,
+        PathAttribute::MULTI_EXIT_DISC(_) => 4,
+        PathAttribute::LOCAL_PREF(_) => 5,
+        PathAttribute::ATOMIC_AGGREGATE => 6,
+        PathAttribute::AGGREGATOR(_) => 7,
+        PathAttribute::COMMUNITY(_) => 8,
+        PathAttribute::ORIGINATOR_ID(_) => 9,
+        PathAttribute::CLUSTER_LIST(_) => 10,
+        PathAttribute::MP_REACH_NLRI(_) => 14,
+        PathAttribute::MP_UNREACH_NLRI(_) => 15,
+        PathAttribute::EXTENDED_COMMUNITIES(_) => 16,
+        PathAttribute::AS4_PATH(_) => 17,
+        PathAttribute::AS4_AGGREGATOR(_) => 18,
+        PathAttribute::UNKNOWN(x, _) => *x,
+    }
+}
@@ -0,0 +1,365 @@
+use mrt_rs::attributes::{Origin, PathAttribute};
+use mrt_rs::bgp;
+use mrt_rs::bgp4mp::BGP4MP;
+use mrt_rs::{AsnSize, ParseOptions, Reader, Record, RouteEvent, AFI, SAFI};
+use std::fs::File;
+
+/// Creates a BGP message of the given type by prepending the BGP header to the body.
+fn pdu(message_type: u8, body: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0xFF; 16];
+    bytes.extend(&((19 + body.len()) as u16).to_be_bytes());
+    bytes.push(message_type);
+    bytes.extend(body);
+    bytes
+}
+
+// Tests if an UPDATE that only withdraws routes is parsed.
+#[test]
+fn test_update_withdrawal() {
+    // Withdraws 10.0.0.0/8 and 192.168.1.0/24 without any path attributes or NLRI.
+    let body = [0, 6, 8, 10, 24, 192, 168, 1, 0, 0];
+    let update =
+        bgp::parse_update(&pdu(2, &body), AsnSize::Four, &ParseOptions::default()).unwrap();
+
+    assert_eq!(
+        update.withdrawn_routes,
+        vec![(8, vec![10]), (24, vec![192, 168, 1])]
+    );
+    assert!(update.attributes.is_empty());
+    assert!(update.nlri.is_empty());
+}
+
+// Tests if an UPDATE without withdrawn routes, path attributes and NLRI is parsed.
+#[test]
+fn test_update_end_of_rib() {
+    let update = bgp::parse_update(
+        &pdu(2, &[0, 0, 0, 0]),
+        AsnSize::Four,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert!(update.is_empty());
+    assert!(update.attributes.is_empty());
+}
+
+// Tests if an UPDATE announcing routes keeps the withdrawn routes separate from the NLRI.
+#[test]
+fn test_update_announcement() {
+    // Withdraws 10.0.0.0/8 and announces 172.16.0.0/12 with ORIGIN IGP.
+    let body = [0, 2, 8, 10, 0, 4, 0x40, 1, 1, 0, 12, 172, 16];
+    let update =
+        bgp::parse_update(&pdu(2, &body), AsnSize::Four, &ParseOptions::default()).unwrap();
+
+    assert_eq!(update.withdrawn_routes, vec![(8, vec![10])]);
+    assert_eq!(update.attributes, vec![PathAttribute::ORIGIN(Origin::IGP)]);
+    assert_eq!(update.nlri, vec![(12, vec![172, 16])]);
+}
+
+// Tests if ill-formatted UPDATE messages are rejected.
+#[test]
+fn test_update_invalid() {
+    // Not an UPDATE message but a KEEPALIVE.
+    assert!(bgp::parse_update(&pdu(4, &[]), AsnSize::Four, &ParseOptions::default()).is_err());
+
+    // The withdrawn routes length exceeds the message.
+    assert!(bgp::parse_update(
+        &pdu(2, &[0, 9, 8, 10, 0, 0]),
+        AsnSize::Four,
+        &ParseOptions::default()
+    )
+    .is_err());
+
+    // A prefix length that requires more bytes than available.
+    assert!(bgp::parse_update(
+        &pdu(2, &[0, 2, 24, 10, 0, 0]),
+        AsnSize::Four,
+        &ParseOptions::default()
+    )
+    .is_err());
+}
+
+// Tests if the UPDATE messages of captured BGP4MP_MESSAGE_AS4 records can be parsed.
+#[test]
+fn test_update_samples() {
+    let mut updates = 0;
+    // The BIRD captures contain ADD-PATH identifiers in their NLRI and are therefore not included.
+    for file in &["res/quagga_bgp", "res/openbgpd_bgp"] {
+        let mut reader = Reader::new(File::open(file).unwrap());
+        while let Some((_, record)) = reader.read().unwrap() {
+            if let Record::BGP4MP(BGP4MP::MESSAGE_AS4(x)) = record {
+                if x.message[18] == 2 {
+                    bgp::parse_update(&x.message, AsnSize::Four, &ParseOptions::default()).unwrap();
+                    updates += 1;
+                }
+            }
+        }
+    }
+    assert!(updates > 0);
+}
+
+/// Creates an OPEN message from AS 65000 advertising the given capabilities.
+fn open_message(capabilities: &[u8]) -> Vec<u8> {
+    let mut body = vec![4, 0xFD, 0xE8, 0, 180, 10, 0, 0, 1];
+    body.push(capabilities.len() as u8 + 2);
+    body.extend(&[2, capabilities.len() as u8]);
+    body.extend(capabilities);
+    pdu(1, &body)
+}
+
+// Tests if the Graceful Restart capability is decoded.
+#[test]
+fn test_open_graceful_restart() {
+    // Restart flag set, a restart time of 120 seconds and IPv4 and IPv6 unicast families.
+    let message = open_message(&[64, 10, 0x80, 120, 0, 1, 1, 0x80, 0, 2, 1, 0]);
+    let open = bgp::parse_open(&message).unwrap();
+
+    assert_eq!(open.version, 4);
+    assert_eq!(open.my_as, 65000);
+    assert_eq!(open.hold_time, 180);
+    assert_eq!(
+        open.capabilities,
+        vec![bgp::Capability::GracefulRestart(bgp::GracefulRestart {
+            restart_flags: 0x8,
+            restart_time: 120,
+            families: vec![
+                bgp::GracefulRestartFamily {
+                    afi: 1,
+                    safi: 1,
+                    forwarding_preserved: true,
+                },
+                bgp::GracefulRestartFamily {
+                    afi: 2,
+                    safi: 1,
+                    forwarding_preserved: false,
+                },
+            ],
+        })]
+    );
+}
+
+// Tests if the ADD-PATH capability is decoded together with other capabilities.
+#[test]
+fn test_open_add_path() {
+    let message = open_message(&[
+        1, 4, 0, 1, 0, 1, // Multiprotocol IPv4 unicast
+        65, 4, 0, 3, 0x0D, 0x40, // 4-octet AS 200000
+        69, 8, 0, 1, 1, 3, 0, 2, 1, 1, // ADD-PATH IPv4 send/receive, IPv6 receive
+    ]);
+    let open = bgp::parse_open(&message).unwrap();
+
+    assert_eq!(open.asn(), 200_000);
+    assert_eq!(open.asn_size(), AsnSize::Four);
+    assert_eq!(
+        open.capabilities,
+        vec![
+            bgp::Capability::Multiprotocol { afi: 1, safi: 1 },
+            bgp::Capability::FourOctetAs(200_000),
+            bgp::Capability::AddPath(vec![
+                bgp::AddPathFamily {
+                    afi: 1,
+                    safi: 1,
+                    direction: bgp::AddPathDirection::Both,
+                },
+                bgp::AddPathFamily {
+                    afi: 2,
+                    safi: 1,
+                    direction: bgp::AddPathDirection::Receive,
+                },
+            ]),
+        ]
+    );
+
+    // An invalid Send/Receive value is rejected.
+    assert!(bgp::parse_open(&open_message(&[69, 4, 0, 1, 1, 4])).is_err());
+}
+
+// Tests if the OPEN messages of captured BGP4MP records can be parsed.
+#[test]
+fn test_open_samples() {
+    let mut opens = 0;
+    for file in &["res/quagga_bgp", "res/bird_bgp", "res/openbgpd_bgp"] {
+        let mut reader = Reader::new(File::open(file).unwrap());
+        while let Some((_, record)) = reader.read().unwrap() {
+            if let Record::BGP4MP(BGP4MP::MESSAGE_AS4(x)) = record {
+                if x.message[18] == 1 {
+                    assert_eq!(bgp::parse_open(&x.message).unwrap().version, 4);
+                    opens += 1;
+                }
+            }
+        }
+    }
+    assert!(opens > 0);
+}
+
+// Tests if normal and Enhanced Route Refresh messages are decoded.
+#[test]
+fn test_route_refresh() {
+    let refresh = bgp::parse_route_refresh(&pdu(5, &[0, 1, 0, 1])).unwrap();
+    assert_eq!(refresh.afi, AFI::IPV4);
+    assert_eq!(refresh.safi, SAFI::UNICAST);
+    assert_eq!(refresh.subtype, 0);
+
+    // A Beginning-of-RIB-Route-Refresh for IPv6 unicast.
+    let refresh = bgp::parse_route_refresh(&pdu(5, &[0, 2, 1, 1])).unwrap();
+    assert_eq!(refresh.afi, AFI::IPV6);
+    assert_eq!(refresh.safi, SAFI::UNICAST);
+    assert_eq!(refresh.subtype, 1);
+
+    // Messages of other types, truncated messages and unknown address families are rejected.
+    assert!(bgp::parse_route_refresh(&pdu(4, &[])).is_err());
+    assert!(bgp::parse_route_refresh(&pdu(5, &[0, 1, 0])).is_err());
+    assert!(bgp::parse_route_refresh(&pdu(5, &[0, 3, 0, 1])).is_err());
+}
+
+// Tests if the BGP UPDATE wrapped by a BMP Route Monitoring message is extracted.
+#[test]
+fn test_bmp_route_monitoring() {
+    // Announces 10.0.0.0/8 with ORIGIN IGP.
+    let update = pdu(2, &[0, 0, 0, 4, 0x40, 1, 1, 0, 8, 10]);
+
+    let mut per_peer = vec![0, 0];
+    per_peer.extend(&[0; 8]); // Peer Distinguisher
+    per_peer.extend(&[0; 12]);
+    per_peer.extend(&[192, 0, 2, 1]); // Peer Address
+    per_peer.extend(&65000u32.to_be_bytes()); // Peer AS
+    per_peer.extend(&[192, 0, 2, 1]); // Peer BGP ID
+    per_peer.extend(&[0; 8]); // Timestamp
+
+    let mut message = vec![3];
+    message.extend(&((6 + per_peer.len() + update.len()) as u32).to_be_bytes());
+    message.push(0);
+    message.extend(&per_peer);
+    message.extend(&update);
+
+    let extracted = mrt_rs::bmp::extract_bgp(&message).unwrap();
+    assert_eq!(extracted, update);
+
+    let update = bgp::parse_update(&extracted, AsnSize::Four, &ParseOptions::default()).unwrap();
+    assert_eq!(update.nlri, vec![(8, vec![10])]);
+    assert_eq!(update.attributes, vec![PathAttribute::ORIGIN(Origin::IGP)]);
+
+    // Messages that are truncated or are not Route Monitoring messages are rejected.
+    assert!(mrt_rs::bmp::extract_bgp(&message[..message.len() - 1]).is_err());
+    message[5] = 4;
+    assert!(mrt_rs::bmp::extract_bgp(&message).is_err());
+}
+
+// Tests if the BGP message header is validated and its length and type are extracted.
+#[test]
+fn test_message_header() {
+    let keepalive = pdu(4, &[]);
+    let header = bgp::MessageHeader::parse(&keepalive).unwrap();
+    assert_eq!(header.length, 19);
+    assert_eq!(header.message_type, 4);
+    assert_eq!(header.body_offset, 19);
+
+    let update = pdu(2, &[0, 0, 0, 0]);
+    let header = bgp::MessageHeader::parse(&update).unwrap();
+    assert_eq!((header.length, header.message_type), (23, 2));
+
+    // A corrupt marker, a length that differs from the message and a truncated header.
+    let mut corrupt = keepalive.clone();
+    corrupt[3] = 0xFE;
+    assert!(bgp::MessageHeader::parse(&corrupt).is_err());
+    assert!(bgp::parse_update(&corrupt, AsnSize::Four, &ParseOptions::default()).is_err());
+    assert!(bgp::MessageHeader::parse(&update[..22]).is_err());
+    assert!(bgp::MessageHeader::parse(&keepalive[..18]).is_err());
+}
+
+// Tests if the BGP4MP records written by BIRD are parsed, including its encoding quirks: the
+// interface index is always 0, the addresses of a session that is being established are
+// 0.0.0.0, OPEN messages are written using the 2-byte ASN subtype and all other messages of an
+// ADD-PATH session using MESSAGE_AS4_ADDPATH.
+#[test]
+fn test_bird_quirks() {
+    let mut reader = Reader::new(File::open("res/bird-mrtdump_bgp").unwrap());
+    let records = reader.read_all().unwrap();
+    assert_eq!(records.len(), 27);
+    assert!(records.iter().all(|(x, _)| x.record_type == 16));
+
+    match &records[0].1 {
+        Record::BGP4MP(BGP4MP::STATE_CHANGE_AS4(x)) => {
+            assert_eq!((x.peer_as, x.local_as, x.interface), (65000, 65000, 0));
+            assert_eq!(x.peer_address.to_string(), "0.0.0.0");
+            assert_eq!(x.local_address.to_string(), "0.0.0.0");
+            assert_eq!((x.old_state, x.new_state), (1, 3));
+        }
+        x => panic!("Unexpected record: {:?}", x),
+    }
+
+    match &records[3].1 {
+        Record::BGP4MP(BGP4MP::MESSAGE(x)) => {
+            assert_eq!((x.peer_as, x.local_as, x.interface), (65000, 65000, 0));
+            assert_eq!(x.peer_address.to_string(), "192.168.0.10");
+            assert_eq!(x.local_address.to_string(), "192.168.0.17");
+            let header = bgp::MessageHeader::parse(&x.message).unwrap();
+            assert_eq!((header.length, header.message_type), (131, 1));
+            let open = bgp::parse_open(&x.message).unwrap();
+            assert_eq!((open.asn(), open.hold_time), (65000, 90));
+            assert_eq!(open.bgp_identifier.to_string(), "172.16.0.10");
+        }
+        x => panic!("Unexpected record: {:?}", x),
+    }
+
+    let mut nlri = Vec::new();
+    for (_, record) in &records {
+        if let Record::BGP4MP(BGP4MP::MESSAGE_AS4_ADDPATH(x)) = record {
+            assert_eq!(x.interface, 0);
+            let header = bgp::MessageHeader::parse(&x.message).unwrap();
+            if header.message_type == 2 {
+                nlri.extend(
+                    bgp::parse_update_add_path(&x.message, AsnSize::Four, &ParseOptions::default())
+                        .unwrap()
+                        .nlri,
+                );
+            }
+        }
+    }
+    assert_eq!(nlri.len(), 12);
+    assert_eq!(nlri[0], (Some(2), 24, vec![172, 17, 0]));
+    assert_eq!(nlri[5], (Some(1), 24, vec![172, 17, 2]));
+}
+
+// Tests if the UPDATE messages of BGP4MP records are flattened into route events, including the
+// prefixes of the MP_REACH_NLRI attribute and the path identifiers of ADD-PATH messages.
+#[test]
+fn test_route_events() {
+    let mut reader = Reader::new(File::open("res/bird-mrtdump_bgp").unwrap());
+    let events: Vec<RouteEvent> = reader.route_events().map(|x| x.unwrap()).collect();
+    assert_eq!(events.len(), 12);
+    assert!(events.iter().all(|x| matches!(
+        x,
+        RouteEvent::Announce {
+            path_id: Some(_),
+            ..
+        }
+    )));
+    assert_eq!(events[0].prefix(), ("172.17.0.0".parse().unwrap(), 24));
+    assert_eq!(
+        events[0].peer().peer_ip,
+        "192.168.0.10".parse::<std::net::IpAddr>().unwrap()
+    );
+    assert_eq!(events[0].peer().peer_as, 65000);
+    match &events[0] {
+        RouteEvent::Announce { attributes, .. } => assert!(attributes
+            .iter()
+            .any(|x| matches!(x, PathAttribute::AS_PATH(_)))),
+        x => panic!("Unexpected event {:?}", x),
+    }
+
+    let mut reader = Reader::new(File::open("res/bird6-mrtdump_bgp").unwrap());
+    let events: Vec<RouteEvent> = reader.route_events().map(|x| x.unwrap()).collect();
+    assert_eq!(events.len(), 12);
+    assert_eq!(events[0].prefix(), ("fd01:1::".parse().unwrap(), 64));
+
+    let mut reader = Reader::new(File::open("res/openbgpd_bgp").unwrap());
+    let mut announced = 0;
+    let mut withdrawn = 0;
+    for event in reader.route_events() {
+        match event.unwrap() {
+            RouteEvent::Announce { .. } => announced += 1,
+            RouteEvent::Withdraw { .. } => withdrawn += 1,
+        }
+    }
+    assert_eq!((announced, withdrawn), (93, 0));
+}
@@ -0,0 +1,112 @@
+use std::fs;
+
+// A xorshift generator, such that every run feeds the same inputs to the parser.
+fn next(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+// Reads all records of the given bytes, which must not panic regardless of their contents.
+fn read_all(bytes: &[u8]) {
+    let mut reader = mrt_rs::Reader::new(bytes);
+    while let Ok(Some(_)) = reader.read() {}
+
+    let mut stream = bytes;
+    while let Ok(Some(_)) = mrt_rs::read(&mut stream) {}
+
+    let mut framed = mrt_rs::FramedReader::new();
+    framed.push(bytes);
+    while let Ok(Some(_)) = framed.next_record() {}
+}
+
+// Tests if records of every type with a random length and random body are rejected without
+// panicking.
+#[test]
+fn test_fuzz_random_records() {
+    let record_types = [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 16, 17, 32, 33, 48, 49,
+    ];
+    let mut state = 0x2545_F491_4F6C_DD1D;
+    for _ in 0..50_000 {
+        let record_type = record_types[(next(&mut state) % 20) as usize];
+        let sub_type = (next(&mut state) % 14) as u16;
+        let length = (next(&mut state) % 80) as u32;
+
+        let mut bytes = vec![0; 4];
+        bytes.extend_from_slice(&u16::to_be_bytes(record_type));
+        bytes.extend_from_slice(&sub_type.to_be_bytes());
+        bytes.extend_from_slice(&length.to_be_bytes());
+        for _ in 0..length {
+            // Zeroes are more likely to produce small lengths and counts.
+            let byte = next(&mut state);
+            bytes.push(if byte & 3 == 0 { 0 } else { byte as u8 });
+        }
+        read_all(&bytes);
+    }
+}
+
+// Tests if captured streams of which random bytes have been replaced or which have been cut off
+// are read without panicking.
+#[test]
+fn test_fuzz_mutated_samples() {
+    let samples: Vec<Vec<u8>> = fs::read_dir("res")
+        .unwrap()
+        .map(|entry| fs::read(entry.unwrap().path()).unwrap())
+        .collect();
+    let mut state = 0x9E37_79B9_7F4A_7C15;
+    for i in 0..5_000 {
+        let mut bytes = samples[i % samples.len()].clone();
+        for _ in 0..1 + next(&mut state) % 8 {
+            let position = next(&mut state) as usize % bytes.len();
+            bytes[position] = next(&mut state) as u8;
+        }
+        let length = bytes.len() / 2 + next(&mut state) as usize % (bytes.len() / 2 + 1);
+        bytes.truncate(length);
+        read_all(&bytes);
+    }
+}
+
+// Tests if random BGP messages and path attributes are rejected without panicking.
+#[test]
+fn test_fuzz_bgp_messages() {
+    let mut state = 0xD1B5_4A32_D192_ED03;
+    let lenient = mrt_rs::ParseOptions::default().strict(false);
+    for _ in 0..50_000 {
+        let length = (next(&mut state) % 64) as usize;
+        let body: Vec<u8> = (0..length)
+            .map(|_| match next(&mut state) {
+                x if x & 3 == 0 => 0,
+                x => x as u8,
+            })
+            .collect();
+        let mut message = vec![0xFF; 16];
+        message.extend_from_slice(&((19 + length) as u16).to_be_bytes());
+        message.push((next(&mut state) % 6) as u8);
+        message.extend_from_slice(&body);
+
+        let add_path = next(&mut state) & 1 == 0;
+        for &asn_size in &[mrt_rs::AsnSize::Two, mrt_rs::AsnSize::Four] {
+            let _ = mrt_rs::bgp::parse_update(&message, asn_size, &mrt_rs::ParseOptions::default());
+            let _ = mrt_rs::bgp::parse_update_add_path(
+                &message,
+                asn_size,
+                &mrt_rs::ParseOptions::default(),
+            );
+            let _ = mrt_rs::attributes::parse(&body, asn_size, &mrt_rs::ParseOptions::default());
+        }
+        let _ = mrt_rs::bgp::parse_open(&message);
+        let _ = mrt_rs::bgp::parse_route_refresh(&message);
+        let _ = mrt_rs::bmp::extract_bgp(&body);
+        let _ = mrt_rs::attributes::MpReachNlri::parse(
+            &body,
+            add_path,
+            &mrt_rs::ParseOptions::default(),
+        );
+        for options in &[lenient.clone(), mrt_rs::ParseOptions::default()] {
+            let _ = mrt_rs::nlri::parse_prefixes(&body, mrt_rs::AFI::IPV4, add_path, options);
+            let _ = mrt_rs::nlri::parse_prefixes(&body, mrt_rs::AFI::IPV6, add_path, options);
+        }
+    }
+}
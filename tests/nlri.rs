@@ -0,0 +1,84 @@
+use mrt_rs::nlri::parse_prefixes;
+use mrt_rs::{MrtError, ParseOptions, AFI};
+use std::io::ErrorKind;
+
+/// Returns the kind of the IO error contained in the given error.
+fn kind(error: MrtError) -> ErrorKind {
+    match error {
+        MrtError::Io(e) => e.kind(),
+        e => panic!("Unexpected error: {}", e),
+    }
+}
+
+// Tests if a sequence of IPv4 prefixes, including a default route, is parsed.
+#[test]
+fn test_parse_ipv4_prefixes() {
+    let options = ParseOptions::default();
+    let bytes = [24, 192, 0, 2, 0, 32, 10, 0, 0, 1, 17, 172, 16, 128];
+    let prefixes = parse_prefixes(&bytes, AFI::IPV4, false, &options).unwrap();
+    assert_eq!(
+        prefixes,
+        vec![
+            (None, 24, vec![192, 0, 2]),
+            (None, 0, vec![]),
+            (None, 32, vec![10, 0, 0, 1]),
+            (None, 17, vec![172, 16, 128]),
+        ]
+    );
+
+    assert!(parse_prefixes(&[], AFI::IPV4, false, &options)
+        .unwrap()
+        .is_empty());
+}
+
+// Tests if a sequence of IPv6 prefixes is parsed and oversized prefix lengths are rejected.
+#[test]
+fn test_parse_ipv6_prefixes() {
+    let options = ParseOptions::default();
+    let mut bytes = vec![32, 0x20, 0x01, 0x0d, 0xb8, 128];
+    bytes.extend_from_slice(&[0xfe; 16]);
+    let prefixes = parse_prefixes(&bytes, AFI::IPV6, false, &options).unwrap();
+    assert_eq!(
+        prefixes,
+        vec![
+            (None, 32, vec![0x20, 0x01, 0x0d, 0xb8]),
+            (None, 128, vec![0xfe; 16])
+        ]
+    );
+
+    // A length of 128 bits is only valid for IPv6.
+    let error = parse_prefixes(&bytes[5..], AFI::IPV4, false, &options).unwrap_err();
+    assert_eq!(kind(error), ErrorKind::InvalidData);
+}
+
+// Tests if prefixes preceded by ADD-PATH path identifiers are parsed.
+#[test]
+fn test_parse_add_path_prefixes() {
+    let options = ParseOptions::default();
+    let bytes = [0, 0, 0, 1, 24, 192, 0, 2, 0, 0, 1, 0, 8, 10];
+    let prefixes = parse_prefixes(&bytes, AFI::IPV4, true, &options).unwrap();
+    assert_eq!(
+        prefixes,
+        vec![(Some(1), 24, vec![192, 0, 2]), (Some(256), 8, vec![10])]
+    );
+
+    // Parsed without ADD-PATH the path identifier is interpreted as a prefix length.
+    assert!(parse_prefixes(&bytes, AFI::IPV4, false, &options).is_err());
+
+    // A truncated prefix is reported as such.
+    let error = parse_prefixes(&bytes[..7], AFI::IPV4, true, &options).unwrap_err();
+    assert_eq!(kind(error), ErrorKind::UnexpectedEof);
+}
+
+// Tests if oversized prefix lengths are only rejected when parsing strictly.
+#[test]
+fn test_parse_prefixes_lenient() {
+    let bytes = [33, 10, 0, 0, 1, 0];
+    let options = ParseOptions::default();
+    let error = parse_prefixes(&bytes, AFI::IPV4, false, &options).unwrap_err();
+    assert_eq!(kind(error), ErrorKind::InvalidData);
+
+    let options = ParseOptions::default().strict(false);
+    let prefixes = parse_prefixes(&bytes, AFI::IPV4, false, &options).unwrap();
+    assert_eq!(prefixes, vec![(None, 33, vec![10, 0, 0, 1, 0])]);
+}
@@ -1,3 +1,4 @@
+use mrt_rs::tabledump::TABLE_DUMP_V2;
 use std::fs;
 use std::fs::File;
 
@@ -17,3 +18,3342 @@ fn test_samples() {
         }
     }
 }
+
+// Tests if all records of a stream can be collected at once.
+#[test]
+fn test_read_all() {
+    let file = File::open("res/bird-mrtdump_bgp").unwrap();
+    let mut reader = mrt_rs::Reader::new(file);
+    let records = reader.read_all().unwrap();
+    assert_eq!(records.len(), 27);
+}
+
+// Tests if collecting more records than the configured limit results in an error.
+#[test]
+fn test_read_all_limit() {
+    let file = File::open("res/bird-mrtdump_bgp").unwrap();
+    let options = mrt_rs::ParseOptions::default().record_limit(10);
+    let mut reader = mrt_rs::Reader::with_options(file, options);
+    match reader.read_all() {
+        Err(mrt_rs::MrtError::RecordLimitExceeded(10)) => (),
+        x => panic!("Expected RecordLimitExceeded, got {:?}", x),
+    }
+}
+
+// Creates a raw MRT record consisting of a header followed by the given body.
+fn raw_record(record_type: u16, sub_type: u16, body: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&1_500_000_000u32.to_be_bytes());
+    bytes.extend_from_slice(&record_type.to_be_bytes());
+    bytes.extend_from_slice(&sub_type.to_be_bytes());
+    bytes.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(body);
+    bytes
+}
+
+// Tests if unknown record types are skipped when the Reader is configured to do so.
+#[test]
+fn test_skip_unknown_record_type() {
+    let mut bytes = raw_record(99, 0, &[1, 2, 3, 4]);
+    bytes.extend(fs::read("res/bird-mrtdump_bgp").unwrap());
+
+    let options = mrt_rs::ParseOptions::default().skip_unknown(true);
+    let mut reader = mrt_rs::Reader::with_options(bytes.as_slice(), options);
+    let (header, record) = reader.read().unwrap().unwrap();
+    assert_eq!(header.record_type, 99);
+    match record {
+        mrt_rs::Record::UNKNOWN(body) => assert_eq!(body, vec![1, 2, 3, 4]),
+        x => panic!("Expected an UNKNOWN record, got {:?}", x),
+    }
+
+    let (header, _) = reader.read().unwrap().unwrap();
+    assert_eq!(header.record_type, 16);
+
+    // Without skipping an error should be returned.
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    assert!(reader.read().is_err());
+}
+
+// Tests if a record can be read directly after seeking to its offset, and if the state of the
+// previously read record is cleared by seeking.
+#[test]
+fn test_seek_to() {
+    let file = File::open("res/openbgpd_rib_table-v2").unwrap();
+    let options = mrt_rs::ParseOptions::default().keep_raw(true);
+    let mut reader = mrt_rs::Reader::with_options(file, options);
+    let (first, _) = reader.read().unwrap().unwrap();
+    let (second, _) = reader.read().unwrap().unwrap();
+    reader.read().unwrap().unwrap();
+    assert!(reader.last_header().is_some());
+    assert!(reader.raw_bytes().is_some());
+
+    reader.seek_to(12 + u64::from(first.length)).unwrap();
+    assert!(reader.last_header().is_none());
+    assert!(reader.raw_bytes().is_none());
+    let (header, record) = reader.read().unwrap().unwrap();
+    assert_eq!(header.timestamp, second.timestamp);
+    assert_eq!(header.sub_type, second.sub_type);
+    assert_eq!(header.length, second.length);
+    match record {
+        mrt_rs::Record::TABLE_DUMP_V2(_) => (),
+        x => panic!("Expected a TABLE_DUMP_V2 record, got {:?}", x),
+    }
+}
+
+// Tests if identical records produce identical digests and different records do not.
+#[cfg(feature = "digest")]
+#[test]
+fn test_read_with_digest() {
+    let record = raw_record(
+        16,
+        0,
+        &[0, 1, 0, 2, 0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 0, 6],
+    );
+    let mut changed = record.clone();
+    changed[30] = 5;
+
+    let mut bytes = record.clone();
+    bytes.extend(&record);
+    bytes.extend(&changed);
+
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    let (_, _, first) = reader.read_with_digest().unwrap().unwrap();
+    let (_, _, second) = reader.read_with_digest().unwrap().unwrap();
+    let (_, _, third) = reader.read_with_digest().unwrap().unwrap();
+    assert_eq!(first, second);
+    assert_ne!(first, third);
+    assert!(reader.read_with_digest().unwrap().is_none());
+}
+
+// Tests if records written using a HeaderBuilder are identical to the records that were read.
+#[test]
+fn test_write_round_trip() {
+    for entry in fs::read_dir("res/").unwrap() {
+        let path = entry.unwrap().path();
+        let original = fs::read(&path).unwrap();
+
+        let mut reader = mrt_rs::Reader::new(original.as_slice());
+        let mut writer = mrt_rs::Writer::new(Vec::new());
+        while let Some((header, record)) = reader.read().unwrap() {
+            let builder = mrt_rs::HeaderBuilder::new(header.record_type, header.sub_type)
+                .timestamp(header.timestamp);
+            let written = writer.write(&builder.build(), &record).unwrap();
+            assert_eq!(written.length, header.length);
+        }
+
+        assert_eq!(writer.stream, original, "{:?}", path);
+    }
+}
+
+// Tests if the extended timestamp is written and included in the length of the record.
+#[test]
+fn test_write_extended_timestamp() {
+    let body = [0, 1, 0, 2, 0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 0, 6];
+    let (_, record) = mrt_rs::read(&mut raw_record(16, 0, &body).as_slice())
+        .unwrap()
+        .unwrap();
+    let record = match record {
+        mrt_rs::Record::BGP4MP(x) => mrt_rs::Record::BGP4MP_ET(x),
+        x => panic!("Expected a BGP4MP record, got {:?}", x),
+    };
+
+    let mut writer = mrt_rs::Writer::new(Vec::new());
+    let builder = mrt_rs::HeaderBuilder::new(17, 0)
+        .timestamp(1_500_000_000)
+        .extended(123_456);
+    let header = writer.write(&builder.build(), &record).unwrap();
+    assert_eq!(header.length, 24);
+
+    let (header, _) = mrt_rs::read(&mut writer.stream.as_slice())
+        .unwrap()
+        .unwrap();
+    assert_eq!(header.record_type, 17);
+    assert_eq!(header.extended, 123_456);
+    assert_eq!(header.length, 24);
+}
+
+// Tests if the Writer rejects a header of which the type or subtype does not match the record,
+// while ignoring the subtype of record types that do not define subtypes.
+#[test]
+fn test_write_mismatched_header() {
+    let body = [0, 1, 0, 2, 0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 0, 6];
+    let (_, record) = mrt_rs::read(&mut raw_record(16, 0, &body).as_slice())
+        .unwrap()
+        .unwrap();
+
+    let mut writer = mrt_rs::Writer::new(Vec::new());
+    for (record_type, sub_type) in &[(16, 5), (17, 0), (13, 0)] {
+        let header = mrt_rs::HeaderBuilder::new(*record_type, *sub_type).build();
+        match writer.write(&header, &record) {
+            Err(mrt_rs::MrtError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput),
+            x => panic!("Expected an InvalidInput error, got {:?}", x),
+        }
+    }
+    assert!(writer.stream.is_empty());
+
+    let header = mrt_rs::HeaderBuilder::new(2, 3)
+        .timestamp(1_500_000_000)
+        .build();
+    writer.write(&header, &mrt_rs::Record::DIE).unwrap();
+    assert_eq!(writer.stream, raw_record(2, 3, &[]));
+}
+
+// Tests if consecutive records can be parsed from a slice by advancing the offset.
+#[test]
+fn test_parse_one() {
+    let mut bytes = raw_record(
+        16,
+        0,
+        &[0, 1, 0, 2, 0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 0, 6],
+    );
+    bytes.extend(raw_record(13, 1, &[10, 0, 0, 1, 0, 0, 0, 0]));
+
+    let (header, _, consumed) = mrt_rs::Record::parse_one(&bytes).unwrap();
+    assert_eq!(header.record_type, 16);
+    assert_eq!(consumed, 32);
+
+    let (header, record, consumed) = mrt_rs::Record::parse_one(&bytes[consumed..]).unwrap();
+    assert_eq!(header.record_type, 13);
+    assert_eq!(consumed, 20);
+    match record {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(x)) => {
+            assert_eq!(x.collector_id, 0x0A00_0001)
+        }
+        x => panic!("Expected a PEER_INDEX_TABLE record, got {:?}", x),
+    }
+
+    // A truncated record should result in an error.
+    assert!(mrt_rs::Record::parse_one(&bytes[32..48]).is_err());
+}
+
+// Tests if prefix lengths exceeding the address family are rejected in strict mode.
+#[test]
+fn test_strict_prefix_length() {
+    let body = |prefix_length: u8| {
+        let mut body = vec![0, 0, 0, 1, prefix_length];
+        body.extend(vec![10; usize::from(prefix_length).div_ceil(8)]);
+        body.extend(&[0, 0]);
+        body
+    };
+
+    // A /32 is the longest valid IPv4 prefix.
+    let bytes = raw_record(13, 2, &body(32));
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    match reader.read().unwrap().unwrap().1 {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_IPV4_UNICAST(x)) => {
+            assert_eq!(x.prefix_length, 32);
+            assert_eq!(x.prefix, vec![10; 4]);
+        }
+        x => panic!("Expected a RIB_IPV4_UNICAST record, got {:?}", x),
+    }
+
+    let bytes = raw_record(13, 2, &body(33));
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    match reader.read() {
+        Err(mrt_rs::MrtError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidData),
+        x => panic!("Expected an InvalidData error, got {:?}", x),
+    }
+
+    // Without strict parsing the prefix is read as declared.
+    let options = mrt_rs::ParseOptions {
+        strict: false,
+        ..Default::default()
+    };
+    let mut reader = mrt_rs::Reader::with_options(bytes.as_slice(), options);
+    assert!(reader.read().is_ok());
+}
+
+// Tests if a GZIP compressed stream can be read without decompressing it first.
+#[cfg(feature = "gzip")]
+#[test]
+fn test_gzip() {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&fs::read("res/bird-mrtdump_bgp").unwrap())
+        .unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut reader = mrt_rs::Reader::gzip(compressed.as_slice());
+    assert_eq!(reader.read_all().unwrap().len(), 27);
+}
+
+// Tests if a zstandard compressed stream can be read without decompressing it first.
+#[cfg(feature = "zstd")]
+#[test]
+fn test_zstd() {
+    let bytes = fs::read("res/bird-mrtdump_bgp").unwrap();
+    let compressed = zstd::encode_all(bytes.as_slice(), 0).unwrap();
+
+    let mut reader = mrt_rs::Reader::zstd(compressed.as_slice()).unwrap();
+    assert_eq!(reader.read_all().unwrap().len(), 27);
+}
+
+// Tests if a span is emitted for every record and an event for records that fail to parse.
+#[cfg(feature = "tracing")]
+#[test]
+fn test_tracing() {
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    // Collects the fields of all spans and events as text.
+    #[derive(Clone, Default)]
+    struct Collector {
+        spans: Arc<Mutex<Vec<String>>>,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0 += &format!("{}={:?} ", field.name(), value);
+        }
+    }
+
+    impl Subscriber for Collector {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes) -> Id {
+            let mut fields = Fields(String::new());
+            span.record(&mut fields);
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(fields.0);
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _: &Id, _: &Record) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event) {
+            let mut fields = Fields(String::new());
+            event.record(&mut fields);
+            self.events.lock().unwrap().push(fields.0);
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    // The sample followed by a STATE_CHANGE record that is too short.
+    let mut bytes = fs::read("res/quagga_rib").unwrap();
+    let offset = bytes.len();
+    bytes.extend(raw_record(16, 0, &[0, 1]));
+
+    let collector = Collector::default();
+    tracing::subscriber::with_default(collector.clone(), || {
+        let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+        for _ in 0..7 {
+            reader.read().unwrap().unwrap();
+        }
+        assert!(reader.read().is_err());
+    });
+
+    let spans = collector.spans.lock().unwrap();
+    assert_eq!(spans.len(), 8);
+    assert!(spans[0].starts_with("offset=0 record_type=13 sub_type=1 length="));
+    assert_eq!(
+        spans[7],
+        format!("offset={} record_type=16 sub_type=0 length=2 ", offset)
+    );
+
+    let events = collector.events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=Failed to parse MRT record. error="));
+}
+
+// Tests if peers of a PEER_INDEX_TABLE can be looked up by the index used by RIB entries.
+#[test]
+fn test_peer_index_lookup() {
+    let mut reader = mrt_rs::Reader::new(File::open("res/quagga_rib").unwrap());
+    let table = match reader.read().unwrap().unwrap().1 {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(x)) => x,
+        x => panic!("Expected a PEER_INDEX_TABLE record, got {:?}", x),
+    };
+
+    let peer = table.get(1).unwrap();
+    assert_eq!(
+        peer.peer_ip_address,
+        "fd02::10".parse::<std::net::IpAddr>().unwrap()
+    );
+    assert_eq!(peer.peer_as, 65000);
+    assert!(table.get(2).is_none());
+
+    let indices: Vec<u16> = table.iter_indexed().map(|(i, _)| i).collect();
+    assert_eq!(indices, vec![0, 1]);
+    for (index, peer) in table.iter_indexed() {
+        assert!(std::ptr::eq(table.get(index).unwrap(), peer));
+    }
+}
+
+// Tests if the fields of a captured BGP4MP ENTRY record are parsed in the right order.
+#[test]
+fn test_bgp4mp_entry() {
+    let mut reader = mrt_rs::Reader::new(File::open("res/openbgpd_rib_table-mp").unwrap());
+    let entry = match reader.read().unwrap().unwrap().1 {
+        mrt_rs::Record::BGP4MP(mrt_rs::bgp4mp::BGP4MP::ENTRY(x)) => x,
+        x => panic!("Expected a BGP4MP ENTRY record, got {:?}", x),
+    };
+
+    assert_eq!(entry.peer_as, 65000);
+    assert_eq!(entry.local_as, 65000);
+    assert_eq!(
+        entry.peer_address,
+        "192.168.1.102".parse::<std::net::IpAddr>().unwrap()
+    );
+    assert_eq!(
+        entry.local_address,
+        "192.168.1.10".parse::<std::net::IpAddr>().unwrap()
+    );
+    assert_eq!(entry.status, 1);
+    assert_eq!(entry.time_last_change, 1444842835);
+    assert_eq!(entry.afi, 1);
+    assert_eq!(entry.safi, 1);
+    assert_eq!(
+        entry.next_hop,
+        "192.168.0.15".parse::<std::net::IpAddr>().unwrap()
+    );
+    assert_eq!(entry.next_hop_link_local, None);
+    assert_eq!(entry.prefix_length, 16);
+    assert_eq!(entry.prefix, vec![192, 168]);
+    assert_eq!(entry.attributes.len(), 43);
+}
+
+// Tests if a BGP4MP ENTRY with both a global and a link-local IPv6 next hop can be read and written.
+#[test]
+fn test_bgp4mp_entry_link_local_next_hop() {
+    let global: std::net::Ipv6Addr = "2001:db8::1".parse().unwrap();
+    let link_local: std::net::Ipv6Addr = "fe80::1".parse().unwrap();
+
+    // Peer AS, local AS, interface and the IPv4 AFI of the peer and local addresses.
+    let mut body = vec![0, 1, 0, 2, 0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7, 8];
+    // View number, status and the time of the last change.
+    body.extend(&[0, 0, 0, 1, 0, 0, 0, 0]);
+    // The IPv6 AFI and unicast SAFI of the next hop, followed by a 32-byte next hop.
+    body.extend(&[0, 2, 1, 32]);
+    body.extend(&global.octets());
+    body.extend(&link_local.octets());
+    // A 2001:db8::/32 prefix without attributes.
+    body.extend(&[32, 0x20, 0x01, 0x0d, 0xb8, 0, 0]);
+
+    let bytes = raw_record(16, 2, &body);
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    let (header, record) = reader.read().unwrap().unwrap();
+    match &record {
+        mrt_rs::Record::BGP4MP(mrt_rs::bgp4mp::BGP4MP::ENTRY(x)) => {
+            assert_eq!(x.next_hop, std::net::IpAddr::V6(global));
+            assert_eq!(x.next_hop_link_local, Some(link_local));
+            assert_eq!(x.prefix, vec![0x20, 0x01, 0x0d, 0xb8]);
+        }
+        x => panic!("Expected a BGP4MP ENTRY record, got {:?}", x),
+    }
+
+    let mut writer = mrt_rs::Writer::new(Vec::new());
+    writer.write(&header, &record).unwrap();
+    assert_eq!(writer.stream, bytes);
+}
+
+// Tests if a Reader with a capacity hint reads the same records as one without.
+#[test]
+fn test_capacity_hint() {
+    let bytes = fs::read("res/openbgpd_rib_table-v2").unwrap();
+    let expected = mrt_rs::Reader::new(bytes.as_slice()).read_all().unwrap();
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice()).with_capacity_hint(16);
+    let records = reader.read_all().unwrap();
+
+    assert_eq!(records, expected);
+}
+
+// Tests if the ASN size is derived correctly from each BGP4MP sub-type.
+#[test]
+fn test_asn_size_for_bgp4mp_subtype() {
+    use mrt_rs::AsnSize;
+
+    let expected = [
+        (0, AsnSize::Two),   // STATE_CHANGE
+        (1, AsnSize::Two),   // MESSAGE
+        (2, AsnSize::Two),   // ENTRY
+        (3, AsnSize::Two),   // SNAPSHOT
+        (4, AsnSize::Four),  // MESSAGE_AS4
+        (5, AsnSize::Four),  // STATE_CHANGE_AS4
+        (6, AsnSize::Two),   // MESSAGE_LOCAL
+        (7, AsnSize::Four),  // MESSAGE_AS4_LOCAL
+        (8, AsnSize::Two),   // MESSAGE_ADDPATH
+        (9, AsnSize::Four),  // MESSAGE_AS4_ADDPATH
+        (10, AsnSize::Two),  // MESSAGE_LOCAL_ADDPATH
+        (11, AsnSize::Four), // MESSAGE_AS4_LOCAL_ADDPATH
+    ];
+
+    for (sub_type, asn_size) in expected.iter() {
+        assert_eq!(AsnSize::for_bgp4mp_subtype(*sub_type), *asn_size);
+    }
+    assert_eq!(AsnSize::Two.size(), 2);
+    assert_eq!(AsnSize::Four.size(), 4);
+}
+
+// Tests if the body of a deprecated record type is skipped so the next record can be read.
+#[test]
+fn test_deprecated_record_body() {
+    let mut bytes = raw_record(1, 0, &[1, 2, 3, 4, 5]);
+    bytes.extend(raw_record(
+        16,
+        0,
+        &[0, 1, 0, 2, 0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 0, 6],
+    ));
+
+    let mut stream = bytes.as_slice();
+    match mrt_rs::read(&mut stream).unwrap().unwrap().1 {
+        mrt_rs::Record::START => (),
+        x => panic!("Expected a START record, got {:?}", x),
+    }
+    match mrt_rs::read(&mut stream).unwrap().unwrap().1 {
+        mrt_rs::Record::BGP4MP(mrt_rs::bgp4mp::BGP4MP::STATE_CHANGE(x)) => {
+            assert_eq!(x.new_state, 6)
+        }
+        x => panic!("Expected a STATE_CHANGE record, got {:?}", x),
+    }
+
+    let records = mrt_rs::Reader::new(bytes.as_slice()).read_all().unwrap();
+    assert_eq!(records.len(), 2);
+
+    // A body that is shorter than declared is reported as an error.
+    let truncated = &bytes[..14];
+    assert!(mrt_rs::read(&mut &truncated[..]).is_err());
+}
+
+// Tests if the number of prefixes per peer sums up to the number of RIB entries in a RIB dump.
+#[test]
+fn test_peer_distribution() {
+    for file in &[
+        "res/quagga_rib",
+        "res/bird-mrtdump_rib",
+        "res/openbgpd_rib_table-v2",
+    ] {
+        let mut entries = 0;
+        for (_, record) in mrt_rs::Reader::new(File::open(file).unwrap())
+            .read_all()
+            .unwrap()
+        {
+            entries += match record {
+                mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_IPV4_UNICAST(x))
+                | mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_IPV6_UNICAST(x)) => {
+                    x.entries.len()
+                }
+                mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_GENERIC(x)) => x.entries.len(),
+                mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_IPV4_UNICAST_ADDPATH(x))
+                | mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_IPV6_UNICAST_ADDPATH(x)) => {
+                    x.entries.len()
+                }
+                _ => 0,
+            };
+        }
+
+        let mut reader = mrt_rs::Reader::new(File::open(file).unwrap());
+        let distribution = mrt_rs::peer_distribution(&mut reader).unwrap();
+        assert!(entries > 0);
+        assert_eq!(distribution.total(), entries);
+        assert_eq!(distribution.unknown, 0);
+    }
+
+    // A RIB entry referencing a peer that is not part of the table is counted separately.
+    let peer_index_table = [
+        10, 0, 0, 1, 0, 0, 0, 1, 2, 10, 0, 0, 2, 10, 0, 0, 2, 0, 0, 0xFD, 0xE8,
+    ];
+    let rib = |peer_index: u8| vec![0, 0, 0, 1, 8, 10, 0, 1, 0, peer_index, 0, 0, 0, 0, 0, 0];
+    let mut bytes = raw_record(13, 1, &peer_index_table);
+    bytes.extend(raw_record(13, 2, &rib(0)));
+    bytes.extend(raw_record(13, 2, &rib(7)));
+
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    let distribution = mrt_rs::peer_distribution(&mut reader).unwrap();
+    assert_eq!(distribution.peers.len(), 1);
+    assert_eq!(distribution.peers[0].0.peer_as, 65000);
+    assert_eq!(distribution.peers[0].1, 1);
+    assert_eq!(distribution.unknown, 1);
+}
+
+// Tests if an invalid UTF-8 view name is decoded lossily by default and rejected with strict UTF-8.
+#[test]
+fn test_strict_utf8_view_name() {
+    let body = [10, 0, 0, 1, 0, 2, 0xFF, b'a', 0, 0];
+    let bytes = raw_record(13, 1, &body);
+
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    match reader.read().unwrap().unwrap().1 {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(x)) => {
+            assert_eq!(x.view_name, "\u{FFFD}a")
+        }
+        x => panic!("Expected a PEER_INDEX_TABLE record, got {:?}", x),
+    }
+
+    let options = mrt_rs::ParseOptions {
+        strict_utf8: true,
+        ..Default::default()
+    };
+    let mut reader = mrt_rs::Reader::with_options(bytes.as_slice(), options);
+    match reader.read() {
+        Err(mrt_rs::MrtError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidData),
+        x => panic!("Expected an InvalidData error, got {:?}", x),
+    }
+}
+
+// Tests if a view name with invalid UTF-8 is written back unchanged, unless it was modified.
+#[test]
+fn test_view_name_round_trip() {
+    let body = [10, 0, 0, 1, 0, 2, 0xFF, b'a', 0, 0];
+    let bytes = raw_record(13, 1, &body);
+    let (header, record) = mrt_rs::read(&mut bytes.as_slice()).unwrap().unwrap();
+    assert_eq!(record.to_bytes(&header).unwrap(), bytes);
+
+    let mut table = match record {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(x)) => x,
+        x => panic!("Expected a PEER_INDEX_TABLE record, got {:?}", x),
+    };
+    table.view_name = String::from("b");
+    let record = mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(table));
+    let header = mrt_rs::HeaderBuilder::new(13, 1)
+        .timestamp(header.timestamp)
+        .build();
+    assert_eq!(
+        record.to_bytes(&header).unwrap(),
+        raw_record(13, 1, &[10, 0, 0, 1, 0, 1, b'b', 0, 0])
+    );
+}
+
+// Tests if a RecordVisitor receives every record and can skip records based on their header.
+#[test]
+fn test_record_visitor() {
+    use mrt_rs::bgp4mp::{MESSAGE, MESSAGE_AS4, STATE_CHANGE_AS4};
+    use mrt_rs::tabledump::{RIBEntry, RIBEntryAddPath, PEER_INDEX_TABLE};
+    use mrt_rs::{Header, Record, RecordVisitor};
+
+    #[derive(Default)]
+    struct Counter {
+        skip_all: bool,
+        messages: usize,
+        state_changes: usize,
+        tables: usize,
+        entries: usize,
+        other: usize,
+    }
+
+    impl RecordVisitor for Counter {
+        fn visit_header(&mut self, _: &Header) -> bool {
+            !self.skip_all
+        }
+        fn visit_bgp4mp_message(&mut self, _: &Header, _: &MESSAGE) {
+            self.messages += 1;
+        }
+        fn visit_bgp4mp_message_as4(&mut self, _: &Header, _: &MESSAGE_AS4) {
+            self.messages += 1;
+        }
+        fn visit_bgp4mp_state_change_as4(&mut self, _: &Header, _: &STATE_CHANGE_AS4) {
+            self.state_changes += 1;
+        }
+        fn visit_peer_index_table(&mut self, _: &Header, _: &PEER_INDEX_TABLE) {
+            self.tables += 1;
+        }
+        fn visit_rib_entry(&mut self, _: &Header, _: &RIBEntry) {
+            self.entries += 1;
+        }
+        fn visit_rib_entry_addpath(&mut self, _: &Header, _: &RIBEntryAddPath) {
+            self.entries += 1;
+        }
+        fn visit_record(&mut self, _: &Header, _: &Record) {
+            self.other += 1;
+        }
+    }
+
+    let mut counter = Counter::default();
+    let mut reader = mrt_rs::Reader::new(File::open("res/bird-mrtdump_bgp").unwrap());
+    reader.drive(&mut counter).unwrap();
+    assert_eq!(counter.messages, 15);
+    assert_eq!(counter.state_changes, 12);
+    assert_eq!(counter.other, 0);
+
+    let mut counter = Counter::default();
+    let mut reader = mrt_rs::Reader::new(File::open("res/quagga_rib").unwrap());
+    reader.drive(&mut counter).unwrap();
+    assert_eq!(counter.tables, 1);
+    assert_eq!(
+        counter.entries,
+        mrt_rs::peer_distribution(&mut mrt_rs::Reader::new(
+            File::open("res/quagga_rib").unwrap()
+        ))
+        .unwrap()
+        .total()
+    );
+
+    let mut counter = Counter {
+        skip_all: true,
+        ..Default::default()
+    };
+    let mut reader = mrt_rs::Reader::new(File::open("res/bird-mrtdump_bgp").unwrap());
+    reader.drive(&mut counter).unwrap();
+    assert_eq!(counter.messages + counter.state_changes + counter.other, 0);
+}
+
+// Tests if BGP4MP_ET records are read and skipped without losing alignment with the next record.
+#[test]
+fn test_extended_timestamp_alignment() {
+    let mut keepalive = vec![0xFF; 16];
+    keepalive.extend(&[0, 19, 4]);
+
+    // A BGP4MP_ET MESSAGE record starting with a microsecond timestamp of 250000.
+    let mut body = vec![
+        0, 3, 0xD0, 0x90, 0, 1, 0, 2, 0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7, 8,
+    ];
+    body.extend(&keepalive);
+    let mut bytes = raw_record(17, 1, &body);
+    bytes.extend(raw_record(
+        16,
+        0,
+        &[0, 1, 0, 2, 0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 0, 6],
+    ));
+
+    let (header, _, length) = mrt_rs::Record::parse_one(&bytes).unwrap();
+    assert_eq!(length, 12 + body.len());
+    assert!(header.has_extended_timestamp());
+    assert!(!mrt_rs::HeaderBuilder::new(16, 1)
+        .build()
+        .has_extended_timestamp());
+
+    // Both the streaming and the buffered read paths consume exactly the declared length.
+    let mut stream = bytes.as_slice();
+    let mut records = Vec::new();
+    while let Some(record) = mrt_rs::read(&mut stream).unwrap() {
+        records.push(record);
+    }
+    let buffered = mrt_rs::Reader::new(bytes.as_slice()).read_all().unwrap();
+
+    for records in [records, buffered].iter() {
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].0.extended, 250_000);
+        match &records[0].1 {
+            mrt_rs::Record::BGP4MP_ET(mrt_rs::bgp4mp::BGP4MP::MESSAGE(x)) => {
+                assert_eq!(x.message, keepalive)
+            }
+            x => panic!("Expected a BGP4MP_ET MESSAGE record, got {:?}", x),
+        }
+        match &records[1].1 {
+            mrt_rs::Record::BGP4MP(mrt_rs::bgp4mp::BGP4MP::STATE_CHANGE(x)) => {
+                assert_eq!(x.new_state, 6)
+            }
+            x => panic!("Expected a STATE_CHANGE record, got {:?}", x),
+        }
+    }
+
+    // Skipping the BGP4MP_ET record without parsing it lands on the next record.
+    struct SkipExtended(Vec<u16>);
+    impl mrt_rs::RecordVisitor for SkipExtended {
+        fn visit_header(&mut self, header: &mrt_rs::Header) -> bool {
+            !header.has_extended_timestamp()
+        }
+        fn visit_bgp4mp_state_change(
+            &mut self,
+            _: &mrt_rs::Header,
+            x: &mrt_rs::bgp4mp::STATE_CHANGE,
+        ) {
+            self.0.push(x.new_state);
+        }
+    }
+    let mut visitor = SkipExtended(Vec::new());
+    mrt_rs::Reader::new(bytes.as_slice())
+        .drive(&mut visitor)
+        .unwrap();
+    assert_eq!(visitor.0, vec![6]);
+}
+
+// Tests if the header of a record rejected by read_buffered is consumed like it is by read, such
+// that retrying does not report the same record again.
+#[test]
+fn test_read_buffered_record_too_large() {
+    // The body of the rejected record happens to be a NULL record with a 4-byte body.
+    let bytes = raw_record(16, 1, &raw_record(0, 0, &[0; 4]));
+    let options = mrt_rs::ParseOptions::default().max_record_size(8);
+    let stream = std::io::BufReader::new(bytes.as_slice());
+    let mut reader = mrt_rs::Reader::with_options(stream, options);
+
+    match reader.read_buffered() {
+        Err(mrt_rs::MrtError::RecordTooLarge(header)) => assert_eq!(header.length, 16),
+        x => panic!("Expected a RecordTooLarge error, got {:?}", x),
+    }
+    assert_eq!(reader.offset(), 12);
+    match reader.read_buffered().unwrap() {
+        Some((_, mrt_rs::Record::NULL)) => (),
+        x => panic!("Expected a NULL record, got {:?}", x),
+    }
+    assert!(reader.read_buffered().unwrap().is_none());
+}
+
+// Tests if reading directly from the buffer of a BufRead yields the same records as read.
+#[test]
+fn test_read_buffered() {
+    for file in &["res/quagga_rib", "res/openbgpd_bgp", "res/bird-mrtdump_bgp"] {
+        let expected = mrt_rs::Reader::new(File::open(file).unwrap())
+            .read_all()
+            .unwrap();
+
+        // A small buffer forces records to cross the end of the buffer regularly.
+        for capacity in [64, 8 * 1024].iter() {
+            let stream = std::io::BufReader::with_capacity(*capacity, File::open(file).unwrap());
+            let mut reader = mrt_rs::Reader::new(stream);
+            let mut records = Vec::new();
+            while let Some(record) = reader.read_buffered().unwrap() {
+                records.push(record);
+            }
+
+            assert_eq!(records.len(), expected.len());
+            for ((header, record), (expected_header, expected_record)) in
+                records.iter().zip(&expected)
+            {
+                assert_eq!(format!("{:?}", header), format!("{:?}", expected_header));
+                assert_eq!(format!("{:?}", record), format!("{:?}", expected_record));
+            }
+        }
+    }
+}
+
+// Tests if the prefix of an IPv4 unicast RIB_GENERIC record is validated unless parsing leniently.
+#[test]
+fn test_rib_generic_prefix_length() {
+    let bytes = raw_record(13, 6, &[0, 0, 0, 1, 0, 1, 1, 33, 10, 0, 0, 1, 0, 0, 0]);
+    assert!(mrt_rs::Reader::new(bytes.as_slice()).read().is_err());
+
+    let options = mrt_rs::ParseOptions::default().strict(false);
+    match mrt_rs::Reader::with_options(bytes.as_slice(), options).read() {
+        Ok(Some((_, mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_GENERIC(x))))) => {
+            assert_eq!(x.nlri, vec![33, 10, 0, 0, 1, 0]);
+        }
+        x => panic!("Expected a RIB_GENERIC record, got {:?}", x),
+    }
+}
+
+// Tests if RIB_GENERIC records of non-IP address families are parsed without losing alignment.
+#[test]
+fn test_rib_generic_non_ip() {
+    // A BGP-LS (AFI 16388, SAFI 71) node NLRI of 6 bytes with a single RIB entry.
+    let nlri = [0, 1, 0, 2, 0xAB, 0xCD];
+    let mut body = vec![0, 0, 0, 1, 0x40, 0x04, 71];
+    body.extend(&nlri);
+    body.extend(&[0, 1, 0, 0, 0, 0, 0, 0, 0, 4, 0x40, 1, 1, 0]);
+
+    let mut bytes = raw_record(13, 6, &body);
+    bytes.extend(fs::read("res/quagga_rib").unwrap());
+
+    let records = mrt_rs::Reader::new(bytes.as_slice()).read_all().unwrap();
+    match &records[0].1 {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_GENERIC(x)) => {
+            assert_eq!(x.afi, mrt_rs::AFI::BGP_LS);
+            assert_eq!(x.safi, 71);
+            assert_eq!(x.nlri, nlri);
+            assert_eq!(x.entries.len(), 1);
+            assert_eq!(x.entries[0].attributes, vec![0x40, 1, 1, 0]);
+        }
+        x => panic!("Expected a RIB_GENERIC record, got {:?}", x),
+    }
+    match &records[1].1 {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(_)) => (),
+        x => panic!("Expected a PEER_INDEX_TABLE record, got {:?}", x),
+    }
+
+    // The record can be written again without modification.
+    let mut writer = mrt_rs::Writer::new(Vec::new());
+    writer.write(&records[0].0, &records[0].1).unwrap();
+    assert_eq!(writer.stream, raw_record(13, 6, &body));
+
+    // IP address families are represented by an AFI.
+    let bytes = raw_record(13, 6, &[0, 0, 0, 1, 0, 2, 1, 8, 0x20, 0, 0]);
+    match mrt_rs::read(&mut bytes.as_slice()).unwrap().unwrap().1 {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_GENERIC(x)) => {
+            assert_eq!(x.afi, mrt_rs::AFI::IPV6);
+        }
+        x => panic!("Expected a RIB_GENERIC record, got {:?}", x),
+    }
+
+    // A VPLS NLRI starts with a 2-byte length.
+    let bytes = raw_record(13, 6, &[0, 0, 0, 1, 0, 25, 65, 0, 2, 0xAB, 0xCD, 0, 0]);
+    match mrt_rs::read(&mut bytes.as_slice()).unwrap().unwrap().1 {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_GENERIC(x)) => {
+            assert_eq!((x.afi, x.safi), (mrt_rs::AFI::L2VPN, 65));
+            assert_eq!(x.nlri, vec![0, 2, 0xAB, 0xCD]);
+        }
+        x => panic!("Expected a RIB_GENERIC record, got {:?}", x),
+    }
+
+    // An unknown AFI results in an error instead of guessing the NLRI length.
+    body[4..6].copy_from_slice(&[0x12, 0x34]);
+    let bytes = raw_record(13, 6, &body);
+    match mrt_rs::Reader::new(bytes.as_slice()).read() {
+        Err(mrt_rs::MrtError::InvalidAfi { offset, afi }) => assert_eq!((offset, afi), (0, 0x1234)),
+        x => panic!("Expected an InvalidAfi error, got {:?}", x),
+    }
+}
+
+// Tests if records with a timestamp outside of the configured bounds are reported.
+#[test]
+fn test_timestamp_bounds() {
+    let body = [0, 1, 0, 2, 0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 0, 6];
+    let mut bytes = raw_record(16, 0, &body);
+    bytes[0..4].copy_from_slice(&[0, 0, 0, 0]);
+
+    // Without bounds the record is read as usual.
+    assert!(mrt_rs::Reader::new(bytes.as_slice()).read().is_ok());
+
+    let options = mrt_rs::ParseOptions {
+        timestamp_bounds: Some((1_000_000_000, 2_000_000_000)),
+        ..Default::default()
+    };
+    let mut reader = mrt_rs::Reader::with_options(bytes.as_slice(), options.clone());
+    match reader.read() {
+        Err(mrt_rs::MrtError::TimestampOutOfRange(header)) => {
+            assert_eq!(header.timestamp, 0);
+            assert_eq!(header.length, body.len() as u32);
+        }
+        x => panic!("Expected a TimestampOutOfRange error, got {:?}", x),
+    }
+
+    // Timestamps within the bounds are accepted.
+    let bytes = raw_record(16, 0, &body);
+    let mut reader = mrt_rs::Reader::with_options(bytes.as_slice(), options);
+    assert!(reader.read().unwrap().is_some());
+}
+
+// Tests if partially reading a record body still leaves the stream at the start of the next record.
+#[test]
+fn test_read_header_then_body() {
+    use std::io::Read;
+
+    let expected = mrt_rs::Reader::new(File::open("res/quagga_rib").unwrap())
+        .read_all()
+        .unwrap();
+
+    let mut reader = mrt_rs::Reader::new(File::open("res/quagga_rib").unwrap());
+    {
+        let (header, mut body) = reader.read_header_then_body().unwrap().unwrap();
+        assert_eq!(body.remaining(), u64::from(header.length));
+
+        // The PEER_INDEX_TABLE starts with the collector BGP ID.
+        let mut bytes = [0; 4];
+        body.read_exact(&mut bytes).unwrap();
+        assert_eq!(u32::from_be_bytes(bytes), 3232235538);
+        assert_eq!(body.remaining(), u64::from(header.length) - 4);
+    }
+
+    let (header, record) = reader.read().unwrap().unwrap();
+    assert_eq!(format!("{:?}", header), format!("{:?}", expected[1].0));
+    assert_eq!(format!("{:?}", record), format!("{:?}", expected[1].1));
+
+    // A body that is cut short by the end of the stream is reported by finish.
+    let bytes = raw_record(16, 5, &[0; 8]);
+    let mut reader = mrt_rs::Reader::new(&bytes[..bytes.len() - 2]);
+    let (_, body) = reader.read_header_then_body().unwrap().unwrap();
+    let error = body.finish().unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+// Tests if the reason and data of a PEER_DOWN record are parsed and the next record can be read.
+#[test]
+fn test_peer_down() {
+    let mut bytes = raw_record(4, 0, &[2, 0xAB, 0xCD]);
+    bytes.extend(raw_record(1, 0, &[]));
+
+    let mut records = mrt_rs::Reader::new(bytes.as_slice()).read_all().unwrap();
+    assert_eq!(records.len(), 2);
+    match &records[0].1 {
+        mrt_rs::Record::PEER_DOWN(x) => {
+            assert_eq!(x.reason, 2);
+            assert_eq!(x.data, vec![0xAB, 0xCD]);
+        }
+        x => panic!("Expected a PEER_DOWN record, got {:?}", x),
+    }
+    assert!(matches!(records[1].1, mrt_rs::Record::START));
+
+    // The record is written back as it was read.
+    let (header, record) = records.remove(0);
+    let mut writer = mrt_rs::Writer::new(Vec::new());
+    writer.write(&header, &record).unwrap();
+    assert_eq!(writer.stream, raw_record(4, 0, &[2, 0xAB, 0xCD]));
+
+    // A PEER_DOWN record without a reason is rejected.
+    assert!(mrt_rs::read(&mut &raw_record(4, 0, &[])[..]).is_err());
+}
+
+// Tests if the dialect of the samples is detected without consuming any records.
+#[test]
+fn test_detect_dialect() {
+    use mrt_rs::Dialect;
+
+    let expected = [
+        (
+            "res/bird-mrtdump_bgp",
+            Dialect::Bgp4mp {
+                extended_timestamp: false,
+                as4: true,
+                add_path: true,
+            },
+        ),
+        (
+            "res/openbgpd_bgp",
+            Dialect::Bgp4mp {
+                extended_timestamp: false,
+                as4: true,
+                add_path: false,
+            },
+        ),
+        (
+            "res/bird6-mrtdump_rib",
+            Dialect::TableDumpV2 { add_path: true },
+        ),
+        ("res/quagga_rib", Dialect::TableDumpV2 { add_path: false }),
+        ("res/openbgpd_rib_table", Dialect::TableDump),
+    ];
+
+    for (file, dialect) in expected.iter() {
+        let mut reader = mrt_rs::Reader::new(File::open(file).unwrap());
+        assert_eq!(reader.detect_dialect().unwrap(), *dialect);
+
+        let records = reader.read_all().unwrap();
+        let expected = mrt_rs::Reader::new(File::open(file).unwrap())
+            .read_all()
+            .unwrap();
+        assert_eq!(format!("{:?}", records), format!("{:?}", expected));
+    }
+
+    let mut reader = mrt_rs::Reader::new(std::io::Cursor::new(Vec::new()));
+    assert_eq!(reader.detect_dialect().unwrap(), Dialect::Unknown);
+}
+
+// Tests if messages exchanged over the same BGP session produce equal session keys.
+#[test]
+fn test_session_key() {
+    use mrt_rs::bgp4mp::{BGP4MP, MESSAGE, MESSAGE_AS4};
+    use std::collections::HashMap;
+
+    let mut sessions = HashMap::new();
+    let mut reader = mrt_rs::Reader::new(File::open("res/bird-mrtdump_bgp").unwrap());
+    while let Some((_, record)) = reader.read().unwrap() {
+        if let mrt_rs::Record::BGP4MP(BGP4MP::MESSAGE_AS4_ADDPATH(x)) = record {
+            *sessions.entry(x.session_key()).or_insert(0) += 1;
+        }
+    }
+    assert!(sessions.values().any(|count| *count > 1));
+    assert_eq!(sessions.values().sum::<usize>(), 14);
+
+    // The ASN width does not affect the key.
+    let peer_address = "192.0.2.1".parse().unwrap();
+    let local_address = "192.0.2.2".parse().unwrap();
+    let message = MESSAGE {
+        peer_as: 65000,
+        local_as: 65001,
+        interface: 0,
+        peer_address,
+        local_address,
+        message: vec![],
+    };
+    let message_as4 = MESSAGE_AS4 {
+        peer_as: 65000,
+        local_as: 65001,
+        interface: 1,
+        peer_address,
+        local_address,
+        message: vec![0; 19],
+    };
+    assert_eq!(message.session_key(), message_as4.session_key());
+}
+
+// Tests if a PEER_INDEX_TABLE and a MESSAGE_AS4 can be parsed from the bytes of a record body.
+#[test]
+fn test_from_bytes() {
+    use mrt_rs::bgp4mp::{BGP4MP, MESSAGE_AS4};
+    use mrt_rs::tabledump::PEER_INDEX_TABLE;
+    use std::convert::TryFrom;
+    use std::io::Read;
+
+    let expected = mrt_rs::Reader::new(File::open("res/quagga_rib").unwrap())
+        .read()
+        .unwrap()
+        .unwrap()
+        .1;
+
+    let mut reader = mrt_rs::Reader::new(File::open("res/quagga_rib").unwrap());
+    let (_, mut body) = reader.read_header_then_body().unwrap().unwrap();
+    let mut bytes = Vec::new();
+    body.read_to_end(&mut bytes).unwrap();
+
+    let table = PEER_INDEX_TABLE::from_bytes(&bytes).unwrap();
+    let record = mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(table));
+    assert_eq!(format!("{:?}", record), format!("{:?}", expected));
+    let table = PEER_INDEX_TABLE::try_from(&bytes[..]).unwrap();
+    assert_eq!(table.peer_entries.len(), 2);
+
+    // Trailing bytes are rejected.
+    bytes.push(0);
+    let error = PEER_INDEX_TABLE::from_bytes(&bytes).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    let body = [
+        0, 0, 253, 232, 0, 0, 253, 233, 0, 1, 0, 1, 192, 0, 2, 1, 192, 0, 2, 2, 1, 2, 3,
+    ];
+    let message = MESSAGE_AS4::try_from(&body[..]).unwrap();
+    assert_eq!(message.peer_as, 65000);
+    assert_eq!(message.local_as, 65001);
+    assert_eq!(message.message, vec![1, 2, 3]);
+
+    let bytes = raw_record(16, 4, &body);
+    match mrt_rs::Reader::new(&bytes[..]).read().unwrap().unwrap().1 {
+        mrt_rs::Record::BGP4MP(BGP4MP::MESSAGE_AS4(x)) => {
+            assert_eq!(format!("{:?}", x), format!("{:?}", message))
+        }
+        x => panic!("Unexpected record: {:?}", x),
+    }
+
+    let error = MESSAGE_AS4::from_bytes(&body[..10]).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+// Tests if records fed one byte at a time are only parsed once they have been received entirely.
+#[test]
+fn test_framed_reader() {
+    let bytes = fs::read("res/quagga_bgp").unwrap();
+    let expected = mrt_rs::Reader::new(bytes.as_slice()).read_all().unwrap();
+
+    let mut reader = mrt_rs::FramedReader::new();
+    let mut records = Vec::new();
+    for byte in &bytes {
+        reader.push(&[*byte]);
+        if let Some(record) = reader.next_record().unwrap() {
+            records.push(record);
+        }
+        assert!(reader.next_record().unwrap().is_none());
+    }
+    assert_eq!(reader.buffered(), 0);
+    assert_eq!(format!("{:?}", records), format!("{:?}", expected));
+
+    // Reading from a stream that ended in the middle of a record leaves the partial record.
+    let mut reader = mrt_rs::FramedReader::new();
+    let mut stream = &bytes[..100];
+    while reader.read_from(&mut stream).unwrap() > 0 {}
+    while reader.next_record().unwrap().is_some() {}
+    assert!(reader.buffered() > 0);
+}
+
+// Tests if a FramedReader rejects a record exceeding the maximum record size as soon as its
+// header has been received, and discards its body as it arrives.
+#[test]
+fn test_framed_reader_record_too_large() {
+    let options = mrt_rs::ParseOptions::default().max_record_size(1024);
+    let mut reader = mrt_rs::FramedReader::with_options(options);
+    let mut header = raw_record(16, 0, &[]);
+    header[8..12].copy_from_slice(&u32::MAX.to_be_bytes());
+    reader.push(&header);
+    match reader.next_record() {
+        Err(mrt_rs::MrtError::RecordTooLarge(header)) => assert_eq!(header.length, u32::MAX),
+        x => panic!("Expected a RecordTooLarge error, got {:?}", x),
+    }
+    assert_eq!(reader.buffered(), 0);
+
+    let body = [0, 1, 0, 2, 0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 0, 6];
+    let valid = raw_record(16, 0, &body);
+    let mut bytes = raw_record(16, 0, &[0; 32]);
+    bytes.extend(&valid);
+
+    let options = mrt_rs::ParseOptions::default().max_record_size(24);
+    let mut reader = mrt_rs::FramedReader::with_options(options);
+    reader.push(&bytes[..12]);
+    match reader.next_record() {
+        Err(mrt_rs::MrtError::RecordTooLarge(header)) => assert_eq!(header.length, 32),
+        x => panic!("Expected a RecordTooLarge error, got {:?}", x),
+    }
+    let mut records = Vec::new();
+    for byte in &bytes[12..] {
+        reader.push(&[*byte]);
+        if let Some(record) = reader.next_record().unwrap() {
+            records.push(record);
+        }
+    }
+    assert_eq!(reader.buffered(), 0);
+    assert_eq!(
+        records,
+        vec![mrt_rs::read(&mut valid.as_slice()).unwrap().unwrap()]
+    );
+}
+
+// Tests if RIB entries are formatted like the output of `bgpdump -m`.
+#[test]
+fn test_bgpdump_route() {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    let records = mrt_rs::Reader::new(File::open("res/quagga_rib").unwrap())
+        .read_all()
+        .unwrap();
+    let peers = match &records[0].1 {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(x)) => x,
+        x => panic!("Expected a PEER_INDEX_TABLE record, got {:?}", x),
+    };
+
+    let mut lines = Vec::new();
+    for (header, record) in &records[1..] {
+        let (rib, prefix) = match record {
+            mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_IPV4_UNICAST(x)) => {
+                let mut bytes = [0; 4];
+                bytes[..x.prefix.len()].copy_from_slice(&x.prefix);
+                (x, IpAddr::V4(Ipv4Addr::from(bytes)))
+            }
+            mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_IPV6_UNICAST(x)) => {
+                let mut bytes = [0; 16];
+                bytes[..x.prefix.len()].copy_from_slice(&x.prefix);
+                (x, IpAddr::V6(Ipv6Addr::from(bytes)))
+            }
+            x => panic!("Expected a RIB record, got {:?}", x),
+        };
+
+        let entry = &rib.entries[0];
+        let peer = peers.get(entry.peer_index).unwrap();
+        let attributes = mrt_rs::attributes::parse(
+            &entry.attributes,
+            mrt_rs::AsnSize::Four,
+            &mrt_rs::ParseOptions::default(),
+        )
+        .unwrap();
+        let route = mrt_rs::BgpdumpRoute {
+            timestamp: header.timestamp,
+            peer_address: peer.peer_ip_address,
+            peer_as: peer.peer_as,
+            prefix,
+            prefix_length: rib.prefix_length,
+            attributes: &attributes,
+        };
+        lines.push(route.to_string());
+    }
+
+    assert_eq!(
+        lines[0],
+        "TABLE_DUMP2|1486802400|B|192.168.0.10|65000|172.17.0.0/24|\
+         4200000000 4200000000 4200000000 64512 64512 64512|IGP|192.168.0.10|100|10|\
+         65000:100 65000:200 65000:300|NAG||"
+    );
+    assert_eq!(
+        lines[3],
+        "TABLE_DUMP2|1486802400|B|fd02::10|65000|fd01:1::/64|\
+         4200000000 4200000000 4200000000 64512 64512 64512|IGP|fd02::10|100|10|\
+         65000:100 65000:200 65000:300|NAG||"
+    );
+}
+
+// Tests if address families and peers can be used as keys of a set.
+#[test]
+fn test_hashable_identities() {
+    use std::collections::HashSet;
+
+    let afis: HashSet<_> = [mrt_rs::AFI::IPV4, mrt_rs::AFI::IPV4].iter().collect();
+    assert_eq!(afis.len(), 1);
+
+    let safis: HashSet<_> = [mrt_rs::SAFI::UNICAST, mrt_rs::SAFI::MULTICAST]
+        .iter()
+        .collect();
+    assert_eq!(safis.len(), 2);
+
+    let records = mrt_rs::Reader::new(File::open("res/quagga_rib").unwrap())
+        .read_all()
+        .unwrap();
+    match &records[0].1 {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(x)) => {
+            let mut peers: HashSet<_> = x.peer_entries.iter().cloned().collect();
+            assert_eq!(peers.len(), 2);
+            peers.extend(x.peer_entries.iter().cloned());
+            assert_eq!(peers.len(), 2);
+        }
+        x => panic!("Expected a PEER_INDEX_TABLE record, got {:?}", x),
+    }
+}
+
+// Tests if the microseconds of an extended timestamp are validated.
+#[test]
+fn test_subsec_micros() {
+    let keepalive = [0, 1, 0, 2, 0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7, 8];
+    let et_record = |micros: u32| {
+        let mut body = micros.to_be_bytes().to_vec();
+        body.extend(&keepalive);
+        raw_record(17, 1, &body)
+    };
+
+    let (header, _, _) = mrt_rs::Record::parse_one(&et_record(250_000)).unwrap();
+    assert_eq!(header.subsec_micros(), Some(250_000));
+
+    let (header, _, _) = mrt_rs::Record::parse_one(&raw_record(16, 1, &keepalive)).unwrap();
+    assert_eq!(header.subsec_micros(), None);
+
+    // A value of a second or more is rejected in strict mode and clamped otherwise.
+    let bytes = et_record(1_500_000);
+    assert!(mrt_rs::Record::parse_one(&bytes).is_err());
+
+    let options = mrt_rs::ParseOptions {
+        strict: false,
+        ..Default::default()
+    };
+    let (header, _) = mrt_rs::Reader::with_options(bytes.as_slice(), options)
+        .read()
+        .unwrap()
+        .unwrap();
+    assert_eq!(header.extended, 1_500_000);
+    assert_eq!(header.subsec_micros(), Some(999_999));
+}
+
+// Tests if a reset Reader reads the same records again.
+#[test]
+fn test_reset() {
+    let mut reader = mrt_rs::Reader::new(File::open("res/openbgpd_rib_table-v2").unwrap());
+    let records = reader.read_all().unwrap();
+    assert!(reader.read().unwrap().is_none());
+    let length = fs::metadata("res/openbgpd_rib_table-v2").unwrap().len();
+    assert_eq!(reader.offset(), length);
+
+    reader.reset().unwrap();
+    assert_eq!(reader.offset(), 0);
+    let again = reader.read_all().unwrap();
+    assert_eq!(again.len(), records.len());
+    assert_eq!(format!("{:?}", again), format!("{:?}", records));
+}
+
+// Tests if a Reader with a byte budget can read a stream again after a reset, and if the state
+// of the last record is cleared by the reset.
+#[test]
+fn test_reset_with_byte_budget() {
+    let length = fs::metadata("res/quagga_rib").unwrap().len();
+    let file = File::open("res/quagga_rib").unwrap();
+    let options = mrt_rs::ParseOptions::default().byte_budget(length);
+    let mut reader = mrt_rs::Reader::with_options(file, options);
+    let records = reader.read_all().unwrap();
+    assert!(reader.last_header().is_some());
+
+    reader.reset().unwrap();
+    assert!(reader.last_header().is_none());
+    assert_eq!(reader.read_all().unwrap(), records);
+}
+
+// Tests if the routes of a RIB dump are deduplicated per prefix for both address families.
+#[test]
+fn test_dedup_by_prefix() {
+    // A PEER_INDEX_TABLE with the IPv4 peers 10.0.0.1 (AS 65001) and 10.0.0.2 (AS 65002).
+    let mut bytes = raw_record(
+        13,
+        1,
+        &[
+            1, 1, 1, 1, 0, 0, 0, 2, // Collector, view name and peer count
+            2, 10, 0, 0, 1, 10, 0, 0, 1, 0, 0, 0xFD, 0xE9, // Peer 0
+            2, 10, 0, 0, 2, 10, 0, 0, 2, 0, 0, 0xFD, 0xEA, // Peer 1
+        ],
+    );
+
+    // Two routes for 10.0.0.0/8 with a LOCAL_PREF of 100 and 200 respectively.
+    bytes.extend(raw_record(
+        13,
+        2,
+        &[
+            0, 0, 0, 0, 8, 10, 0, 2, // Sequence number, prefix and entry count
+            0, 0, 0, 0, 0, 1, 0, 7, 0x40, 5, 4, 0, 0, 0, 100, // Peer 0
+            0, 1, 0, 0, 0, 2, 0, 7, 0x40, 5, 4, 0, 0, 0, 200, // Peer 1
+        ],
+    ));
+
+    // A single route for 2001:db8::/32.
+    bytes.extend(raw_record(
+        13,
+        4,
+        &[
+            0, 0, 0, 1, 32, 0x20, 0x01, 0x0D, 0xB8, 0, 1, // Prefix and entry count
+            0, 0, 0, 0, 0, 3, 0, 7, 0x40, 5, 4, 0, 0, 0, 100, // Peer 0
+        ],
+    ));
+
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    let routes: Vec<_> = reader.routes().map(|x| x.unwrap()).collect();
+    assert_eq!(routes.len(), 3);
+    assert_eq!(routes[1].peer.as_ref().unwrap().peer_as, 65002);
+
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    let routes: Vec<_> = reader
+        .routes()
+        .dedup_by_prefix()
+        .map(|x| x.unwrap())
+        .collect();
+    assert_eq!(routes.len(), 2);
+    assert_eq!(routes[0].prefix.to_string(), "10.0.0.0");
+    assert_eq!(routes[0].prefix_length, 8);
+    assert_eq!(routes[0].peer_index, 0);
+    assert_eq!(routes[1].prefix.to_string(), "2001:db8::");
+    assert_eq!(routes[1].prefix_length, 32);
+
+    // The route with the highest LOCAL_PREF is selected using a comparator.
+    let local_pref = |route: &mrt_rs::Route| {
+        route
+            .parse_attributes()
+            .unwrap()
+            .iter()
+            .find_map(|x| match x {
+                mrt_rs::attributes::PathAttribute::LOCAL_PREF(x) => Some(*x),
+                _ => None,
+            })
+    };
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    let routes: Vec<_> = reader
+        .routes()
+        .dedup_by_prefix_with(|a, b| local_pref(a).cmp(&local_pref(b)))
+        .map(|x| x.unwrap())
+        .collect();
+    assert_eq!(routes.len(), 2);
+    assert_eq!(routes[0].peer_index, 1);
+    assert_eq!(local_pref(&routes[0]), Some(200));
+}
+
+// Tests if padding after a STATE_CHANGE record is skipped such that the next record is parsed.
+#[test]
+fn test_padded_state_change() {
+    use mrt_rs::bgp4mp::BGP4MP;
+
+    let state_change = |old_state: u8, new_state: u8| {
+        vec![
+            0, 1, 0, 2, 0, 0, 0, 1, 192, 0, 2, 1, 192, 0, 2, 2, 0, old_state, 0, new_state,
+        ]
+    };
+    let mut padded = state_change(1, 2);
+    padded.extend_from_slice(&[0; 4]);
+
+    let mut bytes = raw_record(16, 0, &padded);
+    bytes.extend(raw_record(16, 0, &state_change(2, 3)));
+
+    let check = |record: mrt_rs::Record, old_state: u16, new_state: u16| match record {
+        mrt_rs::Record::BGP4MP(BGP4MP::STATE_CHANGE(x)) => {
+            assert_eq!((x.old_state, x.new_state), (old_state, new_state))
+        }
+        x => panic!("Unexpected record: {:?}", x),
+    };
+
+    // The free function reads directly from the stream.
+    let mut stream = &bytes[..];
+    check(mrt_rs::read(&mut stream).unwrap().unwrap().1, 1, 2);
+    check(mrt_rs::read(&mut stream).unwrap().unwrap().1, 2, 3);
+    assert!(mrt_rs::read(&mut stream).unwrap().is_none());
+
+    let mut reader = mrt_rs::Reader::new(&bytes[..]);
+    check(reader.read().unwrap().unwrap().1, 1, 2);
+    check(reader.read().unwrap().unwrap().1, 2, 3);
+    assert!(reader.read().unwrap().is_none());
+
+    // The padding is only reported when explicitly requested.
+    let options = mrt_rs::ParseOptions::default().reject_trailing_bytes(true);
+    match mrt_rs::Reader::with_options(&bytes[..], options).read() {
+        Err(mrt_rs::MrtError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidData),
+        x => panic!("Expected an InvalidData error, got {:?}", x),
+    }
+}
+
+// Tests if readers over in-memory bytes yield the same records as a reader over a file.
+#[test]
+fn test_reader_from_bytes() {
+    let expected = mrt_rs::Reader::new(File::open("res/bird_bgp").unwrap())
+        .read_all()
+        .unwrap();
+
+    let bytes = fs::read("res/bird_bgp").unwrap();
+    let borrowed = mrt_rs::Reader::from_bytes(&bytes).read_all().unwrap();
+    let owned = mrt_rs::Reader::from_vec(bytes.clone()).read_all().unwrap();
+    assert_eq!(format!("{:?}", borrowed), format!("{:?}", expected));
+    assert_eq!(format!("{:?}", owned), format!("{:?}", expected));
+}
+
+// Tests if an entry count that cannot fit in the length of a RIB record is rejected up front.
+#[test]
+fn test_implausible_entry_count() {
+    let entry = [0, 0, 0, 0, 0, 0, 0, 0];
+    for (sub_type, path_id) in [(2, &[][..]), (8, &[0, 0, 0, 1][..])].iter() {
+        let mut body = vec![0, 0, 0, 1, 8, 10, 0, 1];
+        body.extend_from_slice(path_id);
+        body.extend_from_slice(&entry);
+
+        // A single entry is consistent with the length of the record.
+        let bytes = raw_record(13, *sub_type, &body);
+        mrt_rs::Reader::new(bytes.as_slice())
+            .read()
+            .unwrap()
+            .unwrap();
+
+        body[6..8].copy_from_slice(&[0xFF, 0xFF]);
+        let bytes = raw_record(13, *sub_type, &body);
+        match mrt_rs::Reader::new(bytes.as_slice()).read() {
+            Err(mrt_rs::MrtError::Io(e)) => {
+                assert_eq!(e.kind(), std::io::ErrorKind::InvalidData)
+            }
+            x => panic!("Expected an InvalidData error, got {:?}", x),
+        }
+    }
+}
+
+// Tests if records are grouped by their kind for a fixture containing messages and state changes.
+#[test]
+fn test_group_by_type() {
+    use mrt_rs::RecordKind;
+
+    let mut reader = mrt_rs::Reader::new(File::open("res/openbgpd_bgp").unwrap());
+    let groups = reader.group_by_type().unwrap();
+
+    let mut kinds: Vec<_> = groups
+        .iter()
+        .map(|(k, v)| (format!("{:?}", k), v.len()))
+        .collect();
+    kinds.sort();
+    assert_eq!(
+        kinds,
+        vec![
+            ("BGP4MP_MESSAGE".to_string(), 4),
+            ("BGP4MP_MESSAGE_AS4".to_string(), 67),
+            ("BGP4MP_STATE_CHANGE".to_string(), 8),
+            ("BGP4MP_STATE_CHANGE_AS4".to_string(), 8),
+        ]
+    );
+    for (kind, records) in groups.iter() {
+        assert!(records.iter().all(|(_, record)| record.kind() == *kind));
+    }
+    assert!(!groups.contains_key(&RecordKind::TABLE_DUMP_V2_PEER_INDEX_TABLE));
+
+    // The record limit applies to the total number of records.
+    let options = mrt_rs::ParseOptions::default().record_limit(10);
+    let mut reader = mrt_rs::Reader::with_options(File::open("res/openbgpd_bgp").unwrap(), options);
+    match reader.group_by_type() {
+        Err(mrt_rs::MrtError::RecordLimitExceeded(10)) => (),
+        x => panic!(
+            "Expected the record limit to be exceeded, got {:?}",
+            x.map(|_| ())
+        ),
+    }
+}
+
+// Tests if a SNAPSHOT filename ends at the NUL or at the end of the record if it is missing.
+#[test]
+fn test_snapshot_filename() {
+    use mrt_rs::bgp4mp::BGP4MP;
+
+    let filename = |bytes: &[u8]| match mrt_rs::Reader::new(bytes).read().unwrap().unwrap().1 {
+        mrt_rs::Record::BGP4MP(BGP4MP::SNAPSHOT(x)) => {
+            assert_eq!(x.filename_bytes(), &x.filename[..]);
+            x.filename_lossy().into_owned()
+        }
+        x => panic!("Expected a SNAPSHOT record, got {:?}", x),
+    };
+
+    let bytes = raw_record(16, 3, b"\0\x01rib.mrt\0");
+    assert_eq!(filename(&bytes), "rib.mrt");
+
+    // The filename occupies the remainder of the record without a NUL terminator.
+    let mut bytes = raw_record(16, 3, b"\0\x01rib.mrt");
+    bytes.extend(raw_record(16, 3, b"\0\x02rib\xFF.mrt\0"));
+    let mut reader = mrt_rs::Reader::new(&bytes[..]);
+    for expected in ["rib.mrt", "rib\u{FFFD}.mrt"].iter() {
+        match reader.read().unwrap().unwrap().1 {
+            mrt_rs::Record::BGP4MP(BGP4MP::SNAPSHOT(x)) => {
+                assert_eq!(x.filename_lossy(), *expected)
+            }
+            x => panic!("Expected a SNAPSHOT record, got {:?}", x),
+        }
+    }
+
+    // The free function reading directly from a stream does not read beyond the record.
+    let mut stream = &bytes[..];
+    mrt_rs::read(&mut stream).unwrap().unwrap();
+    assert_eq!(stream.len(), bytes.len() - 21);
+
+    // SNAPSHOT and SYNC records are written back with or without the NUL as they were read.
+    for (record_type, sub_type) in &[(16, 3), (5, 4), (9, 4)] {
+        for body in &[&b"\0\x01rib.mrt\0"[..], b"\0\x01rib.mrt"] {
+            let bytes = raw_record(*record_type, *sub_type, body);
+            let (header, record) = mrt_rs::read(&mut bytes.as_slice()).unwrap().unwrap();
+            assert_eq!(record.to_bytes(&header).unwrap(), bytes);
+        }
+    }
+}
+
+// Tests if the header of a record whose body fails to parse is available after the error.
+#[test]
+fn test_last_header() {
+    let mut bytes = fs::read("res/bird_bgp").unwrap();
+    let length = bytes.len() as u64;
+
+    // A MESSAGE_AS4 with an invalid AFI.
+    bytes.extend(raw_record(16, 4, &[0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 9]));
+
+    let mut reader = mrt_rs::Reader::new(&bytes[..]);
+    assert!(reader.last_header().is_none());
+    while reader.read().is_ok() {
+        assert_eq!(reader.last_header().unwrap().record_type, 16);
+    }
+
+    let header = reader.last_header().unwrap();
+    assert_eq!(
+        (header.record_type, header.sub_type, header.length),
+        (16, 4, 12)
+    );
+    assert_eq!(reader.offset(), length + 24);
+}
+
+// Tests if consecutive OSPFv3_ET records are parsed without losing the alignment of the stream.
+#[test]
+fn test_ospfv3_extended_timestamp() {
+    let record = |extended: u8, message: &[u8]| {
+        let mut body = vec![0, 0, 0, extended, 0, 1, 192, 0, 2, 1, 192, 0, 2, 2];
+        body.extend_from_slice(message);
+        raw_record(49, 0, &body)
+    };
+    let mut bytes = record(1, &[1, 2, 3]);
+    bytes.extend(record(2, &[4, 5]));
+    bytes.extend(raw_record(11, 0, &[192, 0, 2, 1, 192, 0, 2, 2, 6]));
+
+    let mut reader = mrt_rs::Reader::new(&bytes[..]);
+    let mut writer = mrt_rs::Writer::new(Vec::new());
+    let mut messages = Vec::new();
+    while let Some((header, record)) = reader.read().unwrap() {
+        match &record {
+            mrt_rs::Record::OSPFv3_ET(x) => {
+                assert_eq!(x.remote, "192.0.2.1".parse::<std::net::IpAddr>().unwrap());
+                assert_eq!(x.local, "192.0.2.2".parse::<std::net::IpAddr>().unwrap());
+                messages.push((header.extended, x.message.clone()));
+            }
+            mrt_rs::Record::OSPFv2(x) => messages.push((0, x.message.clone())),
+            x => panic!("Unexpected record: {:?}", x),
+        }
+
+        let builder = mrt_rs::HeaderBuilder::new(header.record_type, header.sub_type)
+            .timestamp(header.timestamp)
+            .extended(header.extended);
+        writer.write(&builder.build(), &record).unwrap();
+    }
+
+    assert_eq!(
+        messages,
+        vec![(1, vec![1, 2, 3]), (2, vec![4, 5]), (0, vec![6])]
+    );
+    assert_eq!(writer.stream, bytes);
+
+    // A record that is too short for its fixed fields is rejected.
+    let bytes = raw_record(49, 0, &[0, 0, 0, 1, 0, 1, 192, 0, 2, 1]);
+    assert!(mrt_rs::Reader::new(&bytes[..]).read().is_err());
+}
+
+// Tests if reading stops with an error once the byte budget of the stream would be exceeded.
+#[test]
+fn test_byte_budget() {
+    let size = fs::metadata("res/bird_bgp").unwrap().len();
+
+    let options = mrt_rs::ParseOptions::default().byte_budget(size);
+    let mut reader = mrt_rs::Reader::with_options(File::open("res/bird_bgp").unwrap(), options);
+    assert_eq!(reader.read_all().unwrap().len(), 29);
+
+    let options = mrt_rs::ParseOptions::default().byte_budget(size / 2);
+    let mut reader = mrt_rs::Reader::with_options(File::open("res/bird_bgp").unwrap(), options);
+    let mut records = 0;
+    loop {
+        match reader.read() {
+            Ok(Some(_)) => records += 1,
+            Err(mrt_rs::MrtError::BudgetExceeded(budget)) => {
+                assert_eq!(budget, size / 2);
+                break;
+            }
+            x => panic!("Expected the budget to be exceeded, got {:?}", x),
+        }
+    }
+    assert!(records > 0 && records < 29);
+    assert!(reader.offset() <= size / 2);
+}
+
+// Tests if a record with a reserved TABLE_DUMP_V2 subtype is returned as raw bytes when enabled.
+#[test]
+fn test_skip_unknown_subtypes() {
+    let mut bytes = raw_record(13, 7, &[1, 2, 3, 4]);
+    bytes.extend(fs::read("res/quagga_rib").unwrap());
+
+    assert!(mrt_rs::Reader::new(&bytes[..]).read().is_err());
+
+    let options = mrt_rs::ParseOptions::default().skip_unknown_subtypes(true);
+    let mut reader = mrt_rs::Reader::with_options(&bytes[..], options);
+    let (header, record) = reader.read().unwrap().unwrap();
+    assert_eq!((header.record_type, header.sub_type), (13, 7));
+    match record {
+        mrt_rs::Record::UNKNOWN(x) => assert_eq!(x, vec![1, 2, 3, 4]),
+        x => panic!("Expected an UNKNOWN record, got {:?}", x),
+    }
+
+    // The stream remains aligned and known subtypes are parsed as usual.
+    match reader.read().unwrap().unwrap().1 {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(_)) => (),
+        x => panic!("Expected a PEER_INDEX_TABLE record, got {:?}", x),
+    }
+    assert_eq!(reader.read_all().unwrap().len(), 6);
+}
+
+// Tests if records of multiple chronologically ordered streams are merged in chronological order.
+#[test]
+fn test_merge_sorted() {
+    let files = ["res/bird_bgp", "res/quagga_bgp", "res/openbgpd_bgp"];
+    let readers = files
+        .iter()
+        .map(|file| mrt_rs::Reader::new(File::open(file).unwrap()))
+        .collect();
+    let merged: Vec<_> = mrt_rs::merge_sorted(readers)
+        .map(|record| record.unwrap().0)
+        .collect();
+
+    let total: usize = files
+        .iter()
+        .map(|file| {
+            mrt_rs::Reader::new(File::open(file).unwrap())
+                .read_all()
+                .unwrap()
+                .len()
+        })
+        .sum();
+    assert_eq!(merged.len(), total);
+    assert!(merged
+        .windows(2)
+        .all(|x| x[0].cmp_time(&x[1]) != std::cmp::Ordering::Greater));
+
+    // Microseconds are compared if both seconds are equal.
+    let header = |timestamp, extended, record_type| mrt_rs::Header {
+        timestamp,
+        extended,
+        record_type,
+        sub_type: 4,
+        length: 0,
+    };
+    let ordering = header(10, 5, 17).cmp_time(&header(10, 7, 17));
+    assert_eq!(ordering, std::cmp::Ordering::Less);
+    let ordering = header(10, 0, 16).cmp_time(&header(10, 0, 17));
+    assert_eq!(ordering, std::cmp::Ordering::Equal);
+    let ordering = header(11, 0, 16).cmp_time(&header(10, 999_999, 17));
+    assert_eq!(ordering, std::cmp::Ordering::Greater);
+}
+
+// Tests if the kept bytes of every record reproduce the stream and parse to the same record.
+#[test]
+fn test_keep_raw() {
+    let original = fs::read("res/quagga_bgp").unwrap();
+
+    let mut reader = mrt_rs::Reader::new(&original[..]);
+    reader.read().unwrap().unwrap();
+    assert!(reader.raw_bytes().is_none());
+
+    let options = mrt_rs::ParseOptions::default().keep_raw(true);
+    let mut reader = mrt_rs::Reader::with_options(&original[..], options.clone());
+    let mut copy = Vec::new();
+    while let Some((header, record)) = reader.read().unwrap() {
+        let raw = reader.raw_bytes().unwrap();
+        let (reparsed_header, reparsed) = mrt_rs::Reader::from_bytes(raw).read().unwrap().unwrap();
+        assert_eq!(reparsed_header, header);
+        assert_eq!(format!("{:?}", reparsed), format!("{:?}", record));
+        copy.extend_from_slice(raw);
+    }
+    assert_eq!(copy, original);
+
+    // Records parsed from the buffer of a BufRead are kept as well.
+    let stream = std::io::BufReader::with_capacity(256, &original[..]);
+    let mut reader = mrt_rs::Reader::with_options(stream, options);
+    let mut copy = Vec::new();
+    while reader.read_buffered().unwrap().is_some() {
+        copy.extend_from_slice(reader.raw_bytes().unwrap());
+    }
+    assert_eq!(copy, original);
+}
+
+// Tests if default routes with a prefix length of 0 are parsed and reconstructed for both families.
+#[test]
+fn test_default_route() {
+    let entry = [0, 0, 0, 0, 0, 1, 0, 4, 0x40, 1, 1, 0];
+    let addpath_entry = [0, 0, 0, 0, 0, 1, 0, 0, 0, 7, 0, 4, 0x40, 1, 1, 0];
+
+    let mut bytes = Vec::new();
+    for sub_type in [2, 4].iter() {
+        let mut body = vec![0, 0, 0, 1, 0, 0, 1];
+        body.extend_from_slice(&entry);
+        bytes.extend(raw_record(13, *sub_type, &body));
+    }
+    for sub_type in [8, 10].iter() {
+        let mut body = vec![0, 0, 0, 1, 0, 0, 1];
+        body.extend_from_slice(&addpath_entry);
+        bytes.extend(raw_record(13, *sub_type, &body));
+    }
+
+    let records = mrt_rs::Reader::new(&bytes[..]).read_all().unwrap();
+    for (_, record) in records.iter() {
+        let (prefix_length, prefix, entries) = match record {
+            mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_IPV4_UNICAST(x))
+            | mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_IPV6_UNICAST(x)) => {
+                (x.prefix_length, &x.prefix, x.entries.len())
+            }
+            mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_IPV4_UNICAST_ADDPATH(x))
+            | mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_IPV6_UNICAST_ADDPATH(x)) => {
+                (x.prefix_length, &x.prefix, x.entries.len())
+            }
+            x => panic!("Unexpected record: {:?}", x),
+        };
+        assert_eq!((prefix_length, prefix.len(), entries), (0, 0, 1));
+    }
+
+    let routes: Vec<_> = mrt_rs::Reader::new(&bytes[..])
+        .routes()
+        .map(|route| {
+            let route = route.unwrap();
+            (
+                format!("{}/{}", route.prefix, route.prefix_length),
+                route.path_id,
+            )
+        })
+        .collect();
+    assert_eq!(
+        routes,
+        vec![
+            ("0.0.0.0/0".to_string(), None),
+            ("::/0".to_string(), None),
+            ("0.0.0.0/0".to_string(), Some(7)),
+            ("::/0".to_string(), Some(7)),
+        ]
+    );
+
+    // The records are written again without modification.
+    let mut writer = mrt_rs::Writer::new(Vec::new());
+    for (header, record) in records.iter() {
+        writer.write(header, record).unwrap();
+    }
+    assert_eq!(writer.stream, bytes);
+}
+
+// Tests if the public record type and subtype codes match the values used to dispatch records.
+#[test]
+fn test_codes() {
+    use mrt_rs::codes;
+
+    assert_eq!(codes::TABLE_DUMP_V2, 13);
+    assert_eq!(codes::BGP4MP, 16);
+    assert_eq!(codes::BGP4MP_ET, 17);
+
+    let body = [0, 0, 0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0];
+    let bytes = raw_record(
+        codes::TABLE_DUMP_V2,
+        codes::table_dump_v2::RIB_IPV4_UNICAST,
+        &body,
+    );
+    let (header, record) = mrt_rs::Reader::new(&bytes[..]).read().unwrap().unwrap();
+    assert_eq!(header.record_type, codes::TABLE_DUMP_V2);
+    assert_eq!(
+        record.kind(),
+        mrt_rs::RecordKind::TABLE_DUMP_V2_RIB_IPV4_UNICAST
+    );
+
+    let body = [
+        0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 1, 10, 0, 0, 1, 10, 0, 0, 2, 0, 1, 0, 6,
+    ];
+    let bytes = raw_record(codes::BGP4MP, codes::bgp4mp::STATE_CHANGE_AS4, &body);
+    let (_, record) = mrt_rs::Reader::new(&bytes[..]).read().unwrap().unwrap();
+    assert_eq!(record.kind(), mrt_rs::RecordKind::BGP4MP_STATE_CHANGE_AS4);
+}
+
+// Tests if locally generated BGP4MP messages without an AFI are only accepted in lenient mode.
+#[test]
+fn test_missing_afi() {
+    let mut keepalive = vec![0xFF; 16];
+    keepalive.extend_from_slice(&[0, 19, 4]);
+    let mut body = vec![0, 1, 0, 2, 0, 0, 0, 0];
+    body.extend_from_slice(&keepalive);
+
+    let mut bytes = raw_record(
+        16,
+        0,
+        &[0, 1, 0, 2, 0, 0, 0, 1, 10, 0, 0, 1, 10, 0, 0, 2, 0, 1, 0, 6],
+    );
+    let offset = bytes.len() as u64;
+    bytes.extend(raw_record(16, 6, &body));
+
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    reader.read().unwrap().unwrap();
+    match reader.read() {
+        Err(mrt_rs::MrtError::InvalidAfi { offset: o, afi }) => assert_eq!((o, afi), (offset, 0)),
+        x => panic!("Expected an InvalidAfi error, got {:?}", x),
+    }
+
+    // The FramedReader reports the same error.
+    let mut framed = mrt_rs::FramedReader::new();
+    framed.push(&bytes);
+    framed.next_record().unwrap().unwrap();
+    match framed.next_record() {
+        Err(mrt_rs::MrtError::InvalidAfi { offset: o, afi }) => assert_eq!((o, afi), (offset, 0)),
+        x => panic!("Expected an InvalidAfi error, got {:?}", x),
+    }
+
+    // The free functions do not know the offset of the record, so they report a plain IO error.
+    let mut stream = &bytes[offset as usize..];
+    match mrt_rs::read(&mut stream) {
+        Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::Other),
+        x => panic!("Expected an IO error, got {:?}", x),
+    }
+    match mrt_rs::Record::parse_one(&bytes[offset as usize..]) {
+        Err(mrt_rs::MrtError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::Other),
+        x => panic!("Expected an IO error, got {:?}", x),
+    }
+
+    let options = mrt_rs::ParseOptions {
+        allow_missing_afi: true,
+        ..Default::default()
+    };
+    let mut reader = mrt_rs::Reader::with_options(bytes.as_slice(), options.clone());
+    reader.read().unwrap().unwrap();
+    match reader.read().unwrap().unwrap().1 {
+        mrt_rs::Record::BGP4MP(mrt_rs::bgp4mp::BGP4MP::MESSAGE_LOCAL(x)) => {
+            assert_eq!((x.peer_as, x.local_as), (1, 2));
+            assert!(x.peer_address.is_unspecified() && x.local_address.is_unspecified());
+            assert_eq!(x.message, keepalive);
+        }
+        x => panic!("Expected a MESSAGE_LOCAL, got {:?}", x),
+    }
+    assert!(reader.read().unwrap().is_none());
+
+    // Messages exchanged with a peer must still contain addresses.
+    let bytes = raw_record(16, 1, &body);
+    match mrt_rs::Reader::with_options(bytes.as_slice(), options).read() {
+        Err(mrt_rs::MrtError::InvalidAfi { offset, afi }) => assert_eq!((offset, afi), (0, 0)),
+        x => panic!("Expected an InvalidAfi error, got {:?}", x),
+    }
+}
+
+// Tests if options constructed using the builder are respected while parsing.
+#[test]
+fn test_parse_options_builder() {
+    let options = mrt_rs::ParseOptions::default()
+        .max_record_size(16)
+        .timestamp_bounds(1_000_000_000, 2_000_000_000)
+        .skip_unknown(true);
+    assert!(options.strict && options.skip_unknown && !options.keep_raw);
+
+    let mut bytes = raw_record(99, 0, &[1, 2, 3]);
+    bytes.extend(raw_record(99, 0, &[0; 17]));
+    let mut reader = mrt_rs::Reader::with_options(bytes.as_slice(), options);
+    match reader.read().unwrap().unwrap().1 {
+        mrt_rs::Record::UNKNOWN(body) => assert_eq!(body, vec![1, 2, 3]),
+        x => panic!("Expected an UNKNOWN record, got {:?}", x),
+    }
+    match reader.read() {
+        Err(mrt_rs::MrtError::RecordTooLarge(header)) => assert_eq!(header.length, 17),
+        x => panic!("Expected a RecordTooLarge error, got {:?}", x),
+    }
+
+    let options = mrt_rs::ParseOptions::default().timestamp_bounds(0, 1_000_000_000);
+    let bytes = raw_record(99, 0, &[]);
+    match mrt_rs::Reader::with_options(bytes.as_slice(), options).read() {
+        Err(mrt_rs::MrtError::TimestampOutOfRange(_)) => (),
+        x => panic!("Expected a TimestampOutOfRange error, got {:?}", x),
+    }
+}
+
+// Tests if the collector and peer BGP identifiers are returned as IPv4 addresses.
+#[test]
+fn test_bgp_identifiers() {
+    use mrt_rs::tabledump::PEER_INDEX_TABLE;
+    use std::net::Ipv4Addr;
+
+    let body = [
+        10, 0, 0, 1, 0, 0, 0, 1, 0, 192, 0, 2, 1, 198, 51, 100, 1, 253, 232,
+    ];
+    let table = PEER_INDEX_TABLE::from_bytes(&body).unwrap();
+    assert_eq!(table.collector_ip(), Ipv4Addr::new(10, 0, 0, 1));
+    assert_eq!(table.collector_ip().to_string(), "10.0.0.1");
+    assert_eq!(table.peer_entries[0].bgp_id_ip().to_string(), "192.0.2.1");
+    assert_eq!(table.peer_entries[0].peer_as, 65000);
+}
+
+// Tests if the routes of a RIB dump can be looked up using longest-prefix-matching.
+#[cfg(feature = "trie")]
+#[test]
+fn test_rib_trie() {
+    let mut reader = mrt_rs::Reader::new(File::open("res/openbgpd_rib_table-v2").unwrap());
+    let trie = mrt_rs::RibTrie::from_reader(&mut reader).unwrap();
+    assert!(!trie.is_empty());
+
+    let lookup = |address: &str| {
+        trie.longest_match(address.parse().unwrap())
+            .map(|route| format!("{}/{}", route.prefix, route.prefix_length))
+    };
+    assert_eq!(lookup("192.168.0.13").as_deref(), Some("192.168.0.13/32"));
+    assert_eq!(lookup("192.168.0.11").as_deref(), Some("192.168.0.0/16"));
+    assert_eq!(lookup("192.168.1.77").as_deref(), Some("192.168.1.0/24"));
+    assert_eq!(lookup("2001:db8::1").as_deref(), Some("2001:db8::/64"));
+    assert_eq!(lookup("10.0.0.1"), None);
+
+    // A default route matches every address of its family.
+    let mut reader = mrt_rs::Reader::new(File::open("res/bird-mrtdump_rib").unwrap());
+    let trie = mrt_rs::RibTrie::from_reader(&mut reader).unwrap();
+    assert_eq!(lookup_length(&trie, "10.0.0.1"), Some(0));
+    assert_eq!(lookup_length(&trie, "172.17.1.1"), Some(24));
+
+    // The fixture contains two dumps, so the prefix is present multiple times.
+    let routes = trie.longest_match_all("172.17.1.1".parse().unwrap());
+    assert!(routes.len() > 1);
+    assert!(routes.iter().all(|route| route.prefix_length == 24));
+}
+
+#[cfg(feature = "trie")]
+fn lookup_length(trie: &mrt_rs::RibTrie, address: &str) -> Option<u8> {
+    trie.longest_match(address.parse().unwrap())
+        .map(|route| route.prefix_length)
+}
+
+// Tests if a PEER_INDEX_TABLE without a view name and without peers is parsed and written.
+#[test]
+fn test_empty_peer_index_table() {
+    let mut bytes = raw_record(13, 1, &[10, 0, 0, 1, 0, 0, 0, 0]);
+    bytes.extend(raw_record(
+        13,
+        2,
+        &[0, 0, 0, 0, 8, 10, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0],
+    ));
+
+    let records = mrt_rs::Reader::new(bytes.as_slice()).read_all().unwrap();
+    match &records[0].1 {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(x)) => {
+            assert_eq!(x.view_name, "");
+            assert!(x.peer_entries.is_empty());
+            assert!(x.get(0).is_none());
+            assert!(x.get(u16::MAX).is_none());
+            assert_eq!(x.iter_indexed().count(), 0);
+        }
+        x => panic!("Expected a PEER_INDEX_TABLE record, got {:?}", x),
+    }
+
+    // Routes referencing a peer that is not present have no peer.
+    let routes: Vec<_> = mrt_rs::Reader::new(bytes.as_slice())
+        .routes()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(routes.len(), 1);
+    assert_eq!(routes[0].peer_index, 0);
+    assert!(routes[0].peer.is_none());
+
+    let mut writer = mrt_rs::Writer::new(Vec::new());
+    for (header, record) in records.iter() {
+        writer.write(header, record).unwrap();
+    }
+    assert_eq!(writer.stream, bytes);
+}
+
+// Tests if BGP4MP records are re-encoded byte-for-byte and lossy records are rejected.
+#[test]
+fn test_record_to_bytes() {
+    for name in &["res/bird-mrtdump_bgp", "res/bird_bgp", "res/bird6_bgp"] {
+        let bytes = fs::read(name).unwrap();
+        let mut remaining = &bytes[..];
+        let mut count = 0;
+        while !remaining.is_empty() {
+            let (header, record, consumed) = mrt_rs::Record::parse_one(remaining).unwrap();
+            assert!(header.record_type == 16 || header.record_type == 17);
+            assert_eq!(record.to_bytes(&header).unwrap(), &remaining[..consumed]);
+            remaining = &remaining[consumed..];
+            count += 1;
+        }
+        assert!(count > 0);
+    }
+
+    // The body of the deprecated NULL record is not retained.
+    let bytes = raw_record(0, 0, &[1, 2, 3]);
+    let (header, record, _) = mrt_rs::Record::parse_one(&bytes).unwrap();
+    match record.to_bytes(&header) {
+        Err(mrt_rs::MrtError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput),
+        x => panic!("Expected an InvalidInput error, got {:?}", x),
+    }
+}
+
+// Tests if the headers and offsets of a seekable stream are indexed without reading the bodies.
+#[test]
+fn test_headers() {
+    let expected: Vec<_> = {
+        let mut reader = mrt_rs::Reader::new(File::open("res/openbgpd_rib_table-v2").unwrap());
+        let mut expected = Vec::new();
+        loop {
+            let offset = reader.offset();
+            match reader.read().unwrap() {
+                Some((header, _)) => expected.push((offset, header)),
+                None => break,
+            }
+        }
+        expected
+    };
+
+    let mut reader = mrt_rs::Reader::new(File::open("res/openbgpd_rib_table-v2").unwrap());
+    reader.read().unwrap().unwrap();
+    let headers = reader.headers().unwrap();
+    assert_eq!(headers, expected[1..].to_vec());
+    assert!(reader.read().unwrap().is_none());
+
+    reader.seek_to(headers[2].0).unwrap();
+    let (header, _) = reader.read().unwrap().unwrap();
+    assert_eq!(header, headers[2].1);
+}
+
+// Tests if the MP_REACH_NLRI of ADD-PATH RIB entries is decoded including path identifiers.
+#[test]
+fn test_add_path_mp_reach_nlri() {
+    let mut value = vec![0, 2, 1, 16];
+    value.extend_from_slice(&[0xfe; 16]);
+    value.extend_from_slice(&[0, 0, 0, 0, 5, 32, 0x20, 0x01, 0x0d, 0xb8]);
+    let mut attributes = vec![0x80, 14, value.len() as u8];
+    attributes.extend_from_slice(&value);
+
+    let mut body = vec![0, 0, 0, 1, 32, 0x20, 0x01, 0x0d, 0xb8, 0, 1];
+    body.extend_from_slice(&[0, 0, 0, 0, 0, 1, 0, 0, 0, 5, 0, attributes.len() as u8]);
+    body.extend_from_slice(&attributes);
+    let bytes = raw_record(13, 10, &body);
+
+    let expected = vec![(Some(5), 32, vec![0x20, 0x01, 0x0d, 0xb8])];
+    match mrt_rs::Reader::new(bytes.as_slice())
+        .read()
+        .unwrap()
+        .unwrap()
+        .1
+    {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::RIB_IPV6_UNICAST_ADDPATH(x)) => {
+            let mp_reach = x.entries[0].mp_reach_nlri().unwrap().unwrap();
+            assert_eq!((mp_reach.afi, mp_reach.safi), (Some(2), Some(1)));
+            assert_eq!(mp_reach.next_hop, vec![0xfe; 16]);
+            assert_eq!(mp_reach.prefixes, expected);
+        }
+        x => panic!("Expected a RIB_IPV6_UNICAST_ADDPATH record, got {:?}", x),
+    }
+
+    let route = mrt_rs::Reader::new(bytes.as_slice())
+        .routes()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(route.path_id, Some(5));
+    assert_eq!(route.mp_reach_nlri().unwrap().unwrap().prefixes, expected);
+
+    // The abbreviated form used by RIB entries only contains the next hop.
+    let mut value = vec![16];
+    value.extend_from_slice(&[0xfe; 16]);
+    let mp_reach =
+        mrt_rs::attributes::MpReachNlri::parse(&value, true, &mrt_rs::ParseOptions::default())
+            .unwrap();
+    assert_eq!((mp_reach.afi, mp_reach.safi), (None, None));
+    assert_eq!(mp_reach.next_hop, vec![0xfe; 16]);
+    assert!(mp_reach.prefixes.is_empty());
+}
+
+// Tests if the layout of a record is described using the names of its fields.
+#[test]
+fn test_explain() {
+    let header = mrt_rs::HeaderBuilder::new(16, 4).build();
+    let explanation = header.explain();
+    assert!(explanation.starts_with("BGP4MP MESSAGE_AS4"));
+    for field in &[
+        "peer_as(u32)",
+        "local_as(u32)",
+        "interface(u16)",
+        "afi(u16)",
+        "peer_address",
+        "local_address",
+        "BGP message",
+    ] {
+        assert!(
+            explanation.contains(field),
+            "{} lacks {}",
+            explanation,
+            field
+        );
+    }
+
+    let header = mrt_rs::HeaderBuilder::new(17, 4).build();
+    assert!(header.explain().starts_with("BGP4MP_ET MESSAGE_AS4"));
+    assert!(header
+        .explain()
+        .contains("microsecond_timestamp(u32), peer_as(u32)"));
+
+    // Every supported record is explained, unknown records are reported as such.
+    for (header, _) in mrt_rs::Reader::new(File::open("res/quagga_rib").unwrap())
+        .read_all()
+        .unwrap()
+    {
+        assert!(header.explain().starts_with("TABLE_DUMP_V2 "));
+    }
+    let header = mrt_rs::HeaderBuilder::new(13, 7).build();
+    assert!(header.explain().starts_with("Unknown record"));
+}
+
+// Tests if the TABLE_DUMP records of interleaved views are grouped and ordered by sequence number.
+#[test]
+fn test_table_dump_views() {
+    let entry = |view: u16, sequence: u16| {
+        let mut body = Vec::new();
+        body.extend_from_slice(&view.to_be_bytes());
+        body.extend_from_slice(&sequence.to_be_bytes());
+        body.extend_from_slice(&[10, 0, 0, 0, 24, 1, 0, 0, 0, 0, 192, 0, 2, 1, 0, 1, 0, 0]);
+        raw_record(12, 1, &body)
+    };
+
+    // View 1 wraps around, view 0 contains a record that arrived out of order.
+    let mut bytes = Vec::new();
+    for (view, sequence) in &[
+        (1, 65534),
+        (0, 5),
+        (1, 65535),
+        (0, 7),
+        (1, 0),
+        (0, 6),
+        (1, 1),
+    ] {
+        bytes.extend(entry(*view, *sequence));
+    }
+    bytes.extend(raw_record(0, 0, &[]));
+
+    let reader = mrt_rs::Reader::from_vec(bytes);
+    let views: Vec<(u16, Vec<u16>)> = mrt_rs::TableDumpReader::new(reader)
+        .map(|view| {
+            let (number, records) = view.unwrap();
+            (
+                number,
+                records.iter().map(|(_, r)| r.sequence_number).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        views,
+        vec![(0, vec![5, 6, 7]), (1, vec![65534, 65535, 0, 1])]
+    );
+
+    // The legacy RIB dumps of OpenBGPD only use a single view.
+    let reader = mrt_rs::Reader::new(File::open("res/openbgpd_rib_table").unwrap());
+    let views: Vec<_> = mrt_rs::TableDumpReader::new(reader).collect();
+    assert_eq!(views.len(), 1);
+    assert_eq!(views[0].as_ref().unwrap().0, 0);
+}
+
+// Tests if a final record that is cut short by the end of the stream is reported as truncated.
+#[test]
+fn test_truncated_record() {
+    let mut body = vec![0, 0, 0xFD, 0xE8, 0, 0, 0xFD, 0xE9, 0, 0, 0, 1];
+    body.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2]);
+    body.extend_from_slice(&[0xFF; 16]);
+    body.extend_from_slice(&[0, 19, 4]);
+    let mut bytes = fs::read("res/bird-mrtdump_bgp").unwrap();
+    let complete = mrt_rs::Reader::new(bytes.as_slice())
+        .read_all()
+        .unwrap()
+        .len();
+    bytes.extend(raw_record(16, 4, &body));
+    bytes.truncate(bytes.len() - 5);
+
+    let check =
+        |result: Result<Option<(mrt_rs::Header, mrt_rs::Record)>, mrt_rs::MrtError>| match result {
+            Err(mrt_rs::MrtError::TruncatedRecord {
+                header,
+                bytes_available,
+            }) => {
+                assert_eq!(header.record_type, 16);
+                assert_eq!(header.length, body.len() as u32);
+                assert_eq!(bytes_available, body.len() as u32 - 5);
+            }
+            x => panic!("Unexpected result: {:?}", x),
+        };
+
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    for _ in 0..complete {
+        reader.read().unwrap().unwrap();
+    }
+    check(reader.read());
+
+    let mut reader = mrt_rs::Reader::new(std::io::BufReader::new(bytes.as_slice()));
+    for _ in 0..complete {
+        reader.read_buffered().unwrap().unwrap();
+    }
+    check(reader.read_buffered());
+
+    #[cfg(feature = "digest")]
+    {
+        let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+        for _ in 0..complete {
+            reader.read_with_digest().unwrap().unwrap();
+        }
+        check(reader.read_with_digest().map(|x| x.map(|(h, r, _)| (h, r))));
+    }
+}
+
+// Tests if a borrowed in-memory Reader can be iterated repeatedly without consuming it.
+#[test]
+fn test_iterate_borrowed_reader() {
+    let bytes = fs::read("res/bird-mrtdump_bgp").unwrap();
+    let expected = mrt_rs::Reader::new(bytes.as_slice()).read_all().unwrap();
+
+    let mut reader = mrt_rs::Reader::from_bytes(&bytes);
+    let first: Vec<_> = (&reader).into_iter().map(Result::unwrap).collect();
+    let mut second = 0;
+    for record in &reader {
+        record.unwrap();
+        second += 1;
+    }
+    assert_eq!(first.len(), expected.len());
+    assert_eq!(second, expected.len());
+    assert_eq!(format!("{:?}", first), format!("{:?}", expected));
+
+    // The position of the Reader itself is not affected.
+    assert_eq!(reader.offset(), 0);
+    reader.read().unwrap().unwrap();
+    assert_eq!((&reader).into_iter().count(), expected.len());
+    assert_eq!(reader.read_all().unwrap().len(), expected.len() - 1);
+}
+
+// Tests if RIB entries that do not span exactly the record length are rejected when requested,
+// and how this differs from rejecting trailing bytes of any record.
+#[test]
+fn test_exact_rib_length() {
+    let rib = |attribute_length: u16| {
+        let mut body = vec![
+            0, 0, 0, 1, 24, 192, 0, 2, 0, 1, 0, 0, 0x59, 0x68, 0x2F, 0x00,
+        ];
+        body.extend_from_slice(&attribute_length.to_be_bytes());
+        body.extend_from_slice(&[0x40, 1, 1, 0]);
+        raw_record(13, 2, &body)
+    };
+    let exact = mrt_rs::ParseOptions::default().exact_rib_length(true);
+    let trailing = mrt_rs::ParseOptions::default().reject_trailing_bytes(true);
+    let kind = |bytes: &[u8], options: mrt_rs::ParseOptions| match mrt_rs::Reader::with_options(
+        bytes, options,
+    )
+    .read()
+    {
+        Ok(_) => None,
+        Err(mrt_rs::MrtError::Io(e)) => Some(e.kind()),
+        Err(e) => panic!("Unexpected error: {:?}", e),
+    };
+
+    assert_eq!(kind(&rib(4), exact.clone()), None);
+
+    // An attribute length that is too short leaves a byte of the record unread, which is
+    // skipped by default and rejected by both options.
+    assert_eq!(kind(&rib(3), mrt_rs::ParseOptions::default()), None);
+    assert_eq!(
+        kind(&rib(3), trailing.clone()),
+        Some(std::io::ErrorKind::InvalidData)
+    );
+    assert_eq!(
+        kind(&rib(3), exact.clone()),
+        Some(std::io::ErrorKind::InvalidData)
+    );
+
+    // An attribute length that is too long reads beyond the end of the record, which looks like
+    // a truncated stream unless the RIB length is checked exactly.
+    assert_eq!(
+        kind(&rib(5), mrt_rs::ParseOptions::default()),
+        Some(std::io::ErrorKind::UnexpectedEof)
+    );
+    assert_eq!(
+        kind(&rib(5), trailing),
+        Some(std::io::ErrorKind::UnexpectedEof)
+    );
+    assert_eq!(
+        kind(&rib(5), exact.clone()),
+        Some(std::io::ErrorKind::InvalidData)
+    );
+
+    for fixture in &[
+        "res/quagga_rib",
+        "res/openbgpd_rib_table-v2",
+        "res/bird6-mrtdump_rib",
+    ] {
+        let file = File::open(fixture).unwrap();
+        mrt_rs::Reader::with_options(file, exact.clone())
+            .read_all()
+            .unwrap();
+    }
+}
+
+// Tests if every supported AFI and SAFI code round-trips and all other codes are rejected.
+#[test]
+fn test_afi_safi_codes() {
+    use mrt_rs::{AFI, SAFI};
+    use std::convert::TryFrom;
+
+    let mut afis = Vec::new();
+    for code in 0..=u16::MAX {
+        if let Ok(afi) = AFI::try_from(code) {
+            assert_eq!(afi.code(), code);
+            assert_eq!(AFI::try_from(afi.code()).unwrap(), afi);
+            afis.push(afi);
+        }
+    }
+    assert_eq!(afis, vec![AFI::IPV4, AFI::IPV6, AFI::L2VPN, AFI::BGP_LS]);
+    let sizes: Vec<u32> = afis.iter().map(|x| x.size()).collect();
+    assert_eq!(sizes, vec![4, 16, 0, 0]);
+
+    let mut safis = Vec::new();
+    for code in 0..=u8::MAX {
+        if let Ok(safi) = SAFI::try_from(code) {
+            assert_eq!(safi.code(), code);
+            assert_eq!(SAFI::try_from(safi.code()).unwrap(), safi);
+            safis.push(safi);
+        }
+    }
+    assert_eq!(
+        safis,
+        vec![
+            SAFI::UNICAST,
+            SAFI::MULTICAST,
+            SAFI::MPLS_LABEL,
+            SAFI::VPLS,
+            SAFI::EVPN,
+            SAFI::BGP_LS,
+            SAFI::BGP_LS_VPN,
+            SAFI::MPLS_VPN,
+            SAFI::FLOWSPEC,
+            SAFI::FLOWSPEC_VPN
+        ]
+    );
+}
+
+// Tests if RIB entries referencing unknown peers and eBGP routes without AS_PATH are reported.
+#[test]
+fn test_validate() {
+    use mrt_rs::{ValidationContext, ValidationWarning};
+
+    let records = mrt_rs::Reader::new(File::open("res/openbgpd_rib_table-v2").unwrap())
+        .read_all()
+        .unwrap();
+    let mut context = ValidationContext::new();
+    for (_, record) in &records {
+        context.update(record);
+        assert!(record.validate(&context).is_empty());
+    }
+
+    // A RIB entry referencing the third peer while only two peers are known.
+    assert!(context.peers.as_ref().unwrap().len() > 2);
+    let body = [
+        0, 0, 0, 0, 24, 192, 0, 2, 0, 1, 0, 2, 0x59, 0x68, 0x2F, 0x00, 0, 4, 0x40, 1, 1, 0,
+    ];
+    let bytes = raw_record(13, 2, &body);
+    let (_, record) = mrt_rs::Reader::new(&bytes[..]).read().unwrap().unwrap();
+    let mut truncated = context.clone();
+    truncated.peers.as_mut().unwrap().truncate(2);
+    assert_eq!(
+        record.validate(&truncated),
+        vec![ValidationWarning::PeerIndexOutOfRange {
+            peer_index: 2,
+            peer_count: 2
+        }]
+    );
+    assert_eq!(
+        record.validate(&ValidationContext::new()),
+        vec![ValidationWarning::MissingPeerIndexTable]
+    );
+
+    // The entry only has an ORIGIN attribute, which is suspicious for a route learned over eBGP.
+    let peer_as = context.peers.as_ref().unwrap()[2].peer_as;
+    let ebgp = context.clone().local_as(peer_as.wrapping_add(1));
+    assert_eq!(
+        record.validate(&ebgp),
+        vec![ValidationWarning::EmptyAsPath { peer_as }]
+    );
+    assert!(record
+        .validate(&context.clone().local_as(peer_as))
+        .is_empty());
+
+    // BGP4MP messages carry the ASNs of the session themselves.
+    let records = mrt_rs::Reader::new(File::open("res/bird-mrtdump_bgp").unwrap())
+        .read_all()
+        .unwrap();
+    for (_, record) in &records {
+        assert!(record.validate(&ValidationContext::new()).is_empty());
+    }
+}
+
+// Tests if the bytes following the peer entries of a PEER_INDEX_TABLE are kept when requested.
+#[test]
+fn test_keep_extra() {
+    let body = [
+        10, 0, 0, 1, 0, 0, 0, 1, 0, 10, 0, 0, 2, 192, 0, 2, 1, 0xFD, 0xE8, 0xAB, 0xCD,
+    ];
+    let mut bytes = raw_record(13, 1, &body);
+    bytes.extend(raw_record(0, 0, &[]));
+
+    let table = |options: mrt_rs::ParseOptions| {
+        let mut reader = mrt_rs::Reader::with_options(bytes.as_slice(), options);
+        let table = match reader.read().unwrap().unwrap().1 {
+            mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(x)) => x,
+            x => panic!("Unexpected record: {:?}", x),
+        };
+        assert!(matches!(
+            reader.read().unwrap(),
+            Some((_, mrt_rs::Record::NULL))
+        ));
+        table
+    };
+
+    let kept = table(mrt_rs::ParseOptions::default().keep_extra(true));
+    assert_eq!(kept.peer_entries.len(), 1);
+    assert_eq!(kept.peer_entries[0].peer_as, 65000);
+    assert_eq!(kept.extra, vec![0xAB, 0xCD]);
+    assert!(table(mrt_rs::ParseOptions::default()).extra.is_empty());
+
+    // The extra bytes are written back, such that the record is reproduced exactly.
+    let header = mrt_rs::Reader::new(bytes.as_slice())
+        .read()
+        .unwrap()
+        .unwrap()
+        .0;
+    let record = mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(kept));
+    assert_eq!(record.to_bytes(&header).unwrap(), raw_record(13, 1, &body));
+
+    // Without the Reader the stream remains aligned at the next record.
+    let mut stream = bytes.as_slice();
+    mrt_rs::read(&mut stream).unwrap().unwrap();
+    assert!(matches!(
+        mrt_rs::read(&mut stream).unwrap(),
+        Some((_, mrt_rs::Record::NULL))
+    ));
+}
+
+// Tests if an OSPFv3_ET record with IPv6 addresses is parsed, of which the body consists of the
+// extended timestamp, the AFI, both addresses and the message, in that order.
+#[test]
+fn test_ospfv3_extended_timestamp_ipv6() {
+    let remote: std::net::Ipv6Addr = "2001:db8::1".parse().unwrap();
+    let local: std::net::Ipv6Addr = "2001:db8::2".parse().unwrap();
+    let mut body = vec![0, 0x0F, 0x42, 0x3F, 0, 2];
+    body.extend_from_slice(&remote.octets());
+    body.extend_from_slice(&local.octets());
+    body.extend_from_slice(&[3, 1, 0, 44]);
+    let mut bytes = raw_record(49, 0, &body);
+    bytes.extend(raw_record(0, 0, &[]));
+
+    let mut reader = mrt_rs::Reader::new(&bytes[..]);
+    let (header, record) = reader.read().unwrap().unwrap();
+    assert_eq!(header.extended, 999_999);
+    match &record {
+        mrt_rs::Record::OSPFv3_ET(x) => {
+            assert_eq!(x.remote, std::net::IpAddr::V6(remote));
+            assert_eq!(x.local, std::net::IpAddr::V6(local));
+            assert_eq!(x.message, vec![3, 1, 0, 44]);
+        }
+        x => panic!("Unexpected record: {:?}", x),
+    }
+    assert!(matches!(
+        reader.read().unwrap(),
+        Some((_, mrt_rs::Record::NULL))
+    ));
+    assert_eq!(record.to_bytes(&header).unwrap(), raw_record(49, 0, &body));
+
+    // Without a message the record consists of only the fixed fields.
+    let bytes = raw_record(49, 0, &body[..body.len() - 4]);
+    match mrt_rs::Reader::new(&bytes[..]).read().unwrap().unwrap().1 {
+        mrt_rs::Record::OSPFv3_ET(x) => assert!(x.message.is_empty()),
+        x => panic!("Unexpected record: {:?}", x),
+    }
+
+    // A record that ends within the local address is rejected.
+    let bytes = raw_record(49, 0, &body[..body.len() - 8]);
+    assert!(mrt_rs::Reader::new(&bytes[..]).read().is_err());
+}
+
+// Tests if BGP4PLUS and BGP4PLUS_01 records are parsed identically, since they only differ in
+// the draft of the BGP messages they contain, and if the message following the two ASNs and IPv6
+// addresses is kept.
+#[test]
+fn test_bgp4plus_01() {
+    let peer: std::net::Ipv6Addr = "2001:db8::1".parse().unwrap();
+    let local: std::net::Ipv6Addr = "2001:db8::2".parse().unwrap();
+    let mut body = vec![0xFD, 0xE8];
+    body.extend_from_slice(&peer.octets());
+    body.extend_from_slice(&[0xFD, 0xE9]);
+    body.extend_from_slice(&local.octets());
+    body.extend_from_slice(&[0xFF; 16]);
+    body.extend_from_slice(&[0, 19, 4]);
+
+    for &record_type in &[9, 10] {
+        let bytes = raw_record(record_type, 7, &body);
+        let (header, record) = mrt_rs::Reader::new(&bytes[..]).read().unwrap().unwrap();
+        let message = match &record {
+            mrt_rs::Record::BGP4PLUS(mrt_rs::bgp4plus::BGP4PLUS::KEEPALIVE(x))
+                if record_type == 9 =>
+            {
+                x
+            }
+            mrt_rs::Record::BGP4PLUS_01(mrt_rs::bgp4plus::BGP4PLUS::KEEPALIVE(x))
+                if record_type == 10 =>
+            {
+                x
+            }
+            x => panic!("Unexpected record: {:?}", x),
+        };
+        assert_eq!(message.peer_as, 65000);
+        assert_eq!(message.peer_ip, peer);
+        assert_eq!(message.local_as, 65001);
+        assert_eq!(message.local_ip, local);
+        assert_eq!(message.message.len(), 19);
+        assert_eq!(record.to_bytes(&header).unwrap(), bytes);
+
+        // A record that ends within the local address is rejected.
+        let bytes = raw_record(record_type, 7, &body[..30]);
+        assert!(mrt_rs::Reader::new(&bytes[..]).read().is_err());
+    }
+}
+
+// Tests if a Reader resumed from a checkpoint reads the remaining records of the stream and
+// recovers the PEER_INDEX_TABLE that preceded the checkpoint.
+#[test]
+fn test_checkpoint_resume() {
+    let headers: Vec<mrt_rs::Header> = mrt_rs::Reader::new(File::open("res/quagga_rib").unwrap())
+        .read_all()
+        .unwrap()
+        .into_iter()
+        .map(|(header, _)| header)
+        .collect();
+    let half = headers.len() / 2;
+    assert!(half > 1);
+
+    let mut reader = mrt_rs::Reader::new(File::open("res/quagga_rib").unwrap());
+    assert_eq!(reader.checkpoint(), mrt_rs::Checkpoint::default());
+    for _ in 0..half {
+        reader.read().unwrap().unwrap();
+    }
+    let checkpoint = reader.checkpoint();
+    assert_eq!(checkpoint.offset, reader.offset());
+    assert_eq!(checkpoint.peer_index_table, Some(0));
+    drop(reader);
+
+    let mut reader =
+        mrt_rs::Reader::resume(File::open("res/quagga_rib").unwrap(), checkpoint).unwrap();
+    let table = reader.read_peer_index_table().unwrap().unwrap();
+    assert_eq!(table.peer_entries.len(), 2);
+    assert_eq!(reader.offset(), checkpoint.offset);
+
+    let remaining: Vec<mrt_rs::Header> = reader
+        .read_all()
+        .unwrap()
+        .into_iter()
+        .map(|(header, _)| header)
+        .collect();
+    assert_eq!(remaining, headers[half..].to_vec());
+
+    // Without a PEER_INDEX_TABLE there is nothing to recover.
+    let mut reader = mrt_rs::Reader::new(File::open("res/quagga_bgp").unwrap());
+    reader.read().unwrap();
+    assert!(reader.read_peer_index_table().unwrap().is_none());
+}
+
+// Tests if a Reader resumed with options keeps them, and if recovering the PEER_INDEX_TABLE
+// neither counts against the byte budget nor replaces the raw bytes of the last record.
+#[test]
+fn test_checkpoint_resume_with_options() {
+    let mut reader = mrt_rs::Reader::new(File::open("res/quagga_rib").unwrap());
+    reader.read().unwrap().unwrap();
+    reader.read().unwrap().unwrap();
+    let checkpoint = reader.checkpoint();
+    let remaining = fs::metadata("res/quagga_rib").unwrap().len() - checkpoint.offset;
+
+    let options = mrt_rs::ParseOptions::default()
+        .keep_raw(true)
+        .byte_budget(remaining);
+    let file = File::open("res/quagga_rib").unwrap();
+    let mut reader = mrt_rs::Reader::resume_with_options(file, checkpoint, options).unwrap();
+    assert!(reader.read_peer_index_table().unwrap().is_some());
+    assert!(reader.raw_bytes().is_none());
+
+    let (header, _) = reader.read().unwrap().unwrap();
+    let raw = reader.raw_bytes().unwrap().to_vec();
+    assert_eq!(raw.len(), 12 + header.length as usize);
+    assert!(reader.read_peer_index_table().unwrap().is_some());
+    assert_eq!(reader.raw_bytes(), Some(&raw[..]));
+    assert_eq!(reader.last_header(), Some(&header));
+
+    // The budget covers exactly the records following the checkpoint.
+    while reader.read().unwrap().is_some() {}
+}
+
+// Tests if RIB dumps that consist of only a PEER_INDEX_TABLE, possibly without any peers, yield
+// no routes instead of an error, and if peers are still resolved against such tables.
+#[test]
+fn test_peer_index_table_only() {
+    // An empty PEER_INDEX_TABLE of collector 10.0.0.1 without a view name.
+    let empty = [10, 0, 0, 1, 0, 0, 0, 0];
+    let bytes = raw_record(13, 1, &empty);
+    assert_eq!(mrt_rs::Reader::new(&bytes[..]).routes().count(), 0);
+    let distribution = mrt_rs::peer_distribution(&mut mrt_rs::Reader::new(&bytes[..])).unwrap();
+    assert!(distribution.peers.is_empty());
+    assert_eq!(distribution.total(), 0);
+    match mrt_rs::Reader::new(&bytes[..]).read().unwrap().unwrap().1 {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(x)) => {
+            assert!(x.peer_entries.is_empty());
+            assert!(x.get(0).is_none());
+            assert_eq!(x.iter_indexed().count(), 0);
+        }
+        x => panic!("Unexpected record: {:?}", x),
+    }
+
+    // A PEER_INDEX_TABLE with a single peer using a 4-byte ASN, but without RIB entries.
+    let mut table = vec![10, 0, 0, 1, 0, 0, 0, 1, 2, 192, 0, 2, 1, 192, 0, 2, 1];
+    table.extend_from_slice(&65000u32.to_be_bytes());
+    let bytes = raw_record(13, 1, &table);
+    assert_eq!(mrt_rs::Reader::new(&bytes[..]).routes().count(), 0);
+    let distribution = mrt_rs::peer_distribution(&mut mrt_rs::Reader::new(&bytes[..])).unwrap();
+    assert_eq!(distribution.peers.len(), 1);
+    assert_eq!(distribution.peers[0].1, 0);
+    assert_eq!(distribution.total(), 0);
+    match mrt_rs::Reader::new(&bytes[..]).read().unwrap().unwrap().1 {
+        mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(x)) => {
+            assert_eq!(x.get(0).unwrap().peer_as, 65000);
+            assert!(x.get(1).is_none());
+        }
+        x => panic!("Unexpected record: {:?}", x),
+    }
+
+    // A RIB entry following an empty table cannot be attributed to a peer.
+    let mut bytes = raw_record(13, 1, &empty);
+    bytes.extend(raw_record(
+        13,
+        2,
+        &[0, 0, 0, 0, 8, 10, 0, 1, 0, 0, 0x59, 0x68, 0x2F, 0, 0, 0],
+    ));
+    let routes: Vec<mrt_rs::Route> = mrt_rs::Reader::new(&bytes[..])
+        .routes()
+        .map(|x| x.unwrap())
+        .collect();
+    assert_eq!(routes.len(), 1);
+    assert!(routes[0].peer.is_none());
+    let distribution = mrt_rs::peer_distribution(&mut mrt_rs::Reader::new(&bytes[..])).unwrap();
+    assert!(distribution.peers.is_empty());
+    assert_eq!(distribution.unknown, 1);
+}
+
+// Tests if the length computed by wire_len matches the length of the header of every record
+// that was read, and the length of the header assigned by the Writer.
+#[test]
+fn test_wire_len() {
+    for entry in fs::read_dir("res").unwrap() {
+        let path = entry.unwrap().path();
+        let mut reader = mrt_rs::Reader::new(File::open(&path).unwrap());
+        while let Some((header, record)) = reader.read().unwrap() {
+            assert_eq!(record.wire_len().unwrap(), header.length, "{:?}", path);
+        }
+    }
+
+    let header = mrt_rs::HeaderBuilder::new(17, 0).extended(42).build();
+    let record = mrt_rs::Record::BGP4MP_ET(mrt_rs::bgp4mp::BGP4MP::STATE_CHANGE(
+        mrt_rs::bgp4mp::STATE_CHANGE {
+            peer_as: 65000,
+            local_as: 65001,
+            interface: 0,
+            peer_address: "192.0.2.1".parse().unwrap(),
+            local_address: "192.0.2.2".parse().unwrap(),
+            old_state: 1,
+            new_state: 2,
+        },
+    ));
+    let written = mrt_rs::Writer::new(Vec::new())
+        .write(&header, &record)
+        .unwrap();
+    assert_eq!(record.wire_len().unwrap(), written.length);
+    assert_eq!(written.length, 24);
+
+    // Fields that cannot be encoded are reported instead of being truncated.
+    let record = mrt_rs::Record::TABLE_DUMP_V2(TABLE_DUMP_V2::PEER_INDEX_TABLE(
+        mrt_rs::tabledump::PEER_INDEX_TABLE {
+            collector_id: 0,
+            view_name: "x".repeat(70_000),
+            view_name_bytes: Vec::new(),
+            peer_entries: Vec::new(),
+            extra: Vec::new(),
+        },
+    ));
+    assert!(record.wire_len().is_err());
+
+    // Records of which the body is skipped are rejected like they are by to_bytes.
+    let bytes = raw_record(0, 0, &[1, 2, 3, 4]);
+    let (header, record) = mrt_rs::read(&mut &bytes[..]).unwrap().unwrap();
+    assert_eq!(record, mrt_rs::Record::NULL);
+    let to_bytes = record.to_bytes(&header).unwrap_err();
+    let wire_len = record.wire_len().unwrap_err();
+    for error in [to_bytes, wire_len] {
+        match error {
+            mrt_rs::MrtError::Io(e) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput),
+            e => panic!("Expected an InvalidInput error, got {:?}", e),
+        }
+    }
+    for record in [
+        mrt_rs::Record::START,
+        mrt_rs::Record::DIE,
+        mrt_rs::Record::I_AM_DEAD,
+        mrt_rs::Record::IDRP,
+    ] {
+        assert!(record.wire_len().is_err());
+    }
+}
+
+// Tests if a nonzero interface index is read from between the local ASN and the AFI of every
+// BGP4MP subtype carrying it, and if it is written back at the same position.
+#[test]
+fn test_bgp4mp_interface() {
+    use mrt_rs::bgp4mp::BGP4MP;
+
+    let addresses = [0, 1, 192, 0, 2, 1, 192, 0, 2, 2];
+    let keepalive = [0xFF; 16].iter().chain(&[0, 19, 4]).cloned();
+    let as2 = [0xFD, 0xE8, 0xFD, 0xE9, 0x01, 0x02];
+    let as4 = [0, 0, 0xFD, 0xE8, 0, 0, 0xFD, 0xE9, 0x01, 0x02];
+
+    let bodies = [
+        (0, [&as2[..], &addresses[..], &[0, 1, 0, 2]].concat()),
+        (1, [&as2[..], &addresses[..]].concat()),
+        (4, [&as4[..], &addresses[..]].concat()),
+        (5, [&as4[..], &addresses[..], &[0, 1, 0, 2]].concat()),
+        (9, [&as4[..], &addresses[..]].concat()),
+    ];
+    for (sub_type, body) in bodies.iter() {
+        let mut body = body.clone();
+        if *sub_type != 0 && *sub_type != 5 {
+            body.extend(keepalive.clone());
+        }
+        let bytes = raw_record(16, *sub_type, &body);
+        let (header, record) = mrt_rs::Reader::new(&bytes[..]).read().unwrap().unwrap();
+        let (interface, peer_as) = match &record {
+            mrt_rs::Record::BGP4MP(BGP4MP::STATE_CHANGE(x)) => (x.interface, u32::from(x.peer_as)),
+            mrt_rs::Record::BGP4MP(BGP4MP::MESSAGE(x)) => (x.interface, u32::from(x.peer_as)),
+            mrt_rs::Record::BGP4MP(BGP4MP::MESSAGE_AS4(x))
+            | mrt_rs::Record::BGP4MP(BGP4MP::MESSAGE_AS4_ADDPATH(x)) => (x.interface, x.peer_as),
+            mrt_rs::Record::BGP4MP(BGP4MP::STATE_CHANGE_AS4(x)) => (x.interface, x.peer_as),
+            x => panic!("Unexpected record: {:?}", x),
+        };
+        assert_eq!(interface, 0x0102, "subtype {}", sub_type);
+        assert_eq!(peer_as, 65000, "subtype {}", sub_type);
+        assert_eq!(record.to_bytes(&header).unwrap(), bytes);
+    }
+}
+
+// Tests if the normalized forms of records with 2-byte and 4-byte ASNs only differ in their ASN
+// size, and if the ADD-PATH and locally generated subtypes are reflected by flags.
+#[test]
+fn test_bgp4mp_normalized() {
+    use mrt_rs::bgp4mp::{NormalizedContent, BGP4MP};
+
+    fn normalized(sub_type: u16, body: &[u8], check: impl Fn(mrt_rs::bgp4mp::NormalizedMessage)) {
+        let bytes = raw_record(16, sub_type, body);
+        match mrt_rs::Reader::new(&bytes[..]).read().unwrap().unwrap().1 {
+            mrt_rs::Record::BGP4MP(x) => check(x.normalized().unwrap()),
+            x => panic!("Unexpected record: {:?}", x),
+        }
+    }
+
+    let addresses = [0, 1, 192, 0, 2, 1, 192, 0, 2, 2];
+    let keepalive = [&[0xFF; 16][..], &[0, 19, 4]].concat();
+    let as2 = [0xFD, 0xE8, 0xFD, 0xE9, 0, 3];
+    let as4 = [0, 0, 0xFD, 0xE8, 0, 0, 0xFD, 0xE9, 0, 3];
+    let message2 = [&as2[..], &addresses[..], &keepalive[..]].concat();
+    let message4 = [&as4[..], &addresses[..], &keepalive[..]].concat();
+
+    normalized(1, &message2, |two| {
+        assert_eq!(two.asn_size, mrt_rs::AsnSize::Two);
+        normalized(4, &message4, |four| {
+            assert_eq!(four.asn_size, mrt_rs::AsnSize::Four);
+            assert_eq!(
+                mrt_rs::bgp4mp::NormalizedMessage {
+                    asn_size: mrt_rs::AsnSize::Four,
+                    ..two.clone()
+                },
+                four
+            );
+        });
+        assert_eq!(
+            (two.peer_as, two.local_as, two.interface),
+            (65000, 65001, 3)
+        );
+        assert_eq!(
+            two.peer_address,
+            "192.0.2.1".parse::<std::net::IpAddr>().unwrap()
+        );
+        assert_eq!(two.session_key().local_as, 65001);
+        assert!(!two.add_path && !two.local);
+        assert_eq!(two.content, NormalizedContent::Message(&keepalive));
+    });
+
+    normalized(7, &message4, |x| assert!(!x.add_path && x.local));
+    normalized(8, &message2, |x| assert!(x.add_path && !x.local));
+    normalized(11, &message4, |x| assert!(x.add_path && x.local));
+
+    let state2 = [&as2[..], &addresses[..], &[0, 1, 0, 2]].concat();
+    let state4 = [&as4[..], &addresses[..], &[0, 1, 0, 2]].concat();
+    normalized(0, &state2, |two| {
+        normalized(5, &state4, |four| {
+            assert_eq!(two.content, four.content);
+            assert_eq!(two.session_key(), four.session_key());
+        });
+        assert_eq!(
+            two.content,
+            NormalizedContent::StateChange {
+                old_state: 1,
+                new_state: 2
+            }
+        );
+    });
+
+    let entry = BGP4MP::ENTRY(mrt_rs::bgp4mp::ENTRY {
+        peer_as: 0,
+        local_as: 0,
+        interface: 0,
+        peer_address: "192.0.2.1".parse().unwrap(),
+        local_address: "192.0.2.2".parse().unwrap(),
+        view_number: 0,
+        status: 0,
+        time_last_change: 0,
+        next_hop: "192.0.2.1".parse().unwrap(),
+        next_hop_link_local: None,
+        afi: 1,
+        safi: 1,
+        prefix_length: 0,
+        prefix: Vec::new(),
+        attributes: Vec::new(),
+    });
+    assert!(entry.normalized().is_none());
+}
+
+// Tests if IPv4-mapped IPv6 addresses are converted into IPv4 addresses when requested.
+#[test]
+fn test_unmap_ipv4() {
+    use mrt_rs::bgp4mp::BGP4MP;
+    use std::net::IpAddr;
+
+    let mapped: IpAddr = "::ffff:192.0.2.1".parse().unwrap();
+    let ipv4: IpAddr = "192.0.2.1".parse().unwrap();
+    let ipv6: IpAddr = "2001:db8::1".parse().unwrap();
+    assert_eq!(mrt_rs::normalize_addr(mapped), ipv4);
+    assert_eq!(mrt_rs::normalize_addr(ipv4), ipv4);
+    assert_eq!(mrt_rs::normalize_addr(ipv6), ipv6);
+
+    let mut body = vec![0, 1, 0, 2, 0, 0, 0, 2];
+    body.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255, 255, 192, 0, 2, 1]);
+    body.extend_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    body.extend_from_slice(&[0, 1, 0, 2]);
+    let bytes = raw_record(16, 0, &body);
+
+    let addresses = |options: mrt_rs::ParseOptions| {
+        let mut reader = mrt_rs::Reader::with_options(&bytes[..], options);
+        match reader.read().unwrap().unwrap().1 {
+            mrt_rs::Record::BGP4MP(BGP4MP::STATE_CHANGE(x)) => (x.peer_address, x.local_address),
+            x => panic!("Unexpected record: {:?}", x),
+        }
+    };
+    assert_eq!(addresses(mrt_rs::ParseOptions::default()), (mapped, ipv6));
+    assert_eq!(
+        addresses(mrt_rs::ParseOptions::default().unmap_ipv4(true)),
+        (ipv4, ipv6)
+    );
+}
+
+// Tests if skipping rejected records by seeking lands at the same records and offset as reading
+// and discarding them.
+#[test]
+fn test_drive_seek() {
+    use mrt_rs::{Header, Record, RecordVisitor};
+    use std::io::Cursor;
+
+    // Accepts every other record and keeps the records it was given.
+    #[derive(Default)]
+    struct EveryOther {
+        count: usize,
+        records: Vec<String>,
+    }
+
+    impl RecordVisitor for EveryOther {
+        fn visit_header(&mut self, _: &Header) -> bool {
+            self.count += 1;
+            self.count & 1 == 0
+        }
+        fn visit_record(&mut self, header: &Header, record: &Record) {
+            self.records.push(format!("{:?} {:?}", header, record));
+        }
+    }
+
+    for fixture in &[
+        "res/quagga_rib",
+        "res/openbgpd_rib_table-v2",
+        "res/quagga_bgp",
+    ] {
+        let bytes = fs::read(fixture).unwrap();
+
+        let mut discarded = EveryOther::default();
+        let mut reader = mrt_rs::Reader::new(&bytes[..]);
+        reader.drive(&mut discarded).unwrap();
+        let discarded_offset = reader.offset();
+
+        let mut seeked = EveryOther::default();
+        let mut reader = mrt_rs::Reader::new(Cursor::new(&bytes[..]));
+        reader.drive_seek(&mut seeked).unwrap();
+
+        assert_eq!(discarded.records, seeked.records);
+        assert_eq!(discarded_offset, bytes.len() as u64);
+        assert_eq!(reader.offset(), bytes.len() as u64);
+        assert_eq!(reader.stream.position(), bytes.len() as u64);
+    }
+
+    // A rejected record that is truncated is only detected when reading its body.
+    let bytes = fs::read("res/quagga_bgp").unwrap();
+    let truncated = &bytes[..bytes.len() - 1];
+    let mut reader = mrt_rs::Reader::new(truncated);
+    let mut visitor = EveryOther {
+        count: 1,
+        ..Default::default()
+    };
+    assert!(reader.drive(&mut visitor).is_err());
+}
+
+// Tests if bits of a prefix beyond its length are cleared from the address of a route.
+#[test]
+fn test_route_prefix_host_bits() {
+    // RIB records with a single entry without attributes.
+    let entry = [0, 1, 0, 0, 0, 0, 0, 0, 0, 0];
+    let mut bytes = raw_record(13, 1, &[1, 1, 1, 1, 0, 0, 0, 0]);
+    let rib = [&[0, 0, 0, 0, 12, 10, 0xFF][..], &entry].concat();
+    bytes.extend(raw_record(13, 2, &rib));
+    let rib = [&[0, 0, 0, 1, 20, 0x20, 0x01, 0xFF][..], &entry].concat();
+    bytes.extend(raw_record(13, 4, &rib));
+    let rib = [&[0, 0, 0, 2, 0][..], &entry].concat();
+    bytes.extend(raw_record(13, 2, &rib));
+
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    let routes: Vec<_> = reader.routes().map(|x| x.unwrap()).collect();
+    let prefixes: Vec<_> = routes
+        .iter()
+        .map(|x| format!("{}/{}", x.prefix, x.prefix_length))
+        .collect();
+    assert_eq!(
+        prefixes,
+        vec!["10.240.0.0/12", "2001:f000::/20", "0.0.0.0/0"]
+    );
+}
+
+// Tests if routes are tagged with the SAFI given by the subtype of their RIB record.
+#[test]
+fn test_route_safi() {
+    use mrt_rs::SAFI;
+
+    // A PEER_INDEX_TABLE with the IPv4 peer 10.0.0.1 (AS 65001).
+    let mut bytes = raw_record(
+        13,
+        1,
+        &[
+            1, 1, 1, 1, 0, 0, 0, 1, // Collector, view name and peer count
+            2, 10, 0, 0, 1, 10, 0, 0, 1, 0, 0, 0xFD, 0xE9, // Peer 0
+        ],
+    );
+
+    // The same route for 10.0.0.0/8 in the unicast and the multicast RIB.
+    let rib = [
+        0, 0, 0, 0, 8, 10, 0, 1, // Sequence number, prefix and entry count
+        0, 0, 0, 0, 0, 1, 0, 7, 0x40, 5, 4, 0, 0, 0, 100, // Peer 0
+    ];
+    bytes.extend(raw_record(13, 2, &rib));
+    bytes.extend(raw_record(13, 3, &rib));
+
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    let routes: Vec<_> = reader.routes().map(|x| x.unwrap()).collect();
+    assert_eq!(routes.len(), 2);
+    assert_eq!(routes[0].safi, SAFI::UNICAST);
+    assert!(!routes[0].is_multicast());
+    assert_eq!(routes[1].safi, SAFI::MULTICAST);
+    assert!(routes[1].is_multicast());
+
+    // Both routes are kept, as they belong to different RIBs.
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    assert_eq!(reader.routes().dedup_by_prefix().count(), 2);
+}
+
+// Tests if records are counted without parsing them, including records with extended timestamps.
+#[test]
+fn test_count() {
+    for fixture in &["res/bird-mrtdump_bgp", "res/quagga_rib", "res/openbgpd_bgp"] {
+        let expected = mrt_rs::Reader::new(File::open(fixture).unwrap())
+            .read_all()
+            .unwrap()
+            .len();
+        let mut reader = mrt_rs::Reader::new(File::open(fixture).unwrap());
+        assert_eq!(reader.count().unwrap(), expected);
+        assert!(reader.read().unwrap().is_none());
+    }
+
+    // A BGP4MP_ET state change, of which the length includes the extended timestamp, followed by
+    // a BGP4MP state change.
+    let state_change = [0, 1, 0, 2, 0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 0, 6];
+    let mut bytes = raw_record(17, 0, &[&[0, 1, 0xE2, 0x40][..], &state_change].concat());
+    bytes.extend(raw_record(16, 0, &state_change));
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    assert_eq!(reader.count().unwrap(), 2);
+    assert_eq!(reader.offset(), bytes.len() as u64);
+
+    // A truncated record is reported.
+    let mut reader = mrt_rs::Reader::new(&bytes[..bytes.len() - 1]);
+    assert!(reader.count().is_err());
+}
+
+// Tests if the route and route event iterators skip the records they do not handle in a stream
+// mixing BGP4MP and TABLE_DUMP_V2 records, and if RIB routes still resolve their peers.
+#[test]
+fn test_interleaved_record_types() {
+    // A PEER_INDEX_TABLE with the IPv4 peer 10.0.0.1 (AS 65001).
+    let mut bytes = raw_record(
+        13,
+        1,
+        &[
+            1, 1, 1, 1, 0, 0, 0, 1, // Collector, view name and peer count
+            2, 10, 0, 0, 1, 10, 0, 0, 1, 0, 0, 0xFD, 0xE9, // Peer 0
+        ],
+    );
+
+    // A BGP4MP MESSAGE_AS4 from 10.0.0.1 announcing 10.1.0.0/16.
+    let update = [
+        0, 0, 0, 20, // Withdrawn routes and path attribute length
+        0x40, 1, 1, 0, // ORIGIN
+        0x40, 2, 6, 2, 1, 0, 0, 0xFD, 0xE9, // AS_PATH
+        0x40, 3, 4, 10, 0, 0, 1, // NEXT_HOP
+        16, 10, 1, // NLRI
+    ];
+    let mut message = vec![0xFF; 16];
+    message.extend_from_slice(&[0, 19 + update.len() as u8, 2]);
+    message.extend_from_slice(&update);
+    let mut body = vec![0, 0, 0xFD, 0xE9, 0, 0, 0xFD, 0xEA, 0, 0, 0, 1];
+    body.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2]);
+    body.extend_from_slice(&message);
+    bytes.extend(raw_record(16, 4, &body));
+
+    // A route for 10.0.0.0/8 received from peer 0.
+    bytes.extend(raw_record(
+        13,
+        2,
+        &[
+            0, 0, 0, 0, 8, 10, 0, 1, // Sequence number, prefix and entry count
+            0, 0, 0, 0, 0, 1, 0, 7, 0x40, 5, 4, 0, 0, 0, 100, // Peer 0
+        ],
+    ));
+
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    let routes: Vec<_> = reader.routes().map(|x| x.unwrap()).collect();
+    assert_eq!(routes.len(), 1);
+    assert_eq!(routes[0].prefix.to_string(), "10.0.0.0");
+    assert_eq!(routes[0].peer.as_ref().unwrap().peer_as, 65001);
+
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    let events: Vec<_> = reader.route_events().map(|x| x.unwrap()).collect();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].prefix(), ("10.1.0.0".parse().unwrap(), 16));
+    assert_eq!(events[0].peer().peer_as, 65001);
+}
+
+// Tests if the path attributes of a BGP4MP ENTRY are decoded using 2-byte ASNs.
+#[test]
+fn test_bgp4mp_entry_parsed_attributes() {
+    use mrt_rs::attributes::PathAttribute;
+
+    let entry = mrt_rs::bgp4mp::ENTRY {
+        peer_as: 65001,
+        local_as: 65000,
+        interface: 0,
+        peer_address: "192.0.2.1".parse().unwrap(),
+        local_address: "192.0.2.2".parse().unwrap(),
+        view_number: 0,
+        status: 1,
+        time_last_change: 0,
+        next_hop: "192.0.2.1".parse().unwrap(),
+        next_hop_link_local: None,
+        afi: 1,
+        safi: 1,
+        prefix_length: 8,
+        prefix: vec![10],
+        attributes: vec![
+            0x40, 1, 1, 0, // ORIGIN
+            0x40, 2, 6, 2, 2, 0xFD, 0xE9, 0xFD, 0xEA, // AS_PATH with 2-byte ASNs
+        ],
+    };
+
+    let attributes = entry.parsed_attributes().unwrap();
+    assert_eq!(attributes.len(), 2);
+    match &attributes[1] {
+        PathAttribute::AS_PATH(path) => assert_eq!(path.asns(), vec![65001, 65002]),
+        x => panic!("Expected an AS_PATH, got {:?}", x),
+    }
+
+    // The attributes of the openbgpd fixture are decoded as well.
+    let mut reader = mrt_rs::Reader::new(File::open("res/openbgpd_rib_table-mp").unwrap());
+    while let Some((_, record)) = reader.read().unwrap() {
+        if let mrt_rs::Record::BGP4MP(mrt_rs::bgp4mp::BGP4MP::ENTRY(x)) = record {
+            assert!(x.parsed_attributes().is_ok());
+        }
+    }
+}
+
+// Tests if demultiplexing a stream with two peers by peer AS produces one file per peer of which
+// all records share the same peer AS.
+#[test]
+fn test_demux() {
+    use mrt_rs::Record;
+
+    let peer_as = |record: &Record| match record {
+        Record::BGP4MP(x) => x.normalized().unwrap().session_key().peer_as,
+        x => panic!("Unexpected record: {:?}", x),
+    };
+
+    // STATE_CHANGE_AS4 records of the peers AS 65001 and AS 65002, alternating.
+    let mut bytes = Vec::new();
+    for i in 0..6u8 {
+        let body = [
+            &[0, 0, 0xFD, 0xE9 + (i & 1), 0, 0, 0xFD, 0xE8][..], // ASNs
+            &[0, 0, 0, 1],                                       // Interface and AFI
+            &[192, 0, 2, 1 + (i & 1), 192, 0, 2, 100],           // Peer and local address
+            &[0, 1 + i / 2, 0, 2 + i / 2],                       // Old and new state
+        ]
+        .concat();
+        bytes.extend(raw_record(16, 5, &body));
+    }
+
+    let directory = std::env::temp_dir();
+    let path =
+        |peer_as: &u32| directory.join(format!("mrt-rs-demux-{}-{}", std::process::id(), peer_as));
+    let mut reader = mrt_rs::Reader::new(bytes.as_slice());
+    let writers = mrt_rs::demux(
+        &mut reader,
+        |_, record| peer_as(record),
+        |key| File::create(path(key)),
+    )
+    .unwrap();
+    assert_eq!(writers.len(), 2);
+    drop(writers);
+
+    for key in &[65001, 65002] {
+        let records = mrt_rs::Reader::new(File::open(path(key)).unwrap())
+            .read_all()
+            .unwrap();
+        fs::remove_file(path(key)).unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|(_, record)| peer_as(record) == *key));
+    }
+}
+
+// Tests if the addresses of TABLE_DUMP records are returned as the address family given by the
+// subtype of the record.
+#[test]
+fn test_table_dump_typed_addresses() {
+    let mut reader = mrt_rs::Reader::new(File::open("res/openbgpd_rib_table").unwrap());
+    let mut count = 0;
+    while let Some((header, record)) = reader.read().unwrap() {
+        if let mrt_rs::Record::TABLE_DUMP(x) = record {
+            let ipv4 = header.sub_type == 1;
+            assert_eq!(x.prefix_ipv4().is_some(), ipv4);
+            assert_eq!(x.prefix_ipv6().is_some(), !ipv4);
+            assert_eq!(x.peer_address_ipv4().is_some(), ipv4);
+            assert_eq!(x.peer_address_ipv6().is_some(), !ipv4);
+            count += 1;
+        }
+    }
+    assert!(count > 0);
+
+    let entry = mrt_rs::tabledump::TABLE_DUMP {
+        view_number: 0,
+        sequence_number: 0,
+        prefix: "2001:db8::".parse().unwrap(),
+        prefix_length: 32,
+        status: 1,
+        originated_time: 0,
+        peer_address: "2001:db8::1".parse().unwrap(),
+        peer_as: 65001,
+        attributes: Vec::new(),
+    };
+    assert_eq!(entry.prefix_ipv6(), Some("2001:db8::".parse().unwrap()));
+    assert_eq!(
+        entry.peer_address_ipv6(),
+        Some("2001:db8::1".parse().unwrap())
+    );
+    assert_eq!(entry.prefix_ipv4(), None);
+    assert_eq!(entry.peer_address_ipv4(), None);
+}
+
+// Tests if the type and subtype returned by `Record::wire_type` are those from which the record
+// was parsed, for every known type and subtype.
+#[test]
+fn test_wire_type() {
+    let with_afi = |offset: usize| {
+        let mut body = vec![0; 64];
+        body[offset + 1] = 1;
+        body
+    };
+    let entry = [
+        &[0; 6][..],   // ASNs and interface
+        &[0, 1],       // AFI
+        &[0; 16],      // Addresses, view number, status and time of last change
+        &[0, 1, 1, 4], // AFI, SAFI and next hop length
+        &[0; 7],       // Next hop, prefix length and attribute length
+    ]
+    .concat();
+    let rib_generic = [0, 0, 0, 0, 0, 1, 1, 0, 0, 0];
+    let rib_generic_addpath = [0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0];
+    let mut bodies = vec![
+        Vec::new(),
+        vec![0; 64],
+        with_afi(0),
+        with_afi(6),
+        with_afi(10),
+        entry,
+        rib_generic.to_vec(),
+        rib_generic_addpath.to_vec(),
+    ];
+    // The same bodies preceded by an extended timestamp.
+    for i in 0..bodies.len() {
+        bodies.push([&[0; 4][..], &bodies[i]].concat());
+    }
+
+    let known: Vec<(u16, u16)> = (0..=11)
+        .map(|x| (x, 0))
+        .chain((1..=7).flat_map(|x| vec![(5, x), (9, x), (10, x)]))
+        .chain(vec![(12, 1), (12, 2)])
+        .chain((1..=12).filter(|x| *x != 7).map(|x| (13, x)))
+        .chain((0..=11).flat_map(|x| vec![(16, x), (17, x)]))
+        .chain(vec![(32, 0), (33, 0), (48, 0), (49, 0)])
+        .collect();
+
+    for (record_type, sub_type) in known {
+        let parsed: Vec<mrt_rs::Record> = bodies
+            .iter()
+            .filter_map(|body| {
+                mrt_rs::read(&mut raw_record(record_type, sub_type, body).as_slice()).ok()
+            })
+            .map(|x| x.unwrap().1)
+            .collect();
+        assert!(
+            !parsed.is_empty(),
+            "No record of type {} and subtype {} was parsed",
+            record_type,
+            sub_type
+        );
+        for record in parsed {
+            assert_eq!(record.wire_type(), Some((record_type, sub_type)));
+        }
+    }
+
+    // The subtype is normalized to 0 for record types that do not use it.
+    for record_type in &[0, 1, 2, 3, 6, 7, 11, 32] {
+        let bytes = raw_record(*record_type, 5, &[0; 64]);
+        let (_, record) = mrt_rs::read(&mut bytes.as_slice()).unwrap().unwrap();
+        assert_eq!(record.wire_type(), Some((*record_type, 0)));
+    }
+
+    // The type of unknown records is not retained.
+    let bytes = raw_record(100, 0, &[1, 2]);
+    let options = mrt_rs::ParseOptions::default().skip_unknown(true);
+    let mut reader = mrt_rs::Reader::with_options(bytes.as_slice(), options);
+    assert_eq!(reader.read().unwrap().unwrap().1.wire_type(), None);
+}